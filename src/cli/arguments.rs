@@ -0,0 +1,402 @@
+//! # Complex sequences.
+//! src/cli/arguments.rs
+//!
+//! Parse the arguments of the `render` command.
+
+use std::{error, path, slice};
+
+use complex;
+
+use crate::structures::{color, palette};
+use crate::structures::combos::ComboMethod;
+use crate::fractals;
+use crate::expressions;
+
+/// Usage of the `render` command.
+pub const USAGE: &str = "Usage: fractals_detroix23 render [OPTIONS] --out <FILE>
+
+Options:
+  --family <divergence|root>      Fractal family (default: divergence).
+  --method <NAME>                 divergence: mandelbrot, julia;
+                                  root: newton, position, halley, householder, schroder, secant, relaxed.
+  --formula <FORMULA>             divergence: sequence `f(z, c)` (default: \"z^2 + c\");
+                                  root: function `f(z)` (default: \"z^3 + 1\").
+  --constant <REAL,IMAGINARY>     Constant point `c` (default: 0,0).
+  --size <WIDTHxHEIGHT>           Image size in pixels (default: 1024x768).
+  --center <REAL,IMAGINARY>       Point of the complex plane at the center, decimals of any precision (default: 0,0).
+  --zoom <ZOOM>                   Pixels per unit (default: 250).
+  --iterations <COUNT>            Maximum iterations (default: 50).
+  --threshold <THRESHOLD>         Divergence or root threshold (default: 2 or 1).
+  --degree0 <DEGREES>             Hue shift of the root 'position' method (default: 0).
+  --relaxation <REAL,IMAGINARY>   Factor `a` of the root 'relaxed' method (default: 1,0).
+  --color <MODE>                  Color mode: grayscale, hsv, smooth, palette, oklch, histogram (default: grayscale).
+  --palette <FILE>                Gradient of the 'palette' color mode (default: built-in).
+  --interior <MODE>               divergence: color of the bounded points: flat, period, multiplier (default: flat).
+  --trap <SHAPE>                  divergence: color by an orbit trap: point, line, cross, circle (default: none).
+  --trap-center <REAL,IMAGINARY>  Center of the orbit trap (default: 0,0).
+  --trap-radius <RADIUS>          Radius of the 'circle' trap (default: 1).
+  --trap-angle <DEGREES>          Tilt of the 'line' and 'cross' traps (default: 0).
+  --distance <MODE>               divergence: draw the boundary by distance estimation: outline, glow (default: none).
+  --thickness <PIXELS>            Thickness of the boundary (default: 1).
+  --light <DEGREES>               divergence: emboss with a normal map, lit from this angle (default: none).
+  --light-height <HEIGHT>         Height of the light above the plane (default: 1.5).
+  --perturbation <on|off>         divergence: deep zoom of the built-in z^2 + c, escape time only (default: off).
+  --subdivision <on|off>          divergence: fill the rectangles of uniform border, mandelbrot z^n + c from z0 = 0 escape time only,
+                                  the escaped ones with grayscale or hsv, the bounded ones with a flat interior (default: off).
+  --antialiasing <MODE>           Supersampling of each pixel: off, 2x2, 3x3, 4x4, jittered, adaptive (default: off).
+  --adaptive-threshold <COUNT>    Iterations of contrast with a neighbor re-sampling an 'adaptive' pixel (default: 1).
+  --adaptive-samples <COUNT>      Samples of a contrasted 'adaptive' pixel (default: 16).
+  --threads <COUNT>               Worker threads, at most the cores (default: all cores).
+  --out <FILE>                    Output image, format from extension (e.g. `julia.png`).
+  -h, --help                      Print this help.";
+
+/// # `Target` fractal of a render: family and its method.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+	Divergence(fractals::divergence::LimitMethod),
+	Root(fractals::root::RootMethod),
+}
+
+/// # `RenderArguments`, parsed from the command-line.
+pub struct RenderArguments {
+	pub target: Target,
+	/// Function of the divergence sequence, or of the roots. `None` for the defaults.
+	pub formula: Option<expressions::Expression>,
+	/// Constant fixed point. E.g: `c` in `f(z) = z * z + c`.
+	pub constant: complex::Algebraic,
+	/// Size: [width, height].
+	pub size: [usize; 2],
+	/// Camera position, as used by `geometry::position_from_pixel` (opposite of the center).
+	pub position: [complex::Real; 2],
+	pub zoom: complex::Real,
+	/// Center at arbitrary precision, and zoom.
+	pub viewport: fractals::geometry::Viewport,
+	pub iterations: usize,
+	pub threshold: complex::Real,
+	pub degree0: f64,
+	/// Factor `a` of the relaxed Newton's method.
+	pub relaxation: complex::Algebraic,
+	pub color_mode: color::ColorMode,
+	pub palette: palette::Palette,
+	/// Color of the bounded points of the divergence fractals.
+	pub interior_mode: color::InteriorMode,
+	/// What is kept of the orbits of the divergence fractals.
+	pub recording: fractals::divergence::Recording,
+	/// Orbit trap of the `Trap` recording.
+	pub trap: fractals::divergence::trap::OrbitTrap,
+	/// Boundary drawn by the `Distance` recording.
+	pub boundary: color::Boundary,
+	/// Light of the normal map of the divergence fractals.
+	pub light: color::Light,
+	/// Deep zoom of the built-in divergence sequence, by perturbation.
+	pub perturbation: bool,
+	/// Mariani–Silver rectangle subdivision of the Mandelbrot escape time.
+	pub subdivision: bool,
+	/// Sub-pixel samples of each pixel, averaged.
+	pub antialiasing: fractals::antialiasing::Antialiasing,
+	/// Contrast and samples of the `Adaptive` antialiasing.
+	pub adaptive: fractals::antialiasing::Adaptive,
+	pub thread_count: usize,
+	pub output: path::PathBuf,
+}
+
+impl RenderArguments {
+	/// Parse `arguments`, without the program name nor the `render` command.
+	pub fn parse(arguments: &[String]) -> Result<RenderArguments, Box<dyn error::Error>> {
+		let mut family: String = String::from("divergence");
+		let mut method: Option<String> = Option::None;
+		let mut formula: Option<expressions::Expression> = Option::None;
+		let mut constant: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+		let mut size: [usize; 2] = [1024, 768];
+		let mut center: String = String::from("0,0");
+		let mut zoom: complex::Real = 250.0;
+		let mut iterations: usize = 50;
+		let mut threshold: Option<complex::Real> = Option::None;
+		let mut degree0: f64 = 0.0;
+		let mut relaxation: complex::Algebraic = complex::Algebraic::new(1.0, 0.0);
+		let mut color_mode: color::ColorMode = color::ColorMode::GRAYSCALE;
+		let mut palette: palette::Palette = palette::Palette::default();
+		let mut interior_mode: color::InteriorMode = color::InteriorMode::Flat;
+		let mut recording: fractals::divergence::Recording = fractals::divergence::Recording::Escape;
+		let mut trap: fractals::divergence::trap::OrbitTrap = Default::default();
+		let mut boundary: color::Boundary = Default::default();
+		let mut light: color::Light = Default::default();
+		let mut perturbation: bool = false;
+		let mut subdivision: bool = false;
+		let mut antialiasing: fractals::antialiasing::Antialiasing = fractals::antialiasing::Antialiasing::Off;
+		let mut adaptive: fractals::antialiasing::Adaptive = Default::default();
+		let mut thread_count: usize = fractals::threading::determine_threads().into();
+		let mut output: Option<path::PathBuf> = Option::None;
+
+		let mut iterator: slice::Iter<'_, String> = arguments.iter();
+		while let Option::Some(key) = iterator.next() {
+			let value: &str = next_value(&mut iterator, key)?;
+
+			match key.as_str() {
+				"--family" => family = value.to_lowercase(),
+				"--method" => method = Option::Some(value.to_lowercase()),
+				"--formula" => formula = Option::Some(expressions::parse(value)?),
+				"--constant" => {
+					let pair: [complex::Real; 2] = parse_pair(value)?;
+					constant = complex::Algebraic::new(pair[0], pair[1]);
+				},
+				"--size" => size = parse_size(value)?,
+				"--center" => center = String::from(value),
+				"--zoom" => zoom = parse_number(key, value)?,
+				"--iterations" => iterations = parse_number(key, value)?,
+				"--threshold" => threshold = Option::Some(parse_number(key, value)?),
+				"--degree0" => degree0 = parse_number(key, value)?,
+				"--relaxation" => {
+					let pair: [complex::Real; 2] = parse_pair(value)?;
+					relaxation = complex::Algebraic::new(pair[0], pair[1]);
+				},
+				"--color" => color_mode = match color::ColorMode::list()
+					.into_iter()
+					.find(|mode| mode.to_name() == value.to_lowercase())
+				{
+					Option::Some(mode) => mode,
+					Option::None => return Result::Err(format!("(X) Unknown color mode `{}`.", value).into()),
+				},
+				"--palette" => palette = palette::Palette::load(path::Path::new(value))?,
+				"--interior" => interior_mode = match color::InteriorMode::list()
+					.into_iter()
+					.find(|mode| mode.to_name() == value.to_lowercase())
+				{
+					Option::Some(mode) => mode,
+					Option::None => return Result::Err(format!("(X) Unknown interior mode `{}`.", value).into()),
+				},
+				"--trap" => {
+					trap.shape = match fractals::divergence::trap::TrapShape::list()
+						.into_iter()
+						.find(|shape| shape.to_name() == value.to_lowercase())
+					{
+						Option::Some(shape) => shape,
+						Option::None => return Result::Err(format!("(X) Unknown orbit trap `{}`.", value).into()),
+					};
+					recording = select_recording(recording, fractals::divergence::Recording::Trap)?;
+				},
+				"--trap-center" => {
+					let pair: [complex::Real; 2] = parse_pair(value)?;
+					trap.center = complex::Algebraic::new(pair[0], pair[1]);
+				},
+				"--trap-radius" => trap.radius = parse_number(key, value)?,
+				"--trap-angle" => trap.angle = parse_number(key, value)?,
+				"--distance" => {
+					boundary.mode = match color::BoundaryMode::list()
+						.into_iter()
+						.find(|mode| mode.to_name() == value.to_lowercase())
+					{
+						Option::Some(mode) => mode,
+						Option::None => return Result::Err(format!("(X) Unknown boundary mode `{}`.", value).into()),
+					};
+					recording = select_recording(recording, fractals::divergence::Recording::Distance)?;
+				},
+				"--thickness" => boundary.thickness = parse_number(key, value)?,
+				"--light" => {
+					light.angle = parse_number(key, value)?;
+					light.enabled = true;
+				},
+				"--light-height" => light.height = parse_number(key, value)?,
+				"--perturbation" => perturbation = match value.to_lowercase().as_str() {
+					"on" => true,
+					"off" => false,
+					_ => return Result::Err(format!("(X) Option `{}`: expects `on` or `off`, not `{}`.", key, value).into()),
+				},
+				"--subdivision" => subdivision = match value.to_lowercase().as_str() {
+					"on" => true,
+					"off" => false,
+					_ => return Result::Err(format!("(X) Option `{}`: expects `on` or `off`, not `{}`.", key, value).into()),
+				},
+				"--antialiasing" => antialiasing = match fractals::antialiasing::Antialiasing::list()
+					.into_iter()
+					.find(|antialiasing| antialiasing.to_name() == value.to_lowercase())
+				{
+					Option::Some(antialiasing) => antialiasing,
+					Option::None => return Result::Err(format!("(X) Unknown antialiasing `{}`.", value).into()),
+				},
+				"--adaptive-threshold" => adaptive.threshold = parse_number(key, value)?,
+				"--adaptive-samples" => adaptive.samples = parse_number(key, value)?,
+				"--threads" => thread_count = parse_number(key, value)?,
+				"--out" => output = Option::Some(path::PathBuf::from(value)),
+				_ => return Result::Err(format!("(X) Unknown option `{}`.\n\n{}", key, USAGE).into()),
+			}
+		}
+
+		let target: Target = match (family.as_str(), method.as_deref()) {
+			("divergence", Option::None | Option::Some("mandelbrot")) =>
+				Target::Divergence(fractals::divergence::LimitMethod::Mandelbrot),
+			("divergence", Option::Some("julia")) =>
+				Target::Divergence(fractals::divergence::LimitMethod::Julia),
+			("root", Option::None) =>
+				Target::Root(fractals::root::RootMethod::Newton),
+			("root", Option::Some(name)) => match fractals::root::RootMethod::list()
+				.into_iter()
+				.find(|method| method.to_name() == name)
+			{
+				Option::Some(method) => Target::Root(method),
+				Option::None =>
+					return Result::Err(format!("(X) Unknown method `{}` for family `root`.", name).into()),
+			},
+			(family, Option::None) =>
+				return Result::Err(format!("(X) Unknown family `{}`.", family).into()),
+			(family, Option::Some(method)) =>
+				return Result::Err(format!("(X) Unknown method `{}` for family `{}`.", method, family).into()),
+		};
+
+		if size[0] == 0 || size[1] == 0 {
+			return Result::Err(format!("(X) `--size` ({}x{}) must not be empty.", size[0], size[1]).into());
+		}
+		if thread_count == 0 {
+			return Result::Err("(X) `--threads` must be at least 1.".into());
+		}
+		// The workers of the pool, one per core: no more can run at once.
+		let thread_max: usize = fractals::threading::determine_threads().into();
+		if thread_count > thread_max {
+			return Result::Err(format!("(X) `--threads` ({}) must be at most the {} cores.", thread_count, thread_max).into());
+		}
+		let viewport: fractals::geometry::Viewport = fractals::geometry::Viewport::parse(&center, zoom)?;
+		if perturbation {
+			if !matches!(target, Target::Divergence(_)) {
+				return Result::Err("(X) `--perturbation` only applies to the divergence family.".into());
+			}
+			if formula.is_some() {
+				return Result::Err("(X) `--perturbation` only applies to the built-in z^2 + c, not `--formula`.".into());
+			}
+			if recording != fractals::divergence::Recording::Escape || light.enabled {
+				return Result::Err("(X) `--perturbation` only records the escape: no `--trap`, `--distance` nor `--light`.".into());
+			}
+		}
+		if subdivision {
+			if !matches!(target, Target::Divergence(fractals::divergence::LimitMethod::Mandelbrot)) {
+				return Result::Err("(X) `--subdivision` only applies to the mandelbrot method of the divergence family.".into());
+			}
+			if constant != complex::Algebraic::new(0.0, 0.0) {
+				return Result::Err("(X) `--subdivision` only applies from z0 = 0, no `--constant`.".into());
+			}
+			if perturbation {
+				return Result::Err("(X) `--subdivision` doesn't apply with `--perturbation`.".into());
+			}
+			if formula.as_ref().is_some_and(|formula: &expressions::Expression| formula.power_of_z_plus_c().is_none()) {
+				return Result::Err("(X) `--subdivision` only applies to z^n + c as `--formula`.".into());
+			}
+			if recording != fractals::divergence::Recording::Escape || light.enabled {
+				return Result::Err("(X) `--subdivision` only records the escape: no `--trap`, `--distance` nor `--light`.".into());
+			}
+		}
+
+		Result::Ok(RenderArguments {
+			target,
+			formula,
+			constant,
+			size,
+			position: viewport.get_position(),
+			zoom,
+			viewport,
+			iterations,
+			threshold: threshold.unwrap_or(match target {
+				Target::Divergence(_) => 2.0,
+				Target::Root(_) => 1.0,
+			}),
+			degree0,
+			relaxation,
+			color_mode,
+			palette,
+			interior_mode,
+			recording,
+			trap,
+			boundary,
+			light,
+			perturbation,
+			subdivision,
+			antialiasing,
+			adaptive,
+			thread_count,
+			output: output.ok_or("(X) Missing `--out <FILE>`.")?,
+		})
+	}
+}
+
+/// Switch from the `current` recording to `selected`: only one of them can be chosen.
+fn select_recording(
+	current: fractals::divergence::Recording,
+	selected: fractals::divergence::Recording,
+) -> Result<fractals::divergence::Recording, Box<dyn error::Error>> {
+	match current {
+		fractals::divergence::Recording::Escape => Result::Ok(selected),
+		current if current == selected => Result::Ok(selected),
+		_ => Result::Err("(X) `--trap` and `--distance` can't be used together.".into()),
+	}
+}
+
+/// Take the value following the option `key`.
+fn next_value<'a>(
+	iterator: &mut slice::Iter<'a, String>,
+	key: &str,
+) -> Result<&'a str, Box<dyn error::Error>> {
+	match iterator.next() {
+		Option::Some(value) => Result::Ok(value.as_str()),
+		Option::None => Result::Err(format!("(X) Option `{}` expects a value.", key).into()),
+	}
+}
+
+/// Parse a single number `value` of the option `key`.
+fn parse_number<T>(key: &str, value: &str) -> Result<T, Box<dyn error::Error>>
+where
+	T: std::str::FromStr,
+{
+	value
+		.trim()
+		.parse::<T>()
+		.map_err(|_| format!("(X) Option `{}`: can't parse `{}`.", key, value).into())
+}
+
+/// Parse a pair of reals `"x,y"`.
+pub fn parse_pair(text: &str) -> Result<[complex::Real; 2], Box<dyn error::Error>> {
+	match text.split_once(',') {
+		Option::Some((x, y)) => Result::Ok([
+			parse_number("pair", x)?,
+			parse_number("pair", y)?,
+		]),
+		Option::None => Result::Err(format!("(X) Expected `x,y`, got `{}`.", text).into()),
+	}
+}
+
+/// Parse a size `"WIDTHxHEIGHT"`.
+pub fn parse_size(text: &str) -> Result<[usize; 2], Box<dyn error::Error>> {
+	match text.to_lowercase().split_once('x') {
+		Option::Some((width, height)) => Result::Ok([
+			parse_number("size", width)?,
+			parse_number("size", height)?,
+		]),
+		Option::None => Result::Err(format!("(X) Expected `WIDTHxHEIGHT`, got `{}`.", text).into()),
+	}
+}
+
+
+#[test]
+fn test_parse_render_arguments() -> () {
+	let arguments: Vec<String> = "--family divergence --method julia --constant -0.8,0.156 --size 3840x2160 --zoom 400 --out julia.png"
+		.split(' ')
+		.map(String::from)
+		.collect();
+	let parsed: RenderArguments = RenderArguments::parse(&arguments).unwrap();
+
+	assert!(parsed.target == Target::Divergence(fractals::divergence::LimitMethod::Julia));
+	assert_eq!(parsed.constant, complex::Algebraic::new(-0.8, 0.156));
+	assert_eq!(parsed.size, [3840, 2160]);
+	assert_eq!(parsed.zoom, 400.0);
+	assert_eq!(parsed.output, path::PathBuf::from("julia.png"));
+	assert!(parsed.recording == fractals::divergence::Recording::Escape);
+
+	let arguments: Vec<String> = "--family root --method relaxed --relaxation 1.5,0.25 --out newton.png"
+		.split(' ')
+		.map(String::from)
+		.collect();
+	let parsed: RenderArguments = RenderArguments::parse(&arguments).unwrap();
+	assert!(parsed.target == Target::Root(fractals::root::RootMethod::RelaxedNewton));
+	assert_eq!(parsed.relaxation, complex::Algebraic::new(1.5, 0.25));
+
+	assert!(RenderArguments::parse(&[String::from("--size")]).is_err());
+	assert!(RenderArguments::parse(&[String::from("--threads"), usize::MAX.to_string()]).is_err());
+	assert!(parse_size("3840-2160").is_err());
+}
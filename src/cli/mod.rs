@@ -0,0 +1,7 @@
+//! # Complex sequences.
+//! src/cli/mod.rs
+//! 
+//! Command-line modes, running without any window nor GL context.
+
+pub mod arguments;
+pub mod render;
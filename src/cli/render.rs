@@ -0,0 +1,357 @@
+//! # Complex sequences.
+//! src/cli/render.rs
+//!
+//! `render` command: compute a fractal and write it to an image file, headless.
+
+use std::{error, time};
+
+use complex;
+
+use crate::cli::arguments;
+use crate::structures::computations;
+use crate::fractals;
+use crate::expressions;
+use crate::support;
+
+/// Run the `render` command with its `arguments` (without the program name nor `render`).
+pub fn run(arguments: &[String]) -> Result<(), Box<dyn error::Error>> {
+	if arguments.iter().any(|argument| argument == "-h" || argument == "--help") {
+		println!("{}", arguments::USAGE);
+		return Result::Ok(());
+	}
+
+	let render_arguments: arguments::RenderArguments = arguments::RenderArguments::parse(arguments)?;
+
+	let generation_start: time::Instant = time::Instant::now();
+	let data: computations::Data = render(&render_arguments)?;
+	let generation_time: time::Duration = generation_start.elapsed();
+
+	support::export::save_rgb(&render_arguments.output, &data.raw_pixels, render_arguments.size)?;
+
+	println!(
+		"* Render: out={} size=({}; {}) t={:?} iterations={} threads={}",
+		render_arguments.output.display(),
+		render_arguments.size[0],
+		render_arguments.size[1],
+		generation_time,
+		data.iterations_total,
+		render_arguments.thread_count,
+	);
+
+	Result::Ok(())
+}
+
+/// Compute the fractal described by `render_arguments` into raw RGB `Data`, supersampled.
+/// 
+/// Fails if the root function can't be derived.
+pub fn render(render_arguments: &arguments::RenderArguments) -> Result<computations::Data, Box<dyn error::Error>> {
+	// Headless renders are never cancelled.
+	let control: fractals::threading::Control = fractals::threading::Control::new();
+
+	Result::Ok(match render_arguments.target {
+		arguments::Target::Divergence(method) => {
+			let perturbation: Option<fractals::divergence::perturbation::Perturbation> = match render_arguments.perturbation {
+				true => Option::Some(perturbation(render_arguments, method)),
+				false => Option::None,
+			};
+			if let Option::Some(perturbation) = &perturbation {
+				println!("* Perturbation: {}", perturbation.get_approximation());
+			}
+
+			let limit = |
+				sample: fractals::antialiasing::Sample, 
+				refinement: &fractals::progressive::Refinement<computations::State>,
+			| match &perturbation {
+				Option::Some(perturbation) => perturbation.limit_on_screen(
+					render_arguments.size, 
+					render_arguments.thread_count, 
+					refinement, 
+					sample, 
+					&control,
+				),
+				Option::None => limit_formula(render_arguments, method, refinement, sample, &control),
+			};
+			let color = |table: Vec<Vec<computations::State>>| fractals::tables::state_table_to_data(
+				table,
+				support::defaults::COLOR_STABLE,
+				support::defaults::COLOR_DIVERGENT,
+				render_arguments.iterations,
+				smoothing_degree(render_arguments),
+				render_arguments.color_mode,
+				&render_arguments.palette,
+				render_arguments.interior_mode,
+				render_arguments.light,
+				render_arguments.recording.boundary(&render_arguments.boundary),
+				1.0 / render_arguments.zoom,
+			);
+
+			match render_arguments.antialiasing {
+				fractals::antialiasing::Antialiasing::Adaptive => {
+					let base: fractals::threading::Tiled<computations::State> = limit(
+						fractals::antialiasing::Sample::center(), 
+						&fractals::progressive::Refinement::full(),
+					).ok_or("(X) Render cancelled.")?;
+					let mut resampling: fractals::antialiasing::Resampling<computations::State>;
+					resampling = fractals::antialiasing::Resampling::new(render_arguments.adaptive, base.grid);
+					for sample in resampling.samples() {
+						let grid: Vec<Vec<computations::State>> = limit(sample, &resampling.refinement())
+							.ok_or("(X) Render cancelled.")?
+							.grid;
+						resampling.push(grid);
+					}
+
+					resampling.average(render_arguments.iterations, color)
+				},
+				antialiasing => fractals::antialiasing::supersample(
+					antialiasing,
+					&control,
+					|sample: fractals::antialiasing::Sample| {
+						Option::Some(color(limit(sample, &fractals::progressive::Refinement::full())?.grid))
+					},
+				).ok_or("(X) Render cancelled.")?,
+			}
+		},
+		arguments::Target::Root(method) => {
+			let function: expressions::Holomorphic = match &render_arguments.formula {
+				Option::Some(expression) => expressions::Holomorphic::new(expression.clone())?,
+				Option::None => expressions::Holomorphic::parse(support::defaults::ROOT_FORMULA)?,
+			};
+			let mut root_finder: fractals::root::maths::RootFinder = fractals::root::maths::RootFinder::new(
+				function,
+				render_arguments.threshold,
+				render_arguments.iterations,
+				render_arguments.size,
+				render_arguments.position,
+				render_arguments.zoom,
+				render_arguments.relaxation,
+			);
+
+			match (method, render_arguments.antialiasing) {
+				// Positions have no iterations to compare: `Adaptive` is a single sample.
+				(fractals::root::RootMethod::Position, antialiasing) => fractals::antialiasing::supersample(
+					antialiasing,
+					&control,
+					|sample: fractals::antialiasing::Sample| {
+						let table: Vec<Vec<complex::Polar>> = root_finder
+							.limit_on_screen_position(render_arguments.thread_count, sample, &control)?
+							.grid;
+						let mut position_converter: fractals::tables::PositionConverter;
+						position_converter = fractals::tables::PositionConverter::new(render_arguments.degree0);
+
+						Option::Some(position_converter.convert(table))
+					},
+				).ok_or("(X) Render cancelled.")?,
+				(method, fractals::antialiasing::Antialiasing::Adaptive) => {
+					let base: Vec<Vec<computations::IsRoot>> = root_finder
+						.limit_on_screen_root(
+							method, 
+							render_arguments.thread_count, 
+							&fractals::progressive::Refinement::full(), 
+							fractals::antialiasing::Sample::center(), 
+							&control,
+						)
+						.ok_or("(X) Render cancelled.")?
+						.grid;
+					let mut resampling: fractals::antialiasing::Resampling<computations::IsRoot>;
+					resampling = fractals::antialiasing::Resampling::new(render_arguments.adaptive, base);
+					for sample in resampling.samples() {
+						let grid: Vec<Vec<computations::IsRoot>> = root_finder
+							.limit_on_screen_root(method, render_arguments.thread_count, &resampling.refinement(), sample, &control)
+							.ok_or("(X) Render cancelled.")?
+							.grid;
+						resampling.push(grid);
+					}
+
+					// The colors depend on all the roots: the samples are colored once all are found.
+					resampling.average(
+						render_arguments.iterations, 
+						|table: Vec<Vec<computations::IsRoot>>| root_table_to_data(render_arguments, &root_finder, table),
+					)
+				},
+				(method, antialiasing) => {
+					// The colors depend on all the roots: every sample is found before any is colored.
+					let mut tables: Vec<Vec<Vec<computations::IsRoot>>> = Vec::new();
+					for sample in antialiasing.samples() {
+						tables.push(root_finder
+							.limit_on_screen_root(method, render_arguments.thread_count, &fractals::progressive::Refinement::full(), sample, &control)
+							.ok_or("(X) Render cancelled.")?
+							.grid);
+					}
+
+					let mut tables = tables.into_iter();
+					fractals::antialiasing::supersample(
+						antialiasing,
+						&control,
+						|_: fractals::antialiasing::Sample| {
+							Option::Some(root_table_to_data(render_arguments, &root_finder, tables.next()?))
+						},
+					).ok_or("(X) Render cancelled.")?
+				},
+			}
+		},
+	})
+}
+
+/// Color a root `table`, with the roots of `root_finder`.
+fn root_table_to_data(
+	render_arguments: &arguments::RenderArguments,
+	root_finder: &fractals::root::maths::RootFinder,
+	table: Vec<Vec<computations::IsRoot>>,
+) -> computations::Data {
+	// Converters accumulate their data: one per table.
+	let mut newton_converter: fractals::tables::NewtonConverter;
+	newton_converter = fractals::tables::NewtonConverter::new(
+		root_finder.get_roots(),
+		root_finder.get_threshold(),
+		support::defaults::COLOR_NO_ROOT,
+		render_arguments.iterations,
+		render_arguments.color_mode,
+		render_arguments.palette.clone(),
+	);
+
+	newton_converter.convert(table)
+}
+
+/// Reference orbit of z² + c with `method` around the center at high precision, and its series approximation.
+fn perturbation(
+	render_arguments: &arguments::RenderArguments,
+	method: fractals::divergence::LimitMethod,
+) -> fractals::divergence::perturbation::Perturbation {
+	fractals::divergence::perturbation::Perturbation::new(
+		method,
+		render_arguments.constant,
+		render_arguments.color_mode.bailout(render_arguments.threshold),
+		render_arguments.iterations,
+		render_arguments.size,
+		&render_arguments.viewport.center,
+		render_arguments.zoom,
+		true,
+	)
+}
+
+/// Degree of the divergence sequence at infinity, of which the escape time is smoothed:
+/// of the polynomial formula, or else of the built-in one. 2 if unknown.
+fn smoothing_degree(render_arguments: &arguments::RenderArguments) -> f64 {
+	let degree: Option<usize> = match &render_arguments.formula {
+		Option::Some(expression) => expression.degree(),
+		Option::None => Option::Some(support::defaults::POLYNOMIAL2_C_DEGREE as usize),
+	};
+
+	match degree {
+		Option::Some(degree) if degree >= 2 => degree as f64,
+		_ => 2.0,
+	}
+}
+
+/// Compute the divergence table of the formula with `method`, on the `refinement` pass at a `sample` of the pixels.
+/// 
+/// The escape time of z^n + c goes through the batched kernel, or the rectangle subdivision,
+/// the other formulas through `limit_table`.
+fn limit_formula(
+	render_arguments: &arguments::RenderArguments,
+	method: fractals::divergence::LimitMethod,
+	refinement: &fractals::progressive::Refinement<computations::State>,
+	sample: fractals::antialiasing::Sample,
+	control: &fractals::threading::Control,
+) -> Option<fractals::threading::Tiled<computations::State>> {
+	let recording: fractals::divergence::Recording = render_arguments.recording.lit(&render_arguments.light);
+	let degree: Option<u32> = match (recording, &render_arguments.formula) {
+		(fractals::divergence::Recording::Escape, Option::None) => Option::Some(support::defaults::POLYNOMIAL2_C_DEGREE),
+		(fractals::divergence::Recording::Escape, Option::Some(expression)) => expression.power_of_z_plus_c(),
+		_ => Option::None,
+	};
+
+	match (degree, &render_arguments.formula) {
+		(Option::Some(degree), _) if render_arguments.subdivision => fractals::divergence::subdivision::limit_on_screen_subdivided(
+			degree,
+			recording.bailout(render_arguments.color_mode.bailout(render_arguments.threshold)),
+			render_arguments.iterations,
+			render_arguments.size,
+			render_arguments.position,
+			render_arguments.zoom,
+			render_arguments.thread_count,
+			refinement,
+			sample,
+			fractals::divergence::subdivision::Filling::new(render_arguments.color_mode, render_arguments.interior_mode),
+			control,
+		),
+		(Option::Some(degree), _) => fractals::divergence::maths::limit_on_screen_power(
+			method,
+			render_arguments.constant,
+			degree,
+			recording.bailout(render_arguments.color_mode.bailout(render_arguments.threshold)),
+			render_arguments.iterations,
+			render_arguments.size,
+			render_arguments.position,
+			render_arguments.zoom,
+			render_arguments.thread_count,
+			refinement,
+			sample,
+			control,
+		),
+		(Option::None, Option::Some(expression)) => {
+			let expression: expressions::Expression = expression.clone();
+			let derivative: fractals::divergence::maths::Derivative = fractals::divergence::maths::Derivative::new(&expression);
+			limit_table(render_arguments, method, move |z, c| expression.evaluate(z, c), derivative, refinement, sample, control)
+		},
+		(Option::None, Option::None) => 
+			limit_table(
+				render_arguments, 
+				method, 
+				support::defaults::polynomial2_c, 
+				fractals::divergence::maths::Derivative::Power(support::defaults::POLYNOMIAL2_C_DEGREE),
+				refinement, 
+				sample, 
+				control,
+			),
+	}
+}
+
+/// Compute the divergence table of `function` with `method`, on the `refinement` pass at a `sample` of the pixels.
+fn limit_table<F>(
+	render_arguments: &arguments::RenderArguments,
+	method: fractals::divergence::LimitMethod,
+	function: F,
+	derivative: fractals::divergence::maths::Derivative,
+	refinement: &fractals::progressive::Refinement<computations::State>,
+	sample: fractals::antialiasing::Sample,
+	control: &fractals::threading::Control,
+) -> Option<fractals::threading::Tiled<computations::State>>
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
+{
+	match method {
+		fractals::divergence::LimitMethod::Julia => fractals::divergence::maths::limit_on_screen_julia(
+			render_arguments.constant,
+			function,
+			derivative,
+			render_arguments.recording.lit(&render_arguments.light).bailout(render_arguments.color_mode.bailout(render_arguments.threshold)),
+			render_arguments.iterations,
+			render_arguments.recording.lit(&render_arguments.light),
+			render_arguments.trap,
+			render_arguments.size,
+			render_arguments.position,
+			render_arguments.zoom,
+			render_arguments.thread_count,
+			refinement,
+			sample,
+			control,
+		),
+		fractals::divergence::LimitMethod::Mandelbrot => fractals::divergence::maths::limit_on_screen_mandelbrot(
+			render_arguments.constant,
+			function,
+			derivative,
+			render_arguments.recording.lit(&render_arguments.light).bailout(render_arguments.color_mode.bailout(render_arguments.threshold)),
+			render_arguments.iterations,
+			render_arguments.recording.lit(&render_arguments.light),
+			render_arguments.trap,
+			render_arguments.size,
+			render_arguments.position,
+			render_arguments.zoom,
+			render_arguments.thread_count,
+			refinement,
+			sample,
+			control,
+		),
+	}
+}
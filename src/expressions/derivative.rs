@@ -0,0 +1,279 @@
+//! # Complex sequences.
+//! src/expressions/derivative.rs
+//!
+//! Symbolic differentiation of an `Expression` with respect to `z`.
+//!
+//! Trees are built with simplifying constructors (`0 + u = u`, `1 * u = u`,
+//! constant folding, ...) so derivatives stay small and fast to evaluate.
+
+use std::{error, fmt};
+
+use complex;
+
+use crate::expressions::tree::{Expression, Function, Variable};
+
+/// # `DerivativeError`: the `function` has no complex derivative.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivativeError {
+	pub function: Function,
+}
+
+impl fmt::Display for DerivativeError {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			formatter,
+			"(!) `{}` is not holomorphic: it has no complex derivative.",
+			self.function.to_name(),
+		)
+	}
+}
+
+impl error::Error for DerivativeError {}
+
+impl Expression {
+	/// Returns `true` if the expression contains the variable `z`.
+	pub fn depends_on_z(self: &Self) -> bool {
+		match self {
+			Expression::Constant(_) => false,
+			Expression::Variable(variable) => *variable == Variable::Z,
+			Expression::Negate(operand) | Expression::Call(_, operand) => operand.depends_on_z(),
+			Expression::Add(left, right)
+			| Expression::Subtract(left, right)
+			| Expression::Multiply(left, right)
+			| Expression::Divide(left, right)
+			| Expression::Power(left, right) => left.depends_on_z() || right.depends_on_z(),
+		}
+	}
+
+	/// # Derivative `f'` of the expression `f`, with respect to `z`.
+	/// `c` is a constant.
+	///
+	/// Fails if `z` goes through a function without complex derivative (`conj`, `abs`).
+	pub fn derivative(self: &Self) -> Result<Expression, DerivativeError> {
+		if !self.depends_on_z() {
+			return Result::Ok(constant(0.0));
+		}
+
+		Result::Ok(match self {
+			Expression::Constant(_) | Expression::Variable(Variable::C) => constant(0.0),
+			Expression::Variable(Variable::Z) => constant(1.0),
+			Expression::Negate(operand) => negate(operand.derivative()?),
+			Expression::Add(left, right) => add(left.derivative()?, right.derivative()?),
+			Expression::Subtract(left, right) => subtract(left.derivative()?, right.derivative()?),
+			// (uv)' = u'v + uv'
+			Expression::Multiply(left, right) => add(
+				multiply(left.derivative()?, *right.clone()),
+				multiply(*left.clone(), right.derivative()?),
+			),
+			// (u/v)' = (u'v - uv') / v²
+			Expression::Divide(left, right) => divide(
+				subtract(
+					multiply(left.derivative()?, *right.clone()),
+					multiply(*left.clone(), right.derivative()?),
+				),
+				power(*right.clone(), constant(2.0)),
+			),
+			Expression::Power(base, exponent) if !exponent.depends_on_z() => {
+				// (u^n)' = n u^(n - 1) u'
+				multiply(
+					multiply(
+						*exponent.clone(),
+						power(*base.clone(), subtract(*exponent.clone(), constant(1.0))),
+					),
+					base.derivative()?,
+				)
+			},
+			Expression::Power(base, exponent) => {
+				// (u^v)' = u^v (v' log(u) + v u' / u)
+				multiply(
+					self.clone(),
+					add(
+						multiply(exponent.derivative()?, call(Function::Log, *base.clone())),
+						divide(multiply(*exponent.clone(), base.derivative()?), *base.clone()),
+					),
+				)
+			},
+			Expression::Call(function, argument) => {
+				let inner: Expression = argument.derivative()?;
+				let outer: Expression = match function {
+					Function::Exp => call(Function::Exp, *argument.clone()),
+					Function::Sin => call(Function::Cos, *argument.clone()),
+					Function::Cos => negate(call(Function::Sin, *argument.clone())),
+					Function::Log => divide(constant(1.0), *argument.clone()),
+					Function::Conj | Function::Abs =>
+						return Result::Err(DerivativeError { function: *function }),
+				};
+
+				multiply(outer, inner)
+			},
+		})
+	}
+
+	/// # Derivative `∂f/∂c` of the expression `f`, with respect to `c`.
+	/// `z` is a constant: derive with respect to `z` the expression of `z` and `c` swapped, then swap them back.
+	pub fn derivative_c(self: &Self) -> Result<Expression, DerivativeError> {
+		Result::Ok(self.swapped().derivative()?.swapped())
+	}
+
+	/// The expression with the variables `z` and `c` exchanged.
+	fn swapped(self: &Self) -> Expression {
+		let swap = |operand: &Expression| Box::new(operand.swapped());
+
+		match self {
+			Expression::Constant(_) => self.clone(),
+			Expression::Variable(Variable::Z) => Expression::Variable(Variable::C),
+			Expression::Variable(Variable::C) => Expression::Variable(Variable::Z),
+			Expression::Negate(operand) => Expression::Negate(swap(operand)),
+			Expression::Call(function, operand) => Expression::Call(*function, swap(operand)),
+			Expression::Add(left, right) => Expression::Add(swap(left), swap(right)),
+			Expression::Subtract(left, right) => Expression::Subtract(swap(left), swap(right)),
+			Expression::Multiply(left, right) => Expression::Multiply(swap(left), swap(right)),
+			Expression::Divide(left, right) => Expression::Divide(swap(left), swap(right)),
+			Expression::Power(left, right) => Expression::Power(swap(left), swap(right)),
+		}
+	}
+}
+
+/// Real constant `value`.
+fn constant(value: complex::Real) -> Expression {
+	Expression::Constant(complex::Algebraic::new(value, 0.0))
+}
+
+/// The value of a constant expression.
+fn as_constant(expression: &Expression) -> Option<complex::Algebraic> {
+	match expression {
+		Expression::Constant(value) => Option::Some(*value),
+		_ => Option::None,
+	}
+}
+
+/// Returns `true` if `expression` is the real constant `value`.
+fn is_constant(expression: &Expression, value: complex::Real) -> bool {
+	match as_constant(expression) {
+		Option::Some(constant) => constant.real == value && constant.imaginary == 0.0,
+		Option::None => false,
+	}
+}
+
+/// `-u`, simplified.
+fn negate(operand: Expression) -> Expression {
+	match operand {
+		Expression::Constant(value) => Expression::Constant(complex::Algebraic::new(0.0, 0.0) - value),
+		Expression::Negate(inner) => *inner,
+		operand => Expression::Negate(Box::new(operand)),
+	}
+}
+
+/// `u + v`, simplified.
+fn add(left: Expression, right: Expression) -> Expression {
+	match (as_constant(&left), as_constant(&right)) {
+		(Option::Some(a), Option::Some(b)) => Expression::Constant(a + b),
+		_ if is_constant(&left, 0.0) => right,
+		_ if is_constant(&right, 0.0) => left,
+		_ => Expression::Add(Box::new(left), Box::new(right)),
+	}
+}
+
+/// `u - v`, simplified.
+fn subtract(left: Expression, right: Expression) -> Expression {
+	match (as_constant(&left), as_constant(&right)) {
+		(Option::Some(a), Option::Some(b)) => Expression::Constant(a - b),
+		_ if is_constant(&right, 0.0) => left,
+		_ if is_constant(&left, 0.0) => negate(right),
+		_ => Expression::Subtract(Box::new(left), Box::new(right)),
+	}
+}
+
+/// `u * v`, simplified.
+fn multiply(left: Expression, right: Expression) -> Expression {
+	match (as_constant(&left), as_constant(&right)) {
+		(Option::Some(a), Option::Some(b)) => Expression::Constant(a * b),
+		_ if is_constant(&left, 0.0) || is_constant(&right, 0.0) => constant(0.0),
+		_ if is_constant(&left, 1.0) => right,
+		_ if is_constant(&right, 1.0) => left,
+		_ if is_constant(&left, -1.0) => negate(right),
+		_ if is_constant(&right, -1.0) => negate(left),
+		// Constants first: `3 * z^2` rather than `z^2 * 3`.
+		(Option::None, Option::Some(_)) => Expression::Multiply(Box::new(right), Box::new(left)),
+		_ => Expression::Multiply(Box::new(left), Box::new(right)),
+	}
+}
+
+/// `u / v`, simplified.
+fn divide(left: Expression, right: Expression) -> Expression {
+	match (as_constant(&left), as_constant(&right)) {
+		(Option::Some(a), Option::Some(b)) if b.real != 0.0 || b.imaginary != 0.0 =>
+			Expression::Constant(a / b),
+		_ if is_constant(&left, 0.0) => constant(0.0),
+		_ if is_constant(&right, 1.0) => left,
+		_ => Expression::Divide(Box::new(left), Box::new(right)),
+	}
+}
+
+/// `u ^ v`, simplified.
+fn power(base: Expression, exponent: Expression) -> Expression {
+	if is_constant(&exponent, 0.0) {
+		constant(1.0)
+	} else if is_constant(&exponent, 1.0) {
+		base
+	} else {
+		Expression::Power(Box::new(base), Box::new(exponent))
+	}
+}
+
+/// `function(u)`, folded if `u` is constant.
+fn call(function: Function, argument: Expression) -> Expression {
+	match as_constant(&argument) {
+		Option::Some(value) => Expression::Constant(function.apply(value)),
+		Option::None => Expression::Call(function, Box::new(argument)),
+	}
+}
+
+
+#[test]
+fn test_derivative() -> () {
+	use crate::expressions;
+
+	let z: complex::Algebraic = complex::Algebraic::new(0.7, -0.4);
+	let c: complex::Algebraic = complex::Algebraic::new(-0.2, 0.9);
+	let step: complex::Algebraic = complex::Algebraic::new(1e-6, 0.0);
+
+	let formulas: [&str; 7] = [
+		"z^3 + 1",
+		"3z^5 - 2z + c",
+		"(z - 1) / (z + 2i)",
+		"sin(z^2) * exp(-z)",
+		"log(cos(z)) + z^c",
+		"z^z",
+		"c",
+	];
+	for formula in formulas {
+		let function: Expression = expressions::parse(formula).unwrap();
+		let derivative: Expression = function.derivative().unwrap();
+
+		// Central finite difference.
+		let expected: complex::Algebraic = (function.evaluate(z + step, c) - function.evaluate(z - step, c))
+			/ complex::Algebraic::new(2e-6, 0.0);
+		let computed: complex::Algebraic = derivative.evaluate(z, c);
+
+		assert!(
+			(computed.real - expected.real).abs() < 1e-5
+			&& (computed.imaginary - expected.imaginary).abs() < 1e-5,
+			"f = {}, f' = {}, computed {}, expected {}", function, derivative, computed, expected,
+		);
+	}
+
+	assert_eq!(expressions::parse("z^3 + 1").unwrap().derivative().unwrap().to_string(), "3 * z^2");
+	assert!(expressions::parse("conj(z)").unwrap().derivative().is_err());
+	assert!(expressions::parse("abs(c) * z").unwrap().derivative().is_ok());
+
+	// With respect to c.
+	for (formula, expected) in [("z^2 + c", "1"), ("c z^3 + c^2", "z^3 + 2 * c"), ("exp(c z)", "exp(c * z) * z")] {
+		let function: Expression = expressions::parse(formula).unwrap();
+		let derivative: Expression = function.derivative_c().unwrap();
+		let expected: Expression = expressions::parse(expected).unwrap();
+		let difference: complex::Algebraic = derivative.evaluate(z, c) - expected.evaluate(z, c);
+		assert!(difference.real.abs() + difference.imaginary.abs() < 1e-12, "f = {}, ∂f/∂c = {}", function, derivative);
+	}
+	assert!(expressions::parse("conj(c) + z").unwrap().derivative_c().is_err());
+}
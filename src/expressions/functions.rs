@@ -0,0 +1,101 @@
+//! # Complex sequences.
+//! src/expressions/functions.rs
+//! 
+//! Elementary functions over `complex::Algebraic`.
+
+use complex;
+
+/// Complex exponential:
+/// ```math
+/// exp(a + ib) = e^a * (cos(b) + i sin(b))
+/// ```
+pub fn exp(z: complex::Algebraic) -> complex::Algebraic {
+	let modulus: complex::Real = z.real.exp();
+
+	complex::Algebraic::new(
+		modulus * z.imaginary.cos(), 
+		modulus * z.imaginary.sin(),
+	)
+}
+
+/// Complex sine:
+/// ```math
+/// sin(a + ib) = sin(a) cosh(b) + i cos(a) sinh(b)
+/// ```
+pub fn sin(z: complex::Algebraic) -> complex::Algebraic {
+	complex::Algebraic::new(
+		z.real.sin() * z.imaginary.cosh(), 
+		z.real.cos() * z.imaginary.sinh(),
+	)
+}
+
+/// Complex cosine:
+/// ```math
+/// cos(a + ib) = cos(a) cosh(b) - i sin(a) sinh(b)
+/// ```
+pub fn cos(z: complex::Algebraic) -> complex::Algebraic {
+	complex::Algebraic::new(
+		z.real.cos() * z.imaginary.cosh(), 
+		-z.real.sin() * z.imaginary.sinh(),
+	)
+}
+
+/// Principal complex logarithm, with the argument in ]-π; π]:
+/// ```math
+/// log(z) = ln|z| + i arg(z)
+/// ```
+pub fn log(z: complex::Algebraic) -> complex::Algebraic {
+	complex::Algebraic::new(
+		modulus(z).ln(), 
+		z.imaginary.atan2(z.real),
+	)
+}
+
+/// Complex conjugate: `a - ib`.
+pub fn conj(z: complex::Algebraic) -> complex::Algebraic {
+	complex::Algebraic::new(z.real, -z.imaginary)
+}
+
+/// Modulus `|z|`, as a real complex number.
+pub fn abs(z: complex::Algebraic) -> complex::Algebraic {
+	complex::Algebraic::new(modulus(z), 0.0)
+}
+
+/// Power `z^w`.
+/// - Integer real exponents use exact repeated multiplications (`z^2 = z * z`),
+/// - other exponents use the principal branch `exp(w log(z))`.
+pub fn power(z: complex::Algebraic, w: complex::Algebraic) -> complex::Algebraic {
+	if w.imaginary == 0.0 && w.real.fract() == 0.0 && w.real.abs() <= i32::MAX as complex::Real {
+		power_integer(z, w.real as i32)
+	} else if z.real == 0.0 && z.imaginary == 0.0 {
+		complex::Algebraic::new(0.0, 0.0)
+	} else {
+		exp(w * log(z))
+	}
+}
+
+/// Power `z^n` with an integer `n`, by binary exponentiation.
+pub fn power_integer(z: complex::Algebraic, n: i32) -> complex::Algebraic {
+	let mut result: complex::Algebraic = complex::ONE;
+	let mut base: complex::Algebraic = z;
+	let mut exponent: u32 = n.unsigned_abs();
+
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = result * base;
+		}
+		base = base * base;
+		exponent >>= 1;
+	}
+
+	if n < 0 {
+		complex::ONE / result
+	} else {
+		result
+	}
+}
+
+/// Modulus `|z|`, with `hypot` to avoid overflows.
+pub fn modulus(z: complex::Algebraic) -> complex::Real {
+	z.real.hypot(z.imaginary)
+}
@@ -0,0 +1,91 @@
+//! # Complex sequences.
+//! src/expressions/holomorphic.rs
+//!
+//! Functions of `z` only, derived once for root finding.
+
+use std::error;
+
+use complex;
+
+use crate::expressions::{derivative, parser, polynomial, tree};
+
+/// Highest derivative order kept, for Householder's method of order 3.
+pub const ORDER_MAX: usize = 3;
+
+/// # `Holomorphic` function `f(z)`, with its derivatives `f'(z)`, `f''(z)` and `f'''(z)`.
+/// The derivatives are computed symbolically from `function`.
+///
+/// `c` evaluates to 0.
+///
+/// Polynomial functions are also expanded, evaluated with Horner's scheme and
+/// solved for all their roots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holomorphic {
+	function: tree::Expression,
+	/// `f'`, `f''`, `f'''`.
+	derivatives: [tree::Expression; ORDER_MAX],
+	/// `function` and its derivatives expanded, if `function` is a polynomial.
+	polynomials: Option<[polynomial::Polynomial; ORDER_MAX + 1]>,
+}
+
+impl Holomorphic {
+	/// Derive `function`, or fail if it has no complex derivative.
+	pub fn new(function: tree::Expression) -> Result<Holomorphic, derivative::DerivativeError> {
+		let first: tree::Expression = function.derivative()?;
+		let second: tree::Expression = first.derivative()?;
+		let third: tree::Expression = second.derivative()?;
+
+		let polynomials: Option<[polynomial::Polynomial; ORDER_MAX + 1]> =
+			polynomial::Polynomial::from_expression(&function, complex::Algebraic::new(0.0, 0.0))
+				.map(|polynomial| {
+					let first: polynomial::Polynomial = polynomial.derivative();
+					let second: polynomial::Polynomial = first.derivative();
+					let third: polynomial::Polynomial = second.derivative();
+					[polynomial, first, second, third]
+				});
+
+		Result::Ok(Holomorphic { function, derivatives: [first, second, third], polynomials })
+	}
+
+	/// Parse and derive a `formula`.
+	pub fn parse(formula: &str) -> Result<Holomorphic, Box<dyn error::Error>> {
+		Result::Ok(Holomorphic::new(parser::parse(formula)?)?)
+	}
+
+	pub fn get_function(self: &Self) -> &tree::Expression {
+		&self.function
+	}
+
+	pub fn get_derivative(self: &Self) -> &tree::Expression {
+		&self.derivatives[0]
+	}
+
+	/// The expanded polynomial, if `function` is one.
+	pub fn get_polynomial(self: &Self) -> Option<&polynomial::Polynomial> {
+		self.polynomials.as_ref().map(|polynomials| &polynomials[0])
+	}
+
+	/// All the roots, if `function` is a polynomial. `None` if they can only be found by scanning.
+	pub fn roots(self: &Self) -> Option<Vec<complex::Algebraic>> {
+		self.get_polynomial().map(|polynomial| polynomial.roots())
+	}
+
+	/// `f(z)`.
+	pub fn evaluate(self: &Self, z: complex::Algebraic) -> complex::Algebraic {
+		self.evaluate_derivative_order(z, 0)
+	}
+
+	/// `f'(z)`.
+	pub fn evaluate_derivative(self: &Self, z: complex::Algebraic) -> complex::Algebraic {
+		self.evaluate_derivative_order(z, 1)
+	}
+
+	/// Derivative of `order` at `z`: 0 is `f` itself, up to `ORDER_MAX`.
+	pub fn evaluate_derivative_order(self: &Self, z: complex::Algebraic, order: usize) -> complex::Algebraic {
+		match (&self.polynomials, order) {
+			(Option::Some(polynomials), _) => polynomials[order].evaluate(z),
+			(Option::None, 0) => self.function.evaluate(z, complex::Algebraic::new(0.0, 0.0)),
+			(Option::None, _) => self.derivatives[order - 1].evaluate(z, complex::Algebraic::new(0.0, 0.0)),
+		}
+	}
+}
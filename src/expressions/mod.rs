@@ -0,0 +1,24 @@
+//! # Complex sequences.
+//! src/expressions/mod.rs
+//! 
+//! User-typed formulas over `complex::Algebraic`: parse a text into an `Expression` tree,
+//! then evaluate it for any `z` and `c`.
+//! 
+//! Supported:
+//! - variables `z`, `c`; constants `i`, `pi`, `e` and numbers (`2`, `0.5`, `1e-3`);
+//! - operators `+ - * / ^`, with implicit multiplication (`3z^2`);
+//! - functions `exp`, `sin`, `cos`, `log`, `conj`, `abs`.
+//! 
+//! Expressions of `z` can be derived symbolically, and polynomials solved, for root finding.
+
+pub mod derivative;
+pub mod functions;
+pub mod holomorphic;
+pub mod parser;
+pub mod polynomial;
+pub mod tree;
+
+pub use holomorphic::Holomorphic;
+pub use parser::{parse, ParseError};
+pub use polynomial::Polynomial;
+pub use tree::Expression;
@@ -0,0 +1,373 @@
+//! # Complex sequences.
+//! src/expressions/parser.rs
+//!
+//! Parse a formula text into an `Expression`, by recursive descent.
+//!
+//! Grammar, from the loosest to the tightest binding:
+//! ```ignore
+//! sum     = product (("+" | "-") product)*
+//! product = unary (("*" | "/")? unary)*      // No operator: implicit multiplication.
+//! unary   = ("-" | "+") unary | power
+//! power   = primary ("^" unary)?             // Right associative.
+//! primary = number | name | name "(" sum ")" | "(" sum ")"
+//! ```
+
+use std::{error, fmt};
+
+use complex;
+
+use crate::expressions::tree::{Expression, Function, Variable};
+
+/// # `ParseError` of a formula.
+/// - `position`: index of the character where the error occurs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	pub message: String,
+	pub position: usize,
+}
+
+impl ParseError {
+	fn new(message: String, position: usize) -> ParseError {
+		ParseError { message, position }
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "(!) Column {}: {}", self.position + 1, self.message)
+	}
+}
+
+impl error::Error for ParseError {}
+
+/// # `Token` of a formula, from the lexer.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	Number(complex::Real),
+	Name(String),
+	Plus,
+	Minus,
+	Star,
+	Slash,
+	Caret,
+	LeftParenthesis,
+	RightParenthesis,
+	End,
+}
+
+impl fmt::Display for Token {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Token::Number(value) => write!(formatter, "number `{}`", value),
+			Token::Name(name) => write!(formatter, "name `{}`", name),
+			Token::Plus => write!(formatter, "`+`"),
+			Token::Minus => write!(formatter, "`-`"),
+			Token::Star => write!(formatter, "`*`"),
+			Token::Slash => write!(formatter, "`/`"),
+			Token::Caret => write!(formatter, "`^`"),
+			Token::LeftParenthesis => write!(formatter, "`(`"),
+			Token::RightParenthesis => write!(formatter, "`)`"),
+			Token::End => write!(formatter, "end of formula"),
+		}
+	}
+}
+
+/// Parse a `formula` into an `Expression`.
+pub fn parse(formula: &str) -> Result<Expression, ParseError> {
+	let mut parser: Parser = Parser {
+		tokens: tokenize(formula)?,
+		index: 0,
+	};
+
+	let expression: Expression = parser.sum()?;
+
+	match parser.peek() {
+		Token::End => Result::Ok(expression),
+		token => Result::Err(ParseError::new(
+			format!("unexpected {}.", token),
+			parser.position(),
+		)),
+	}
+}
+
+/// Split `formula` into `Token`s, with their position.
+fn tokenize(formula: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+	let characters: Vec<char> = formula.chars().collect();
+	let mut tokens: Vec<(Token, usize)> = Vec::new();
+	let mut index: usize = 0;
+
+	while index < characters.len() {
+		let character: char = characters[index];
+		let start: usize = index;
+
+		let token: Token = match character {
+			' ' | '\t' | '\n' => {
+				index += 1;
+				continue;
+			},
+			'+' => Token::Plus,
+			'-' => Token::Minus,
+			'*' => Token::Star,
+			'/' => Token::Slash,
+			'^' => Token::Caret,
+			'(' => Token::LeftParenthesis,
+			')' => Token::RightParenthesis,
+			'0'..='9' | '.' => {
+				while index < characters.len()
+					&& (characters[index].is_ascii_digit() || characters[index] == '.')
+				{
+					index += 1;
+				}
+				// Scientific notation, only if digits follow: `2e` is `2 * e`.
+				if index < characters.len() && (characters[index] == 'e' || characters[index] == 'E') {
+					let mut exponent_end: usize = index + 1;
+					if exponent_end < characters.len()
+						&& (characters[exponent_end] == '+' || characters[exponent_end] == '-')
+					{
+						exponent_end += 1;
+					}
+					if exponent_end < characters.len() && characters[exponent_end].is_ascii_digit() {
+						index = exponent_end;
+						while index < characters.len() && characters[index].is_ascii_digit() {
+							index += 1;
+						}
+					}
+				}
+
+				let text: String = characters[start..index].iter().collect();
+				match text.parse::<complex::Real>() {
+					Result::Ok(value) => tokens.push((Token::Number(value), start)),
+					Result::Err(_) => return Result::Err(ParseError::new(
+						format!("invalid number `{}`.", text),
+						start,
+					)),
+				}
+				continue;
+			},
+			character if character.is_alphabetic() || character == '_' => {
+				while index < characters.len()
+					&& (characters[index].is_alphanumeric() || characters[index] == '_')
+				{
+					index += 1;
+				}
+
+				tokens.push((Token::Name(characters[start..index].iter().collect()), start));
+				continue;
+			},
+			character => return Result::Err(ParseError::new(
+				format!("unexpected character `{}`.", character),
+				start,
+			)),
+		};
+
+		tokens.push((token, start));
+		index += 1;
+	}
+
+	tokens.push((Token::End, characters.len()));
+
+	Result::Ok(tokens)
+}
+
+/// # Recursive descent `Parser` over the `tokens`.
+struct Parser {
+	tokens: Vec<(Token, usize)>,
+	index: usize,
+}
+
+impl Parser {
+	/// Current token, without consuming it.
+	fn peek(self: &Self) -> &Token {
+		&self.tokens[self.index].0
+	}
+
+	/// Position of the current token in the formula.
+	fn position(self: &Self) -> usize {
+		self.tokens[self.index].1
+	}
+
+	/// Consume and return the current token.
+	fn next(self: &mut Self) -> Token {
+		let token: Token = self.tokens[self.index].0.clone();
+		if token != Token::End {
+			self.index += 1;
+		}
+
+		token
+	}
+
+	/// `sum = product (("+" | "-") product)*`
+	fn sum(self: &mut Self) -> Result<Expression, ParseError> {
+		let mut left: Expression = self.product()?;
+
+		loop {
+			match self.peek() {
+				Token::Plus => {
+					self.next();
+					left = Expression::Add(Box::new(left), Box::new(self.product()?));
+				},
+				Token::Minus => {
+					self.next();
+					left = Expression::Subtract(Box::new(left), Box::new(self.product()?));
+				},
+				_ => return Result::Ok(left),
+			}
+		}
+	}
+
+	/// `product = unary (("*" | "/")? unary)*`
+	fn product(self: &mut Self) -> Result<Expression, ParseError> {
+		let mut left: Expression = self.unary()?;
+
+		loop {
+			match self.peek() {
+				Token::Star => {
+					self.next();
+					left = Expression::Multiply(Box::new(left), Box::new(self.unary()?));
+				},
+				Token::Slash => {
+					self.next();
+					left = Expression::Divide(Box::new(left), Box::new(self.unary()?));
+				},
+				// Implicit multiplication: `3z`, `2(z + 1)`, `z c`.
+				Token::Number(_) | Token::Name(_) | Token::LeftParenthesis => {
+					left = Expression::Multiply(Box::new(left), Box::new(self.power()?));
+				},
+				_ => return Result::Ok(left),
+			}
+		}
+	}
+
+	/// `unary = ("-" | "+") unary | power`
+	fn unary(self: &mut Self) -> Result<Expression, ParseError> {
+		match self.peek() {
+			Token::Minus => {
+				self.next();
+				Result::Ok(Expression::Negate(Box::new(self.unary()?)))
+			},
+			Token::Plus => {
+				self.next();
+				self.unary()
+			},
+			_ => self.power(),
+		}
+	}
+
+	/// `power = primary ("^" unary)?`
+	fn power(self: &mut Self) -> Result<Expression, ParseError> {
+		let base: Expression = self.primary()?;
+
+		if *self.peek() == Token::Caret {
+			self.next();
+			Result::Ok(Expression::Power(Box::new(base), Box::new(self.unary()?)))
+		} else {
+			Result::Ok(base)
+		}
+	}
+
+	/// `primary = number | name | name "(" sum ")" | "(" sum ")"`
+	fn primary(self: &mut Self) -> Result<Expression, ParseError> {
+		let position: usize = self.position();
+
+		match self.next() {
+			Token::Number(value) => Result::Ok(Expression::Constant(complex::Algebraic::new(value, 0.0))),
+			Token::LeftParenthesis => {
+				let inner: Expression = self.sum()?;
+				self.closing_parenthesis(position)?;
+				Result::Ok(inner)
+			},
+			Token::Name(name) => self.name(&name, position),
+			token => Result::Err(ParseError::new(
+				format!("expected a number, a variable or `(`, found {}.", token),
+				position,
+			)),
+		}
+	}
+
+	/// A variable, a constant or a function call named `name`.
+	fn name(self: &mut Self, name: &str, position: usize) -> Result<Expression, ParseError> {
+		match name {
+			"z" => return Result::Ok(Expression::Variable(Variable::Z)),
+			"c" => return Result::Ok(Expression::Variable(Variable::C)),
+			"i" => return Result::Ok(Expression::Constant(complex::Algebraic::new(0.0, 1.0))),
+			"pi" => return Result::Ok(Expression::Constant(
+				complex::Algebraic::new(std::f64::consts::PI as complex::Real, 0.0)
+			)),
+			"e" => return Result::Ok(Expression::Constant(
+				complex::Algebraic::new(std::f64::consts::E as complex::Real, 0.0)
+			)),
+			_ => {},
+		}
+
+		let function: Function = match (name, Function::list().into_iter().find(|function| function.to_name() == name)) {
+			(_, Option::Some(function)) => function,
+			("ln", Option::None) => Function::Log,
+			(_, Option::None) => return Result::Err(ParseError::new(
+				format!("unknown name `{}`; expected `z`, `c`, `i`, `pi`, `e` or a function.", name),
+				position,
+			)),
+		};
+
+		let parenthesis_position: usize = self.position();
+		if self.next() != Token::LeftParenthesis {
+			return Result::Err(ParseError::new(
+				format!("function `{}` expects `(`.", name),
+				parenthesis_position,
+			));
+		}
+
+		let argument: Expression = self.sum()?;
+		self.closing_parenthesis(parenthesis_position)?;
+
+		Result::Ok(Expression::Call(function, Box::new(argument)))
+	}
+
+	/// Consume a `)` closing the `(` at `opening`.
+	fn closing_parenthesis(self: &mut Self, opening: usize) -> Result<(), ParseError> {
+		let position: usize = self.position();
+
+		match self.next() {
+			Token::RightParenthesis => Result::Ok(()),
+			token => Result::Err(ParseError::new(
+				format!("expected `)` closing column {}, found {}.", opening + 1, token),
+				position,
+			)),
+		}
+	}
+}
+
+
+#[test]
+fn test_parse_and_evaluate() -> () {
+	let z: complex::Algebraic = complex::Algebraic::new(0.5, -1.5);
+	let c: complex::Algebraic = complex::Algebraic::new(-0.8, 0.156);
+	let close = |a: complex::Algebraic, b: complex::Algebraic| {
+		(a.real - b.real).abs() < 1e-9 && (a.imaginary - b.imaginary).abs() < 1e-9
+	};
+
+	assert!(close(parse("z^2 + c").unwrap().evaluate(z, c), z * z + c));
+	assert!(close(parse("z*z*z + c").unwrap().evaluate(z, c), z * z * z + c));
+	assert!(close(parse("3z^2 - 2(z + i)").unwrap().evaluate(z, c),
+		complex::Algebraic::new(3.0, 0.0) * z * z
+		- complex::Algebraic::new(2.0, 0.0) * (z + complex::Algebraic::new(0.0, 1.0))
+	));
+	assert!(close(parse("-z^2").unwrap().evaluate(z, c), complex::Algebraic::new(0.0, 0.0) - z * z));
+	assert!(close(parse("exp(log(z))").unwrap().evaluate(z, c), z));
+	assert!(close(parse("conj(z) / abs(z)^2").unwrap().evaluate(z, c), complex::ONE / z));
+	assert!(close(parse("sin(z)^2 + cos(z)^2").unwrap().evaluate(z, c), complex::ONE));
+	assert!(close(parse("2^-1").unwrap().evaluate(z, c), complex::Algebraic::new(0.5, 0.0)));
+
+	// Printing then parsing again gives the same function.
+	for formula in ["z^2 + c", "-(z - c)^3 / (2 + i)", "z^2^3", "(z^2)^3", "exp(-z) * 1e-3"] {
+		let expression: Expression = parse(formula).unwrap();
+		let printed: Expression = parse(&expression.to_string()).unwrap();
+		assert!(close(printed.evaluate(z, c), expression.evaluate(z, c)), "{}", expression);
+	}
+
+	assert_eq!(parse("z +").unwrap_err().position, 3);
+	assert_eq!(parse("z $ c").unwrap_err().position, 2);
+	assert_eq!(parse("sin z").unwrap_err().position, 4);
+	assert!(parse("(z + c").is_err());
+	assert!(parse("foo(z)").is_err());
+	assert!(parse("").is_err());
+}
@@ -0,0 +1,333 @@
+//! # Complex sequences.
+//! src/expressions/polynomial.rs
+//!
+//! Polynomials over `complex::Algebraic`: evaluation, derivative and all the roots at once.
+
+use complex;
+
+use crate::expressions::functions;
+use crate::expressions::tree::{Expression, Variable};
+
+/// Highest degree expanded by `Polynomial::from_expression`, of each power and product.
+const DEGREE_MAX: usize = 64;
+/// Iterations cap of the Aberth method.
+const ABERTH_ITERATIONS: usize = 500;
+/// Relative step under which Aberth's approximations are considered converged.
+const ABERTH_TOLERANCE: complex::Real = 1e-14;
+/// Relative distance under which two roots are considered the same (multiple roots).
+const ROOT_MERGE: complex::Real = 1e-6;
+
+/// # `Polynomial` of `z`.
+/// `coefficients[k]` is the coefficient of `z^k`, without trailing zeros.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+	coefficients: Vec<complex::Algebraic>,
+}
+
+impl Polynomial {
+	/// Build a polynomial from its `coefficients`, lowest power first.
+	pub fn new(coefficients: Vec<complex::Algebraic>) -> Polynomial {
+		let mut coefficients: Vec<complex::Algebraic> = coefficients;
+		while let Option::Some(last) = coefficients.last()
+			&& last.real == 0.0
+			&& last.imaginary == 0.0
+		{
+			coefficients.pop();
+		}
+
+		Polynomial { coefficients }
+	}
+
+	/// Constant polynomial `value`.
+	pub fn constant(value: complex::Algebraic) -> Polynomial {
+		Polynomial::new(vec![value])
+	}
+
+	/// The monomial `z`.
+	pub fn z() -> Polynomial {
+		Polynomial::new(vec![complex::Algebraic::new(0.0, 0.0), complex::Algebraic::new(1.0, 0.0)])
+	}
+
+	/// Degree of the polynomial. The zero polynomial has degree 0.
+	pub fn degree(self: &Self) -> usize {
+		self.coefficients.len().saturating_sub(1)
+	}
+
+	/// Value of the polynomial at `z`, with Horner's scheme:
+	/// ```math
+	/// P(z) = a0 + z (a1 + z (a2 + ... + z an))
+	/// ```
+	pub fn evaluate(self: &Self, z: complex::Algebraic) -> complex::Algebraic {
+		let mut value: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+		for coefficient in self.coefficients.iter().rev() {
+			value = value * z + *coefficient;
+		}
+
+		value
+	}
+
+	/// Derivative `P'` of the polynomial.
+	pub fn derivative(self: &Self) -> Polynomial {
+		Polynomial::new(
+			self.coefficients
+				.iter()
+				.enumerate()
+				.skip(1)
+				.map(|(power, coefficient)| complex::Algebraic::new(power as complex::Real, 0.0) * *coefficient)
+				.collect()
+		)
+	}
+
+	/// Sum of two polynomials.
+	pub fn add(self: &Self, other: &Polynomial) -> Polynomial {
+		let length: usize = self.coefficients.len().max(other.coefficients.len());
+		let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+		Polynomial::new(
+			(0..length)
+				.map(|k| {
+					*self.coefficients.get(k).unwrap_or(&zero) + *other.coefficients.get(k).unwrap_or(&zero)
+				})
+				.collect()
+		)
+	}
+
+	/// Product of two polynomials.
+	pub fn multiply(self: &Self, other: &Polynomial) -> Polynomial {
+		if self.coefficients.is_empty() || other.coefficients.is_empty() {
+			return Polynomial::new(Vec::new());
+		}
+
+		let mut coefficients: Vec<complex::Algebraic> = vec![
+			complex::Algebraic::new(0.0, 0.0);
+			self.coefficients.len() + other.coefficients.len() - 1
+		];
+		for (i, a) in self.coefficients.iter().enumerate() {
+			for (j, b) in other.coefficients.iter().enumerate() {
+				coefficients[i + j] = coefficients[i + j] + *a * *b;
+			}
+		}
+
+		Polynomial::new(coefficients)
+	}
+
+	/// Multiply every coefficient by `factor`.
+	pub fn scale(self: &Self, factor: complex::Algebraic) -> Polynomial {
+		Polynomial::new(self.coefficients.iter().map(|coefficient| *coefficient * factor).collect())
+	}
+
+	/// Expand `expression` into a polynomial of `z`, with the variable `c` set to `c`.
+	///
+	/// Returns `None` if it is not one: `z` under a function, a division or a non integer power,
+	/// or if a power or a product is of degree above `DEGREE_MAX`.
+	pub fn from_expression(expression: &Expression, c: complex::Algebraic) -> Option<Polynomial> {
+		if !expression.depends_on_z() {
+			return Option::Some(Polynomial::constant(expression.evaluate(complex::Algebraic::new(0.0, 0.0), c)));
+		}
+
+		match expression {
+			Expression::Variable(Variable::Z) => Option::Some(Polynomial::z()),
+			Expression::Negate(operand) => Option::Some(
+				Polynomial::from_expression(operand, c)?.scale(complex::Algebraic::new(-1.0, 0.0))
+			),
+			Expression::Add(left, right) => Option::Some(
+				Polynomial::from_expression(left, c)?.add(&Polynomial::from_expression(right, c)?)
+			),
+			Expression::Subtract(left, right) => Option::Some(
+				Polynomial::from_expression(left, c)?.add(
+					&Polynomial::from_expression(right, c)?.scale(complex::Algebraic::new(-1.0, 0.0))
+				)
+			),
+			Expression::Multiply(left, right) => {
+				let left: Polynomial = Polynomial::from_expression(left, c)?;
+				let right: Polynomial = Polynomial::from_expression(right, c)?;
+				if left.degree() + right.degree() > DEGREE_MAX {
+					return Option::None;
+				}
+
+				Option::Some(left.multiply(&right))
+			},
+			Expression::Divide(left, right) if !right.depends_on_z() => {
+				let divisor: complex::Algebraic = right.evaluate(complex::Algebraic::new(0.0, 0.0), c);
+				if divisor.real == 0.0 && divisor.imaginary == 0.0 {
+					return Option::None;
+				}
+
+				Option::Some(
+					Polynomial::from_expression(left, c)?
+						.scale(complex::Algebraic::new(1.0, 0.0) / divisor)
+				)
+			},
+			Expression::Power(base, exponent) if !exponent.depends_on_z() => {
+				let exponent: complex::Algebraic = exponent.evaluate(complex::Algebraic::new(0.0, 0.0), c);
+				if exponent.imaginary != 0.0
+					|| exponent.real < 0.0
+					|| exponent.real.fract() != 0.0
+					|| exponent.real as usize > DEGREE_MAX
+				{
+					return Option::None;
+				}
+
+				let base: Polynomial = Polynomial::from_expression(base, c)?;
+				if base.degree() * exponent.real as usize > DEGREE_MAX {
+					return Option::None;
+				}
+				let mut result: Polynomial = Polynomial::constant(complex::Algebraic::new(1.0, 0.0));
+				for _ in 0..(exponent.real as usize) {
+					result = result.multiply(&base);
+				}
+
+				Option::Some(result)
+			},
+			_ => Option::None,
+		}
+	}
+
+	/// # All the roots, with the Aberth–Ehrlich method.
+	/// Every approximation `z_k` is updated at once with:
+	/// ```math
+	/// w_k = P(z_k) / P'(z_k)
+	/// z_k <- z_k - w_k / (1 - w_k * Σ_{j ≠ k} 1 / (z_k - z_j))
+	/// ```
+	/// starting from a circle bounding the roots.
+	///
+	/// Multiple roots are returned once, sorted by argument then modulus, so each root
+	/// keeps the same index whatever the view.
+	pub fn roots(self: &Self) -> Vec<complex::Algebraic> {
+		let degree: usize = self.degree();
+		if degree == 0 {
+			return Vec::new();
+		}
+
+		// Monic, so the Cauchy bound applies.
+		let leading: complex::Algebraic = self.coefficients[degree];
+		let monic: Polynomial = self.scale(complex::Algebraic::new(1.0, 0.0) / leading);
+		let derivative: Polynomial = monic.derivative();
+		let radius: complex::Real = 1.0 + monic.coefficients[..degree]
+			.iter()
+			.map(|coefficient| functions::modulus(*coefficient))
+			.fold(0.0, complex::Real::max);
+
+		// Off-axis start, to avoid symmetric stalls.
+		let mut approximations: Vec<complex::Algebraic> = (0..degree)
+			.map(|k| {
+				let angle: complex::Real = 2.0 * std::f64::consts::PI * k as complex::Real / degree as complex::Real + 0.4;
+				complex::Algebraic::new(0.5 * radius * angle.cos(), 0.5 * radius * angle.sin())
+			})
+			.collect();
+
+		for _ in 0..ABERTH_ITERATIONS {
+			let mut step_max: complex::Real = 0.0;
+
+			for k in 0..degree {
+				let z: complex::Algebraic = approximations[k];
+				let value: complex::Algebraic = monic.evaluate(z);
+				if value.real == 0.0 && value.imaginary == 0.0 {
+					continue;
+				}
+
+				let newton: complex::Algebraic = value / derivative.evaluate(z);
+				let mut repulsion: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+				for (j, other) in approximations.iter().enumerate() {
+					if j != k && *other != z {
+						repulsion = repulsion + complex::Algebraic::new(1.0, 0.0) / (z - *other);
+					}
+				}
+
+				let step: complex::Algebraic = newton / (complex::Algebraic::new(1.0, 0.0) - newton * repulsion);
+				if step.real.is_finite() && step.imaginary.is_finite() {
+					approximations[k] = z - step;
+					step_max = step_max.max(functions::modulus(step) / (1.0 + functions::modulus(z)));
+				}
+			}
+
+			if step_max < ABERTH_TOLERANCE {
+				break;
+			}
+		}
+
+		let mut roots: Vec<complex::Algebraic> = Vec::with_capacity(degree);
+		for approximation in approximations {
+			let merge: complex::Real = ROOT_MERGE * (1.0 + functions::modulus(approximation));
+			if roots.iter().all(|root| functions::modulus(*root - approximation) > merge) {
+				roots.push(approximation);
+			}
+		}
+
+		roots.sort_by(|a, b| {
+			let key = |z: &complex::Algebraic| (
+				// Round, so conjugates and real roots don't swap on tiny errors.
+				(z.imaginary.atan2(z.real) * 1e9).round(),
+				functions::modulus(*z),
+			);
+			key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal)
+		});
+
+		roots
+	}
+}
+
+impl Expression {
+	/// Degree in `z`, if the expression is a polynomial of `z`.
+	/// Expanded at an arbitrary `c`, so that no coefficient of `c` vanishes.
+	pub fn degree(self: &Self) -> Option<usize> {
+		Polynomial::from_expression(self, complex::Algebraic::new(0.421, 0.577))
+			.map(|polynomial: Polynomial| polynomial.degree())
+	}
+}
+
+
+#[test]
+fn test_polynomial() -> () {
+	use crate::expressions;
+
+	let close = |a: complex::Algebraic, b: complex::Algebraic| functions::modulus(a - b) < 1e-9;
+
+	// (z - 1)(z - 2i)(z + 3)
+	let product: Polynomial = [
+		complex::Algebraic::new(1.0, 0.0),
+		complex::Algebraic::new(0.0, 2.0),
+		complex::Algebraic::new(-3.0, 0.0),
+	]
+		.iter()
+		.fold(Polynomial::constant(complex::Algebraic::new(1.0, 0.0)), |polynomial, root| {
+			polynomial.multiply(&Polynomial::new(vec![complex::Algebraic::new(0.0, 0.0) - *root, complex::Algebraic::new(1.0, 0.0)]))
+		});
+	assert_eq!(product.degree(), 3);
+
+	let roots: Vec<complex::Algebraic> = product.roots();
+	assert_eq!(roots.len(), 3);
+	// Sorted by argument: 1 (0), 2i (π/2), -3 (π).
+	assert!(close(roots[0], complex::Algebraic::new(1.0, 0.0)), "{:?}", roots);
+	assert!(close(roots[1], complex::Algebraic::new(0.0, 2.0)), "{:?}", roots);
+	assert!(close(roots[2], complex::Algebraic::new(-3.0, 0.0)), "{:?}", roots);
+
+	// Horner and derivative versus the expression tree.
+	let expression: Expression = expressions::parse("3z^5 - 2z + (1 + i) / 2").unwrap();
+	let polynomial: Polynomial = Polynomial::from_expression(&expression, complex::Algebraic::new(0.0, 0.0)).unwrap();
+	let derivative: Expression = expression.derivative().unwrap();
+	let z: complex::Algebraic = complex::Algebraic::new(0.3, -1.2);
+	assert_eq!(polynomial.degree(), 5);
+	assert!(close(polynomial.evaluate(z), expression.evaluate(z, complex::Algebraic::new(0.0, 0.0))));
+	assert!(close(polynomial.derivative().evaluate(z), derivative.evaluate(z, complex::Algebraic::new(0.0, 0.0))));
+	for root in polynomial.roots() {
+		assert!(functions::modulus(polynomial.evaluate(root)) < 1e-9);
+	}
+
+	// z^3 + 1: the cube roots of -1; multiple roots once.
+	let cube: Polynomial = Polynomial::from_expression(&expressions::parse("z^3 + 1").unwrap(), complex::Algebraic::new(0.0, 0.0)).unwrap();
+	assert_eq!(cube.roots().len(), 3);
+	let square: Polynomial = Polynomial::from_expression(&expressions::parse("(z - i)^2 * (z + 2)").unwrap(), complex::Algebraic::new(0.0, 0.0)).unwrap();
+	assert_eq!(square.roots().len(), 2);
+
+	assert!(Polynomial::from_expression(&expressions::parse("sin(z)").unwrap(), complex::Algebraic::new(0.0, 0.0)).is_none());
+	assert!(Polynomial::from_expression(&expressions::parse("z^0.5").unwrap(), complex::Algebraic::new(0.0, 0.0)).is_none());
+	assert!(Polynomial::from_expression(&expressions::parse("1 / z").unwrap(), complex::Algebraic::new(0.0, 0.0)).is_none());
+	// Degrees multiplied past the highest one.
+	assert!(Polynomial::from_expression(&expressions::parse("(z^64)^64").unwrap(), complex::Algebraic::new(0.0, 0.0)).is_none());
+	assert!(Polynomial::from_expression(&expressions::parse("z^40 * z^40").unwrap(), complex::Algebraic::new(0.0, 0.0)).is_none());
+	assert_eq!(Polynomial::from_expression(&expressions::parse("(z^8)^8").unwrap(), complex::Algebraic::new(0.0, 0.0)).unwrap().degree(), 64);
+
+	assert_eq!(expressions::parse("c z^3 + z + c").unwrap().degree(), Option::Some(3));
+	assert_eq!(expressions::parse("exp(z) + c").unwrap().degree(), Option::None);
+}
@@ -0,0 +1,201 @@
+//! # Complex sequences.
+//! src/expressions/tree.rs
+//!
+//! `Expression` tree and its evaluation.
+
+use std::fmt;
+
+use complex;
+
+use crate::expressions::functions;
+
+/// # `Variable` of an `Expression`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variable {
+	/// Member of the sequence, or point of the screen.
+	Z,
+	/// Constant of the sequence, or point of the screen.
+	C,
+}
+
+/// # `Function` of one complex argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+	Exp,
+	Sin,
+	Cos,
+	Log,
+	Conj,
+	Abs,
+}
+
+impl Function {
+	/// Returns a `Vec` of all the functions.
+	pub fn list() -> Vec<Function> {
+		vec![
+			Function::Exp,
+			Function::Sin,
+			Function::Cos,
+			Function::Log,
+			Function::Conj,
+			Function::Abs,
+		]
+	}
+
+	/// Returns the name of the function, as typed in a formula.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			Function::Exp => "exp",
+			Function::Sin => "sin",
+			Function::Cos => "cos",
+			Function::Log => "log",
+			Function::Conj => "conj",
+			Function::Abs => "abs",
+		}
+	}
+
+	/// Apply the function to `z`.
+	pub fn apply(self: &Self, z: complex::Algebraic) -> complex::Algebraic {
+		match &self {
+			Function::Exp => functions::exp(z),
+			Function::Sin => functions::sin(z),
+			Function::Cos => functions::cos(z),
+			Function::Log => functions::log(z),
+			Function::Conj => functions::conj(z),
+			Function::Abs => functions::abs(z),
+		}
+	}
+}
+
+/// # `Expression` tree over `complex::Algebraic`.
+/// Build it with `expressions::parse`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+	Constant(complex::Algebraic),
+	Variable(Variable),
+	Negate(Box<Expression>),
+	Add(Box<Expression>, Box<Expression>),
+	Subtract(Box<Expression>, Box<Expression>),
+	Multiply(Box<Expression>, Box<Expression>),
+	Divide(Box<Expression>, Box<Expression>),
+	Power(Box<Expression>, Box<Expression>),
+	Call(Function, Box<Expression>),
+}
+
+impl Expression {
+	/// Evaluate the expression for the variables `z` and `c`.
+	pub fn evaluate(self: &Self, z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic {
+		match self {
+			Expression::Constant(value) => *value,
+			Expression::Variable(Variable::Z) => z,
+			Expression::Variable(Variable::C) => c,
+			Expression::Negate(operand) =>
+				complex::Algebraic::new(0.0, 0.0) - operand.evaluate(z, c),
+			Expression::Add(left, right) => left.evaluate(z, c) + right.evaluate(z, c),
+			Expression::Subtract(left, right) => left.evaluate(z, c) - right.evaluate(z, c),
+			Expression::Multiply(left, right) => left.evaluate(z, c) * right.evaluate(z, c),
+			Expression::Divide(left, right) => left.evaluate(z, c) / right.evaluate(z, c),
+			Expression::Power(base, exponent) =>
+				functions::power(base.evaluate(z, c), exponent.evaluate(z, c)),
+			Expression::Call(function, argument) => function.apply(argument.evaluate(z, c)),
+		}
+	}
+
+	/// Degree `n` if the expression is `z^n + c`, with an integer `n` ≥ 2 (`z * z + c` too):
+	/// the sequences with a batched kernel.
+	pub fn power_of_z_plus_c(self: &Self) -> Option<u32> {
+		let power: &Expression = match self {
+			Expression::Add(left, right) if **right == Expression::Variable(Variable::C) => left,
+			Expression::Add(left, right) if **left == Expression::Variable(Variable::C) => right,
+			_ => return Option::None,
+		};
+		let z: Expression = Expression::Variable(Variable::Z);
+
+		match power {
+			Expression::Multiply(left, right) if **left == z && **right == z => Option::Some(2),
+			Expression::Power(base, exponent) if **base == z => match **exponent {
+				Expression::Constant(value) 
+					if value.imaginary == 0.0 && value.real.fract() == 0.0 && value.real >= 2.0 && value.real <= 64.0 
+					=> Option::Some(value.real as u32),
+				_ => Option::None,
+			},
+			_ => Option::None,
+		}
+	}
+
+	/// Binding strength, to print the least parentheses.
+	fn precedence(self: &Self) -> u8 {
+		match self {
+			Expression::Add(_, _) | Expression::Subtract(_, _) => 1,
+			Expression::Multiply(_, _) | Expression::Divide(_, _) => 2,
+			Expression::Negate(_) => 3,
+			Expression::Power(_, _) => 4,
+			Expression::Constant(value) if value.real != 0.0 && value.imaginary != 0.0 => 1,
+			Expression::Constant(value) if value.real < 0.0 || value.imaginary < 0.0 => 3,
+			Expression::Constant(_) | Expression::Variable(_) | Expression::Call(_, _) => 5,
+		}
+	}
+
+	/// Write `self`, between parentheses if it binds less than `precedence`.
+	fn fmt_operand(self: &Self, formatter: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+		if self.precedence() < precedence {
+			write!(formatter, "({})", self)
+		} else {
+			write!(formatter, "{}", self)
+		}
+	}
+}
+
+impl fmt::Display for Expression {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Expression::Constant(value) => {
+				if value.imaginary == 0.0 {
+					write!(formatter, "{}", value.real)
+				} else if value.real == 0.0 && value.imaginary == 1.0 {
+					write!(formatter, "i")
+				} else if value.real == 0.0 {
+					write!(formatter, "{}i", value.imaginary)
+				} else if value.imaginary < 0.0 {
+					write!(formatter, "{} - {}i", value.real, -value.imaginary)
+				} else {
+					write!(formatter, "{} + {}i", value.real, value.imaginary)
+				}
+			},
+			Expression::Variable(Variable::Z) => write!(formatter, "z"),
+			Expression::Variable(Variable::C) => write!(formatter, "c"),
+			Expression::Negate(operand) => {
+				write!(formatter, "-")?;
+				operand.fmt_operand(formatter, 4)
+			},
+			Expression::Add(left, right) => {
+				left.fmt_operand(formatter, 1)?;
+				write!(formatter, " + ")?;
+				right.fmt_operand(formatter, 2)
+			},
+			Expression::Subtract(left, right) => {
+				left.fmt_operand(formatter, 1)?;
+				write!(formatter, " - ")?;
+				right.fmt_operand(formatter, 2)
+			},
+			Expression::Multiply(left, right) => {
+				left.fmt_operand(formatter, 2)?;
+				write!(formatter, " * ")?;
+				right.fmt_operand(formatter, 3)
+			},
+			Expression::Divide(left, right) => {
+				left.fmt_operand(formatter, 2)?;
+				write!(formatter, " / ")?;
+				right.fmt_operand(formatter, 3)
+			},
+			Expression::Power(base, exponent) => {
+				base.fmt_operand(formatter, 5)?;
+				write!(formatter, "^")?;
+				exponent.fmt_operand(formatter, 4)
+			},
+			Expression::Call(function, argument) => {
+				write!(formatter, "{}({})", function.to_name(), argument)
+			},
+		}
+	}
+}
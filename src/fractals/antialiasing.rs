@@ -0,0 +1,512 @@
+//! # Complex sequences.
+//! src/fractals/antialiasing.rs
+//!
+//! Supersampling: each pixel is rendered at several sub-pixel positions,
+//! and the colors are averaged in linear RGB.
+//! Adaptive supersampling only renders again the pixels contrasted with their neighbors.
+
+use std::{convert, f64, fmt};
+use std::sync::Arc;
+
+use crate::structures::{color, computations};
+use crate::structures::combos::ComboMethod;
+use crate::fractals::{progressive, threading};
+
+/// # `Antialiasing`: the sub-pixel samples of each pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Antialiasing {
+	/// One sample, at the pixel.
+	Off,
+	Grid2,
+	Grid3,
+	Grid4,
+	/// 4x4 cells, each sampled at a random position.
+	Jittered,
+	/// One sample, then more on the pixels contrasted with a neighbor, cf. `Resampling`.
+	Adaptive,
+}
+
+impl Antialiasing {
+	/// Returns the lowercase name, as used in the command-line.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			Antialiasing::Off => "off",
+			Antialiasing::Grid2 => "2x2",
+			Antialiasing::Grid3 => "3x3",
+			Antialiasing::Grid4 => "4x4",
+			Antialiasing::Jittered => "jittered",
+			Antialiasing::Adaptive => "adaptive",
+		}
+	}
+
+	/// Every pixel is sampled several times.
+	pub fn is_uniform(self: &Self) -> bool {
+		self.side() > 1
+	}
+
+	/// Cells of a side of the pixel.
+	fn side(self: &Self) -> usize {
+		match &self {
+			Antialiasing::Off | Antialiasing::Adaptive => 1,
+			Antialiasing::Grid2 => 2,
+			Antialiasing::Grid3 => 3,
+			Antialiasing::Grid4 | Antialiasing::Jittered => 4,
+		}
+	}
+
+	/// All the samples of a pixel.
+	pub fn samples(self: &Self) -> Vec<Sample> {
+		(0..self.side() * self.side())
+			.map(|index| Sample { antialiasing: *self, index })
+			.collect()
+	}
+}
+
+impl ComboMethod for Antialiasing {
+	/// Return a `Vec` of all the antialiasings.
+	/// ```rust, no_run
+	/// 1. Off;
+	/// 2. 2x2;
+	/// 3. 3x3;
+	/// 4. 4x4;
+	/// 5. Jittered;
+	/// 6. Adaptive.
+	/// ```
+	fn list() -> Vec<Antialiasing> {
+		vec![
+			Antialiasing::Off,
+			Antialiasing::Grid2,
+			Antialiasing::Grid3,
+			Antialiasing::Grid4,
+			Antialiasing::Jittered,
+			Antialiasing::Adaptive,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			Antialiasing::Off => 1,
+			Antialiasing::Grid2 => 2,
+			Antialiasing::Grid3 => 3,
+			Antialiasing::Grid4 => 4,
+			Antialiasing::Jittered => 5,
+			Antialiasing::Adaptive => 6,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			Antialiasing::Off => "1. Off",
+			Antialiasing::Grid2 => "2. Grid 2x2",
+			Antialiasing::Grid3 => "3. Grid 3x3",
+			Antialiasing::Grid4 => "4. Grid 4x4",
+			Antialiasing::Jittered => "5. Jittered 4x4",
+			Antialiasing::Adaptive => "6. Adaptive",
+		}
+	}
+}
+
+impl fmt::Display for Antialiasing {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for Antialiasing {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+/// # `Sample`: the `index`-th sub-pixel position of the pixels, row by row in their cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+	antialiasing: Antialiasing,
+	index: usize,
+}
+
+impl Sample {
+	/// The single sample, at the pixel.
+	pub fn center() -> Sample {
+		Sample { antialiasing: Antialiasing::Off, index: 0 }
+	}
+
+	/// Position of the sample of the pixel (`x`; `y`), for `geometry::position_from_pixel`.
+	/// Within half a pixel of it.
+	pub fn pixel(self: &Self, x: usize, y: usize) -> [f64; 2] {
+		let side: usize = self.antialiasing.side();
+		let cell: [usize; 2] = [self.index % side, (self.index / side) % side];
+		let inside: [f64; 2] = match self.antialiasing {
+			Antialiasing::Jittered => [
+				unit_hash(x, y, self.index, 0),
+				unit_hash(x, y, self.index, 1),
+			],
+			// R2 low discrepancy sequence: even for any count of samples, the first at the center.
+			Antialiasing::Adaptive => [
+				(0.5 + self.index as f64 * R2[0]).fract(),
+				(0.5 + self.index as f64 * R2[1]).fract(),
+			],
+			_ => [0.5, 0.5],
+		};
+
+		[
+			x as f64 + (cell[0] as f64 + inside[0]) / side as f64 - 0.5,
+			y as f64 + (cell[1] as f64 + inside[1]) / side as f64 - 0.5,
+		]
+	}
+}
+
+/// Steps of the R2 sequence: inverses of the plastic number and of its square.
+const R2: [f64; 2] = [0.7548776662466927, 0.5698402909980532];
+
+/// Pseudo-random number in [0; 1[ of a pixel, sample and `axis`: the same on each render.
+///
+/// Source: SplitMix64 finalizer.
+fn unit_hash(x: usize, y: usize, index: usize, axis: u64) -> f64 {
+	let mut hash: u64 = ((x as u64) << 40) ^ ((y as u64) << 16) ^ ((index as u64) << 1) ^ axis;
+	hash = hash.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	hash ^= hash >> 31;
+
+	(hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// # Supersample: `render` each sample of `antialiasing` into raw RGB `Data`, and average them.
+/// The colors are averaged in linear RGB, the iterations summed.
+///
+/// Without antialiasing, the single render is returned as is. `None` if a render is cancelled.
+pub fn supersample<R>(
+	antialiasing: Antialiasing,
+	control: &threading::Control,
+	mut render: R,
+) -> Option<computations::Data>
+where
+	R: FnMut(Sample) -> Option<computations::Data>,
+{
+	let samples: Vec<Sample> = antialiasing.samples();
+	if samples.len() == 1 {
+		return render(samples[0]);
+	}
+
+	let mut average: Average = Average::new();
+	let mut iterations_total: usize = 0;
+
+	for (index, sample) in samples.iter().enumerate() {
+		if index > 0 {
+			control.restart_progress();
+		}
+		let data: computations::Data = render(*sample)?;
+
+		average.add(&data.raw_pixels);
+		iterations_total += data.iterations_total;
+	}
+
+	Option::Some(computations::Data { raw_pixels: average.to_raw_pixels(), iterations_total })
+}
+
+/// # `Average` of raw RGB renders, in linear RGB.
+struct Average {
+	/// sRGB components to linear, once for all.
+	decode: Vec<f64>,
+	sums: Vec<f64>,
+	count: usize,
+}
+
+impl Average {
+	fn new() -> Average {
+		Average {
+			decode: (0..=255u8)
+				.map(|component| color::LinearRgb::from_rgb(&color::Rgb::new(component, component, component)).red)
+				.collect(),
+			sums: Vec::new(),
+			count: 0,
+		}
+	}
+
+	fn add(self: &mut Self, raw_pixels: &[u8]) -> () {
+		if self.sums.is_empty() {
+			self.sums = vec![0.0; raw_pixels.len()];
+		}
+		for (sum, component) in self.sums.iter_mut().zip(raw_pixels.iter()) {
+			*sum += self.decode[*component as usize];
+		}
+		self.count += 1;
+	}
+
+	fn to_raw_pixels(self: &Self) -> Vec<u8> {
+		let count: f64 = self.count.max(1) as f64;
+
+		self.sums
+			.iter()
+			.map(|sum| {
+				let value: f64 = sum / count;
+				color::LinearRgb::new(value, value, value).to_rgb().red
+			})
+			.collect()
+	}
+}
+
+/// # `Adaptive` supersampling settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adaptive {
+	/// Contrast with a neighbor over which a pixel is sampled again: a difference of iterations.
+	/// Across the boundary of a set, or between two roots, it is infinite.
+	pub threshold: f64,
+	/// Samples of a contrasted pixel, the first one included.
+	pub samples: usize,
+}
+
+impl Default for Adaptive {
+	fn default() -> Self {
+		Adaptive { threshold: 1.0, samples: 16 }
+	}
+}
+
+/// # `Pixel` of a table, before coloring.
+pub trait Pixel {
+	/// Contrast with an `other` pixel, cf. `Adaptive::threshold`.
+	fn contrast(self: &Self, other: &Self) -> f64;
+
+	/// Iterations computed for the pixel, as counted by the colorings.
+	fn iterations(self: &Self, iterations_max: usize) -> usize;
+}
+
+impl Pixel for computations::State {
+	fn contrast(self: &Self, other: &Self) -> f64 {
+		match (escaped(self), escaped(other)) {
+			(Option::Some(first), Option::Some(second)) => (first as f64 - second as f64).abs(),
+			(Option::Some(_), Option::None) | (Option::None, Option::Some(_)) => f64::INFINITY,
+			(Option::None, Option::None) => match (self, other) {
+				(
+					computations::State::Periodic { period: first, .. },
+					computations::State::Periodic { period: second, .. },
+				) if first != second => f64::INFINITY,
+				_ => 0.0,
+			},
+		}
+	}
+
+	fn iterations(self: &Self, iterations_max: usize) -> usize {
+		match self {
+			computations::State::Divergent { iterations, .. }
+			| computations::State::Estimated { iterations, .. }
+			| computations::State::Periodic { iterations, .. } => *iterations,
+			computations::State::Trapped { escaped, .. } => escaped.unwrap_or(iterations_max),
+			computations::State::Stable => iterations_max,
+		}
+	}
+}
+
+/// Iterations to escape of a `state`, `None` if it remained bounded.
+fn escaped(state: &computations::State) -> Option<usize> {
+	match state {
+		computations::State::Divergent { iterations, .. }
+		| computations::State::Estimated { iterations, .. } => Option::Some(*iterations),
+		computations::State::Trapped { escaped, .. } => *escaped,
+		computations::State::Stable | computations::State::Periodic { .. } => Option::None,
+	}
+}
+
+impl Pixel for computations::IsRoot {
+	fn contrast(self: &Self, other: &Self) -> f64 {
+		match (self, other) {
+			(
+				computations::IsRoot::Yes { root: first, iterations: first_iterations },
+				computations::IsRoot::Yes { root: second, iterations: second_iterations },
+			) if first == second => (*first_iterations as f64 - *second_iterations as f64).abs(),
+			(computations::IsRoot::No, computations::IsRoot::No) => 0.0,
+			_ => f64::INFINITY,
+		}
+	}
+
+	fn iterations(self: &Self, iterations_max: usize) -> usize {
+		match self {
+			computations::IsRoot::Yes { iterations, .. } => *iterations,
+			computations::IsRoot::No => iterations_max,
+		}
+	}
+}
+
+/// # `Resampling` of the contrasted pixels of a `base` table, computed at the pixels.
+/// Each extra sample is computed with the `refinement`, then `push`ed: only its contrasted
+/// pixels are kept, until all are colored and averaged.
+pub struct Resampling<T> {
+	adaptive: Adaptive,
+	base: Arc<Vec<Vec<T>>>,
+	mask: Arc<Vec<Vec<bool>>>,
+	/// Contrasted pixels of each extra sample, row by row.
+	values: Vec<Vec<T>>,
+}
+
+impl<T> Resampling<T>
+where
+	T: Pixel + Clone,
+{
+	pub fn new(adaptive: Adaptive, base: Vec<Vec<T>>) -> Resampling<T> {
+		let mask: Vec<Vec<bool>> = contrast_mask(&base, adaptive.threshold);
+
+		Resampling { adaptive, base: Arc::new(base), mask: Arc::new(mask), values: Vec::new() }
+	}
+
+	/// Count of the contrasted pixels.
+	pub fn count(self: &Self) -> usize {
+		self.mask.iter().map(|line| line.iter().filter(|contrasted| **contrasted).count()).sum()
+	}
+
+	/// The extra samples to compute, none without any contrasted pixel.
+	pub fn samples(self: &Self) -> Vec<Sample> {
+		match self.count() {
+			0 => Vec::new(),
+			_ => (1..self.adaptive.samples.max(1))
+				.map(|index| Sample { antialiasing: Antialiasing::Adaptive, index })
+				.collect(),
+		}
+	}
+
+	/// Pass computing the contrasted pixels only.
+	pub fn refinement(self: &Self) -> progressive::Refinement<T> {
+		progressive::Refinement::masked(self.base.clone(), self.mask.clone())
+	}
+
+	/// Keep the contrasted pixels of an extra sample's `grid`.
+	pub fn push(self: &mut Self, grid: Vec<Vec<T>>) -> () {
+		let mut values: Vec<T> = Vec::new();
+		for (line, mask_line) in grid.into_iter().zip(self.mask.iter()) {
+			for (value, contrasted) in line.into_iter().zip(mask_line.iter()) {
+				if *contrasted {
+					values.push(value);
+				}
+			}
+		}
+
+		self.values.push(values);
+	}
+
+	/// `color` the base table, then each sample over it, into raw RGB `Data`, and average them.
+	/// The iterations are the base ones plus those of the contrasted pixels.
+	pub fn average<C>(self: Self, iterations_max: usize, mut color: C) -> computations::Data
+	where
+		C: FnMut(Vec<Vec<T>>) -> computations::Data,
+	{
+		let base: computations::Data = color(self.base.as_ref().clone());
+		let mut average: Average = Average::new();
+		average.add(&base.raw_pixels);
+		let mut iterations_total: usize = base.iterations_total;
+
+		for values in self.values {
+			iterations_total += values.iter().map(|value| value.iterations(iterations_max)).sum::<usize>();
+
+			let mut values = values.into_iter();
+			let grid: Vec<Vec<T>> = self.base
+				.iter()
+				.zip(self.mask.iter())
+				.map(|(line, mask_line)| line
+					.iter()
+					.zip(mask_line.iter())
+					.map(|(base_value, contrasted)| match contrasted {
+						true => values.next().unwrap_or_else(|| base_value.clone()),
+						false => base_value.clone(),
+					})
+					.collect()
+				)
+				.collect();
+			average.add(&color(grid).raw_pixels);
+		}
+
+		computations::Data { raw_pixels: average.to_raw_pixels(), iterations_total }
+	}
+}
+
+/// Pixels of `grid` contrasted over `threshold` with a neighbor: both sides of an edge.
+fn contrast_mask<T>(grid: &Vec<Vec<T>>, threshold: f64) -> Vec<Vec<bool>>
+where
+	T: Pixel,
+{
+	let mut mask: Vec<Vec<bool>> = grid.iter().map(|line| vec![false; line.len()]).collect();
+
+	for y in 0..grid.len() {
+		for x in 0..grid[y].len() {
+			if x + 1 < grid[y].len() && grid[y][x].contrast(&grid[y][x + 1]) > threshold {
+				mask[y][x] = true;
+				mask[y][x + 1] = true;
+			}
+			if y + 1 < grid.len() && x < grid[y + 1].len() && grid[y][x].contrast(&grid[y + 1][x]) > threshold {
+				mask[y][x] = true;
+				mask[y + 1][x] = true;
+			}
+		}
+	}
+
+	mask
+}
+
+
+#[test]
+fn test_supersample() -> () {
+	// Samples are centered in their cells, around the pixel.
+	let positions: Vec<[f64; 2]> = Antialiasing::Grid2.samples().iter().map(|sample| sample.pixel(3, 5)).collect();
+	assert_eq!(positions, vec![[2.75, 4.75], [3.25, 4.75], [2.75, 5.25], [3.25, 5.25]]);
+	assert_eq!(Sample::center().pixel(3, 5), [3.0, 5.0]);
+	for sample in Antialiasing::Jittered.samples() {
+		let position: [f64; 2] = sample.pixel(3, 5);
+		assert!((position[0] - 3.0).abs() <= 0.5 && (position[1] - 5.0).abs() <= 0.5);
+	}
+
+	// Half black, half white: the linear average, not 128.
+	let data: computations::Data = supersample(
+		Antialiasing::Grid2,
+		&threading::Control::new(),
+		|sample: Sample| Option::Some(computations::Data {
+			raw_pixels: vec![if sample.pixel(0, 0)[0] < 0.0 { 0 } else { 255 }; 3],
+			iterations_total: 1,
+		}),
+	).unwrap();
+	assert_eq!(data.raw_pixels, vec![188, 188, 188]);
+	assert_eq!(data.iterations_total, 4);
+}
+
+
+#[test]
+fn test_adaptive_resampling() -> () {
+	// A single edge, between the columns 1 and 2.
+	let base: Vec<Vec<computations::IsRoot>> = (0..3)
+		.map(|_| (0..4)
+			.map(|x| match x {
+				0 | 1 => computations::IsRoot::No,
+				_ => computations::IsRoot::Yes { root: complex::Algebraic::new(1.0, 0.0), iterations: 3 },
+			})
+			.collect()
+		)
+		.collect();
+	let mut resampling: Resampling<computations::IsRoot> = Resampling::new(Adaptive { threshold: 1.0, samples: 4 }, base);
+	assert_eq!(resampling.count(), 6);
+	assert_eq!(resampling.samples().len(), 3);
+	for sample in resampling.samples() {
+		let position: [f64; 2] = sample.pixel(3, 5);
+		assert!((position[0] - 3.0).abs() <= 0.5 && (position[1] - 5.0).abs() <= 0.5);
+	}
+
+	// Only the contrasted pixels are computed again: here, all found the root.
+	let tile: threading::Tile = threading::Tile { id: 0, start: [0, 0], end: [4, 3] };
+	let mut computed: usize = 0;
+	for _ in resampling.samples() {
+		let grid: Vec<Vec<computations::IsRoot>> = resampling.refinement().render_tile(&tile, |_, _| {
+			computed += 1;
+			computations::IsRoot::Yes { root: complex::Algebraic::new(1.0, 0.0), iterations: 3 }
+		});
+		resampling.push(grid);
+	}
+	assert_eq!(computed, 3 * 6);
+
+	let data: computations::Data = resampling.average(10, |grid: Vec<Vec<computations::IsRoot>>| computations::Data {
+		raw_pixels: grid.iter().flatten().map(|pixel| match pixel {
+			computations::IsRoot::No => 0,
+			computations::IsRoot::Yes { .. } => 255,
+		}).collect(),
+		iterations_total: grid.iter().flatten().map(|pixel| pixel.iterations(10)).sum(),
+	});
+	// The other pixels are untouched; 1 black sample out of 4 in the column 1.
+	assert_eq!(&data.raw_pixels[0..4], &[0, 225, 255, 255]);
+	assert_eq!(data.iterations_total, 3 * (10 + 10 + 3 + 3) + 3 * 6 * 3);
+}
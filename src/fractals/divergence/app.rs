@@ -20,8 +20,6 @@ pub fn draw<F>(
 	ui: &imgui::Ui,
 	// Rc<RefCell<Divergent<impl Fn(Algebraic, Algebraic) -> Algebraic>>>
 	divergent_texture: rc::Rc<cell::RefCell<fractals::divergence::Divergent<F>>>,
-	renderer: &mut imgui_glium_renderer::Renderer, 
-	display: &glium::Display<glium::glutin::surface::WindowSurface>,
 ) -> () 
 where 
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Copy + Send + 'static,
@@ -40,8 +38,6 @@ where
 		settings_state.clone(),
 		ui, 
 		divergent_texture.clone(), 
-		renderer, 
-		display,
 	);
 }
 
@@ -61,15 +57,19 @@ where
 		.borrow_mut()
 		.update_size(window_size);
 
-	// If a setting change, draw the fractal anew.
+	// If a setting change, draw the fractal anew, in the background.
 	if divergent_texture.borrow_mut().is_state_updated() {
 		divergent_texture
 			.borrow_mut()
-			.register_texture(
-				display.get_context(), 
-				global_settings.clone(),
-				renderer.textures(), 
-			)
-			.expect("(!) gui::default::launch_default() Divergent: update: can't register texture.");
+			.start_render(global_settings.clone());
 	}
+
+	// Show the render once it is done.
+	divergent_texture
+		.borrow_mut()
+		.register_texture(
+			display.get_context(), 
+			renderer.textures(), 
+		)
+		.expect("(!) gui::default::launch_default() Divergent: update: can't register texture.");
 }
@@ -3,14 +3,69 @@
 //! 
 //! Compute limits of sequences, and determine if they are divergent.  
 
-use std::thread;
-use std::sync::mpsc;
-
 use complex;
 use complex::Complex;
 
 use crate::structures::computations;
-use crate::fractals::{geometry, threading};
+use crate::expressions;
+use crate::expressions::functions;
+use crate::fractals::{antialiasing, geometry, progressive, threading};
+use crate::fractals::divergence::{method, trap};
+
+/// Distance under which two iterates are the same point of a cycle.
+const PERIOD_TOLERANCE: complex::Real = 1e-10;
+/// Relative step of the numerical derivative of `f`.
+const DERIVATIVE_STEP: complex::Real = 1e-6;
+
+/// # `Derivative` of the function f(z, c) of a sequence: its partial derivatives ∂f/∂z and ∂f/∂c.
+#[derive(Debug, Clone)]
+pub enum Derivative {
+	/// z^n + c: n z^(n - 1) and 1, in closed form.
+	Power(u32),
+	/// Derived symbolically from the formula.
+	Symbolic { z: expressions::Expression, c: expressions::Expression },
+	/// Central differences, for opaque functions only: about 1e-6 of relative error.
+	Numerical,
+}
+
+impl Derivative {
+	/// Exact derivatives of `expression`, `Numerical` if it has none (`conj`, `abs`).
+	pub fn new(expression: &expressions::Expression) -> Derivative {
+		if let Option::Some(degree) = expression.power_of_z_plus_c() {
+			return Derivative::Power(degree);
+		}
+
+		match (expression.derivative(), expression.derivative_c()) {
+			(Result::Ok(z), Result::Ok(c)) => Derivative::Symbolic { z, c },
+			_ => Derivative::Numerical,
+		}
+	}
+
+	/// ∂f/∂z at (`z`; `c`).
+	fn z<F>(self: &Self, f: &F, z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic
+	where
+		F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+	{
+		match self {
+			Derivative::Power(degree) => complex::Algebraic::new(*degree as complex::Real, 0.0)
+				* functions::power_integer(z, *degree as i32 - 1),
+			Derivative::Symbolic { z: derivative, .. } => derivative.evaluate(z, c),
+			Derivative::Numerical => derivative_z(f, z, c),
+		}
+	}
+
+	/// ∂f/∂c at (`z`; `c`).
+	fn c<F>(self: &Self, f: &F, z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic
+	where
+		F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+	{
+		match self {
+			Derivative::Power(_) => complex::Algebraic::new(1.0, 0.0),
+			Derivative::Symbolic { c: derivative, .. } => derivative.evaluate(z, c),
+			Derivative::Numerical => derivative_c(f, z, c),
+		}
+	}
+}
 
 /// # `Limit` of `f`. 
 /// Compute a recursive sequence `iteration` times, with z0 = `z`:
@@ -22,6 +77,12 @@ use crate::fractals::{geometry, threading};
 /// To find if |z| > `threshold` or remains stable.
 /// 
 /// `c` remains constant, and `z0` defines the first value taken by the sequence.
+/// `derivative` holds the partial derivatives of `f`.
+/// 
+/// The `recording` chooses what is kept of the orbit:
+/// - `Escape`: bounded orbits are stopped as soon as they cycle, `State::Periodic`,
+/// - `Trap`: its closest approach to the `trap`, `State::Trapped`,
+/// - `Distance`: the distance to the boundary of the set of `method`, `State::Estimated`.
 ///
 /// ## Example
 /// Mandelbrot with f(z) = z² + c 
@@ -29,24 +90,203 @@ fn limit<F>(
 	c: complex::Algebraic, 
 	z0: complex::Algebraic, 
 	f: F, 
+	derivative: &Derivative,
 	threshold: complex::Real, 
 	iterations: usize,
+	recording: method::Recording,
+	trap: trap::OrbitTrap,
+	method: method::LimitMethod,
 ) -> computations::State 
 where
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
 {
+	match recording {
+		method::Recording::Trap => return limit_trapped(c, z0, f, threshold, iterations, trap),
+		method::Recording::Distance => return limit_estimated(c, z0, f, derivative, threshold, iterations, method),
+		method::Recording::Escape => {},
+	}
+
+	let threshold_squared: complex::Real = threshold * threshold;
+	let tolerance_squared: complex::Real = PERIOD_TOLERANCE * PERIOD_TOLERANCE;
 	let mut current: complex::Algebraic = z0;
 	let mut counter: usize = 0;
+	// Brent's cycle detection: each iterate is compared to `saved`, moved every `power` iterates,
+	// by their squared distance, as in the batched kernel.
+	let mut saved: complex::Algebraic = z0;
+	let mut power: usize = 1;
+	let mut lambda: usize = 0;
 
-	while counter < iterations && current.absolute() <= threshold {
+	while counter < iterations && current.absolute_squared() <= threshold_squared {
 		current = f(current, c);
 		counter += 1;
+		lambda += 1;
+
+		if (current - saved).absolute_squared() < tolerance_squared {
+			return computations::State::Periodic {
+				iterations: counter,
+				period: lambda,
+				multiplier: multiplier(&f, derivative, current, c, lambda),
+			};
+		}
+		if lambda == power {
+			saved = current;
+			power *= 2;
+			lambda = 0;
+		}
 	}
 
-	if current.absolute() <= threshold {
+	if current.absolute_squared() <= threshold_squared {
 		computations::State::Stable
 	} else {
-		computations::State::Divergent { iterations: counter }
+		computations::State::Divergent { iterations: counter, modulus: current.absolute() }
+	}
+}
+
+/// Partial derivative ∂f/∂z at (`z`; `c`), by central differences.
+fn derivative_z<F>(f: &F, z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let step: complex::Algebraic = complex::Algebraic::new(DERIVATIVE_STEP * z.absolute().max(1.0), 0.0);
+
+	(f(z + step, c) - f(z - step, c)) / (complex::Algebraic::new(2.0, 0.0) * step)
+}
+
+/// Partial derivative ∂f/∂c at (`z`; `c`), by central differences.
+fn derivative_c<F>(f: &F, z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let step: complex::Algebraic = complex::Algebraic::new(DERIVATIVE_STEP * c.absolute().max(1.0), 0.0);
+
+	(f(z, c + step) - f(z, c - step)) / (complex::Algebraic::new(2.0, 0.0) * step)
+}
+
+/// # `Multiplier` of a cycle.
+/// Modulus of the derivative of `f` composed `period` times, along the cycle through `z`:
+/// ```math
+/// |(fᵖ)'(z)| = |f'(z(0)) * f'(z(1)) * ... * f'(z(p - 1))|
+/// ```
+/// The exact `derivative` ∂f/∂z is accumulated along the cycle.
+fn multiplier<F>(
+	f: &F,
+	derivative: &Derivative,
+	z: complex::Algebraic,
+	c: complex::Algebraic,
+	period: usize,
+) -> complex::Real
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let mut current: complex::Algebraic = z;
+	let mut product: complex::Algebraic = complex::Algebraic::new(1.0, 0.0);
+
+	for _ in 0..period {
+		product = product * derivative.z(f, current, c);
+		current = f(current, c);
+	}
+
+	product.absolute()
+}
+
+/// # `Limit` of `f`, through an orbit `trap`.
+/// Same sequence as `limit`, keeping the iterate closest to the `trap`.
+fn limit_trapped<F>(
+	c: complex::Algebraic, 
+	z0: complex::Algebraic, 
+	f: F, 
+	threshold: complex::Real, 
+	iterations: usize,
+	trap: trap::OrbitTrap,
+) -> computations::State 
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let mut current: complex::Algebraic = z0;
+	let mut counter: usize = 0;
+	let mut distance: f64 = f64::INFINITY;
+	let mut closest: usize = 0;
+
+	while counter < iterations && current.absolute() <= threshold {
+		current = f(current, c);
+		counter += 1;
+
+		let current_distance: f64 = trap.distance(current);
+		if current_distance < distance {
+			distance = current_distance;
+			closest = counter;
+		}
+	}
+
+	computations::State::Trapped {
+		distance,
+		closest,
+		escaped: match current.absolute() <= threshold {
+			true => Option::None,
+			false => Option::Some(counter),
+		},
+	}
+}
+
+/// # `Limit` of `f`, with the distance estimation.
+/// Same sequence as `limit`, along with its derivative with respect to the pixel, from the exact partial
+/// `derivative` of `f`:
+/// ```math
+/// Mandelbrot: p'(0) = 0, p'(n + 1) = ∂f/∂z p'(n) + ∂f/∂c
+/// Julia:      p'(0) = 1, p'(n + 1) = ∂f/∂z p'(n)
+/// ```
+/// Once escaped, the distance to the boundary is about `|p| ln|p| / (2 |p'|)`,
+/// and `p / p'` is normal to the equipotential lines.
+fn limit_estimated<F>(
+	c: complex::Algebraic, 
+	z0: complex::Algebraic, 
+	f: F, 
+	derivative_f: &Derivative,
+	threshold: complex::Real, 
+	iterations: usize,
+	method: method::LimitMethod,
+) -> computations::State 
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let mut current: complex::Algebraic = z0;
+	let mut derivative: complex::Algebraic = match method {
+		method::LimitMethod::Mandelbrot => complex::Algebraic::new(0.0, 0.0),
+		method::LimitMethod::Julia => complex::Algebraic::new(1.0, 0.0),
+	};
+	let mut counter: usize = 0;
+
+	while counter < iterations && current.absolute() <= threshold {
+		derivative = match method {
+			method::LimitMethod::Mandelbrot => derivative_f.z(&f, current, c) * derivative + derivative_f.c(&f, current, c),
+			method::LimitMethod::Julia => derivative_f.z(&f, current, c) * derivative,
+		};
+		current = f(current, c);
+		counter += 1;
+	}
+
+	let modulus: complex::Real = current.absolute();
+	if modulus <= threshold {
+		return computations::State::Stable;
+	}
+
+	let slope: complex::Real = derivative.absolute();
+	if slope <= 0.0 {
+		return computations::State::Estimated {
+			iterations: counter,
+			modulus,
+			distance: complex::Real::INFINITY,
+			normal: complex::Algebraic::new(0.0, 0.0),
+		};
+	}
+
+	let normal: complex::Algebraic = current / derivative;
+	let length: complex::Real = normal.absolute();
+	computations::State::Estimated {
+		iterations: counter,
+		modulus,
+		distance: modulus * modulus.ln() / (2.0 * slope),
+		normal: complex::Algebraic::new(normal.real / length, normal.imaginary / length),
 	}
 }
 
@@ -55,7 +295,9 @@ where
 // =========================
 
 /// # Limit for each in point of screen.
-/// Compute the limit for each point in `size` [width, height].
+/// Compute the limit for each point in `size` [width, height],
+/// by tiles on the `threading::pool()`, for the `refinement` pass at the sub-pixel `sample`.
+/// `None` if `control` is cancelled.
 /// 
 /// It is Mandelbrot-like, which is:
 /// - `z0`: Complex
@@ -66,130 +308,86 @@ where
 pub fn limit_on_screen_mandelbrot<F>(
 	z0: complex::Algebraic,
 	f: F,
+	derivative: Derivative,
 	threshold: complex::Real, 
 	iterations: usize,
+	recording: method::Recording,
+	trap: trap::OrbitTrap,
 	size: [usize; 2],
 	position: [complex::Real; 2],
 	zoom: complex::Real,
 	thread_count: usize,
-) -> Vec<Vec<computations::State>>
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
+	control: &threading::Control,
+) -> Option<threading::Tiled<computations::State>>
 where
-	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic+ Clone + Send + 'static,
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
 {
-	let mut threads: Vec<thread::JoinHandle<()>> = Vec::with_capacity(thread_count);
-	let (sender, receiver) = mpsc::channel();
-
-	let mut grid: Vec<Vec<computations::State>> = Vec::with_capacity(size[1]);
 	let screen_size: [complex::Real; 2] = [size[0] as complex::Real, size[1] as complex::Real];
-	
-	let mut sub_grids: Vec<Vec<Vec<computations::State>>> = Vec::with_capacity(thread_count);
-	for _ in 0..thread_count {
-		sub_grids.push(Vec::new());
-	}
-
-	// Spawn threads.
-	for thread_id in 0..thread_count {
-		let sender_local = sender.clone();
-		let f_local: F = f.clone();
-
-		let handler: thread::JoinHandle<()> = thread::spawn(move || {
-			let id: usize = thread_id;
-			let start: [usize; 2] = [0, size[1] * thread_id / thread_count];
-			let end: [usize; 2] = [size[0], size[1] * (thread_id + 1) / thread_count];
-			
-			let sub_grid: Vec<Vec<computations::State>> = limit_on_screen_mandelbrot_part(
-				z0, 
-				f_local, 
-				threshold, 
-				iterations, 
-				screen_size, 
-				start, 
-				end, 
-				position, 
-				zoom
-			);
-
-			let result: threading::GenerationPart<computations::State> = threading::GenerationPart::new(
-				id,
-				(start, end),
-				sub_grid,
-			);
-			sender_local
-				.send(result)
-				.expect(&format!("(X) divergence::maths::limit_on_screen_julia() Couldn't send payload."));
-		});
-
-		threads.push(handler);
-	}	
-
-	// Collect results.
-	for iteration in 0..thread_count {
-		let result = receiver
-			.recv()
-			.expect(&format!(
-				"(X) divergence::maths::limit_on_screen_julia() Couldn't receive payload for iteration={}.", 
-				iteration
-			));
-		
-		sub_grids[result.thread_id] = result.data;
-	}
-
-	// Aggregate results.
-	for thread_id in 0..thread_count {
-		let sub_grid: &Vec<Vec<computations::State>> = &sub_grids[thread_id];
-
-		for y in 0..sub_grid.len() {
-			grid.push(sub_grid[y].clone());
-		}
-	}
-
-	grid
+	let refinement: progressive::Refinement<computations::State> = refinement.clone();
+
+	threading::render_tiles(size, thread_count, control, move |tile: &threading::Tile| {
+		limit_on_screen_mandelbrot_part(
+			z0, 
+			&f, 
+			&derivative,
+			threshold, 
+			iterations, 
+			recording, 
+			trap, 
+			screen_size, 
+			tile, 
+			&refinement, 
+			sample, 
+			position, 
+			zoom
+		)
+	})
 }
 
 
-/// Compute a Mandelbrot screen part. Division for each threads, in `limit_on_screen_mandelbrot`.
+/// Compute a Mandelbrot screen part. Division in tiles, in `limit_on_screen_mandelbrot`.
 /// 
-/// Draw only a rectangle from point `stat` to `end`.
+/// Draw only the `tile`, at the resolution of the `refinement` pass.
 fn limit_on_screen_mandelbrot_part<F>(
 	z0: complex::Algebraic,
-	f: F,
+	f: &F,
+	derivative: &Derivative,
 	threshold: complex::Real, 
 	iterations: usize,
+	recording: method::Recording,
+	trap: trap::OrbitTrap,
 	screen_size: [complex::Real; 2],
-	start: [usize; 2],
-	end: [usize; 2],
+	tile: &threading::Tile,
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
 	position: [complex::Real; 2],
 	zoom: complex::Real,
 ) -> Vec<Vec<computations::State>>
 where
-	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
 {
-	let size: [usize; 2] = [end[0] - start[0], end[1] - start[1]];
-	let mut grid: Vec<Vec<computations::State>> = Vec::with_capacity(size[1]);
-	
-
-	for y in start[1]..end[1] {
-		let mut line: Vec<computations::State> = Vec::with_capacity(size[0]); 
-		for x in start[0]..end[0] {
-			let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
-				[x as complex::Real, y as complex::Real], 
-				screen_size, 
-				zoom, 
-				position
-			);
-			line.push(limit(
-				complex::Algebraic::new(complex_position[0], complex_position[1]),
-				z0,
-				&f,
-				threshold,
-				iterations,
-			))
-		}
-
-		grid.push(line);
-	}
-
-	grid
+	refinement.render_tile(tile, |x: usize, y: usize| {
+		let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
+			sample.pixel(x, y), 
+			screen_size, 
+			zoom, 
+			position
+		);
+
+		limit(
+			complex::Algebraic::new(complex_position[0], complex_position[1]),
+			z0,
+			f,
+			derivative,
+			threshold,
+			iterations,
+			recording,
+			trap,
+			method::LimitMethod::Mandelbrot,
+		)
+	})
 }
 
 
@@ -198,7 +396,9 @@ where
 // =========================
 
 /// # Limit for each in point of screen.
-/// Compute the limit for each point in `size` [width, height].
+/// Compute the limit for each point in `size` [width, height],
+/// by tiles on the `threading::pool()`, for the `refinement` pass at the sub-pixel `sample`.
+/// `None` if `control` is cancelled.
 /// 
 /// It is Julia-like, which is:
 /// - `z`: Complex
@@ -209,130 +409,584 @@ where
 pub fn limit_on_screen_julia<F>(
 	c: complex::Algebraic,
 	f: F,
+	derivative: Derivative,
 	threshold: complex::Real, 
 	iterations: usize,
+	recording: method::Recording,
+	trap: trap::OrbitTrap,
 	size: [usize; 2],
 	position: [complex::Real; 2],
 	zoom: complex::Real,
 	thread_count: usize,
-) -> Vec<Vec<computations::State>>
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
+	control: &threading::Control,
+) -> Option<threading::Tiled<computations::State>>
 where
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
 {
-	let mut threads: Vec<thread::JoinHandle<()>> = Vec::with_capacity(thread_count);
-	let (sender, receiver) = mpsc::channel();
-
-	let mut grid: Vec<Vec<computations::State>> = Vec::with_capacity(size[1]);
 	let screen_size: [complex::Real; 2] = [size[0] as complex::Real, size[1] as complex::Real];
-	
-	let mut sub_grids: Vec<Vec<Vec<computations::State>>> = Vec::with_capacity(thread_count);
-	for _ in 0..thread_count {
-		sub_grids.push(Vec::new());
-	}
-
-	// Spawn threads.
-	for thread_id in 0..thread_count {
-		let sender_local = sender.clone();
-		let f_local: F = f.clone();
-
-		let handler: thread::JoinHandle<()> = thread::spawn(move || {
-			let id: usize = thread_id;
-			let start: [usize; 2] = [0, size[1] * thread_id / thread_count];
-			let end: [usize; 2] = [size[0], size[1] * (thread_id + 1) / thread_count];
-			
-			let sub_grid: Vec<Vec<computations::State>> = limit_on_screen_julia_part(
-				c, 
-				f_local, 
-				threshold, 
-				iterations, 
-				screen_size, 
-				start, 
-				end, 
-				position, 
-				zoom
-			);
-
-			let result = threading::GenerationPart::new(
-				id,
-				(start, end),
-				sub_grid,
-			);
-			sender_local
-				.send(result)
-				.expect(&format!(
-					"(X) divergence::maths::limit_on_screen_julia() Couldn't send payload."
-				));
-		});
-
-		threads.push(handler);
-	}	
-
-	// Collect results.
-	for iteration in 0..thread_count {
-		let result = receiver
-			.recv()
-			.expect(&format!(
-				"(X) divergence::maths::limit_on_screen_julia() Couldn't receive payload for iteration={}.", 
-				iteration
-			));
-		
-		sub_grids[result.thread_id] = result.data;
-	}
-
-	// Aggregate results.
-	for thread_id in 0..thread_count {
-		let sub_grid: &Vec<Vec<computations::State>> = &sub_grids[thread_id];
-
-		for y in 0..sub_grid.len() {
-			grid.push(sub_grid[y].clone());
-		}
-	}
-
-	grid	
+	let refinement: progressive::Refinement<computations::State> = refinement.clone();
+
+	threading::render_tiles(size, thread_count, control, move |tile: &threading::Tile| {
+		limit_on_screen_julia_part(
+			c, 
+			&f, 
+			&derivative,
+			threshold, 
+			iterations, 
+			recording, 
+			trap, 
+			screen_size, 
+			tile, 
+			&refinement, 
+			sample, 
+			position, 
+			zoom
+		)
+	})
 }
 
-/// Compute a Julia screen part. Division for each threads.
+/// Compute a Julia screen part. Division in tiles, in `limit_on_screen_julia`.
 /// 
-/// Draw only a rectangle from point `stat` to `end`.
+/// Draw only the `tile`, at the resolution of the `refinement` pass.
 fn limit_on_screen_julia_part<F>(
 	c: complex::Algebraic,
-	f: F,
+	f: &F,
+	derivative: &Derivative,
 	threshold: complex::Real, 
 	iterations: usize,
+	recording: method::Recording,
+	trap: trap::OrbitTrap,
 	screen_size: [complex::Real; 2],
-	start: [usize; 2],
-	end: [usize; 2],
+	tile: &threading::Tile,
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
 	position: [complex::Real; 2],
 	zoom: complex::Real,
 ) -> Vec<Vec<computations::State>>
 where
-	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Send + 'static,
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
 {
-	let size: [usize; 2] = [end[0] - start[0], end[1] - start[1]];
-	let mut grid: Vec<Vec<computations::State>> = Vec::with_capacity(size[1]);
-
-	for y in start[1]..end[1] {
-		let mut line: Vec<computations::State> = Vec::with_capacity(size[0]); 
-		for x in start[0]..end[0] {
-			let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
-				[x as complex::Real, y as complex::Real], 
-				screen_size, 
-				zoom, 
-				position,
-			);
-			line.push(limit(
-				c,
-				complex::Algebraic::new(
-					complex_position[0], 
-					complex_position[1]
-				),
-				&f,
-				threshold,
-				iterations,
-			))
+	refinement.render_tile(tile, |x: usize, y: usize| {
+		let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
+			sample.pixel(x, y), 
+			screen_size, 
+			zoom, 
+			position,
+		);
+
+		limit(
+			c,
+			complex::Algebraic::new(
+				complex_position[0], 
+				complex_position[1]
+			),
+			f,
+			derivative,
+			threshold,
+			iterations,
+			recording,
+			trap,
+			method::LimitMethod::Julia,
+		)
+	})
+}
+
+
+// =========================
+// BATCHED KERNEL.
+// =========================
+
+/// Pixels iterated together by the batched kernel. 
+pub const LANES: usize = 8;
+
+/// Reals of the `LANES` pixels of a batch.
+type Lanes = [complex::Real; LANES];
+
+/// # Arithmetic of `Lanes`, in SSE2 registers of two reals.
+/// SSE2 is part of every x86_64 target: same IEEE operations as the scalar ones, lane by lane.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+mod lanes {
+	use std::arch::x86_64;
+
+	use super::{Lanes, LANES};
+
+	// Whole registers of two lanes.
+	const _: () = assert!(LANES.is_multiple_of(2));
+
+	pub fn add(first: &Lanes, second: &Lanes) -> Lanes {
+		let mut result: Lanes = [0.0; LANES];
+		for lane in (0..LANES).step_by(2) {
+			// SAFETY: SSE2 is enabled by the `cfg`, and `lane + 1` is within the arrays.
+			unsafe {
+				let sum: x86_64::__m128d = x86_64::_mm_add_pd(
+					x86_64::_mm_loadu_pd(first.as_ptr().add(lane)),
+					x86_64::_mm_loadu_pd(second.as_ptr().add(lane)),
+				);
+				x86_64::_mm_storeu_pd(result.as_mut_ptr().add(lane), sum);
+			}
+		}
+
+		result
+	}
+
+	pub fn subtract(first: &Lanes, second: &Lanes) -> Lanes {
+		let mut result: Lanes = [0.0; LANES];
+		for lane in (0..LANES).step_by(2) {
+			// SAFETY: SSE2 is enabled by the `cfg`, and `lane + 1` is within the arrays.
+			unsafe {
+				let difference: x86_64::__m128d = x86_64::_mm_sub_pd(
+					x86_64::_mm_loadu_pd(first.as_ptr().add(lane)),
+					x86_64::_mm_loadu_pd(second.as_ptr().add(lane)),
+				);
+				x86_64::_mm_storeu_pd(result.as_mut_ptr().add(lane), difference);
+			}
+		}
+
+		result
+	}
+
+	pub fn multiply(first: &Lanes, second: &Lanes) -> Lanes {
+		let mut result: Lanes = [0.0; LANES];
+		for lane in (0..LANES).step_by(2) {
+			// SAFETY: SSE2 is enabled by the `cfg`, and `lane + 1` is within the arrays.
+			unsafe {
+				let product: x86_64::__m128d = x86_64::_mm_mul_pd(
+					x86_64::_mm_loadu_pd(first.as_ptr().add(lane)),
+					x86_64::_mm_loadu_pd(second.as_ptr().add(lane)),
+				);
+				x86_64::_mm_storeu_pd(result.as_mut_ptr().add(lane), product);
+			}
+		}
+
+		result
+	}
+
+	/// Mask of the lanes greater than `bound`, from the sign bits of the comparison.
+	pub fn greater(values: &Lanes, bound: f64) -> [bool; LANES] {
+		let mut mask: [bool; LANES] = [false; LANES];
+		for lane in (0..LANES).step_by(2) {
+			// SAFETY: SSE2 is enabled by the `cfg`, and `lane + 1` is within the arrays.
+			let bits: i32 = unsafe {
+				x86_64::_mm_movemask_pd(x86_64::_mm_cmpgt_pd(
+					x86_64::_mm_loadu_pd(values.as_ptr().add(lane)),
+					x86_64::_mm_set1_pd(bound),
+				))
+			};
+			mask[lane] = bits & 1 != 0;
+			mask[lane + 1] = bits & 2 != 0;
+		}
+
+		mask
+	}
+
+	/// Mask of the lanes less than `bound`, from the sign bits of the comparison.
+	pub fn less(values: &Lanes, bound: f64) -> [bool; LANES] {
+		let mut mask: [bool; LANES] = [false; LANES];
+		for lane in (0..LANES).step_by(2) {
+			// SAFETY: SSE2 is enabled by the `cfg`, and `lane + 1` is within the arrays.
+			let bits: i32 = unsafe {
+				x86_64::_mm_movemask_pd(x86_64::_mm_cmplt_pd(
+					x86_64::_mm_loadu_pd(values.as_ptr().add(lane)),
+					x86_64::_mm_set1_pd(bound),
+				))
+			};
+			mask[lane] = bits & 1 != 0;
+			mask[lane + 1] = bits & 2 != 0;
+		}
+
+		mask
+	}
+}
+
+/// # Arithmetic of `Lanes`, lane by lane, for the targets without SSE2.
+#[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+mod lanes {
+	use super::{Lanes, LANES};
+
+	pub fn add(first: &Lanes, second: &Lanes) -> Lanes {
+		std::array::from_fn(|lane: usize| first[lane] + second[lane])
+	}
+
+	pub fn subtract(first: &Lanes, second: &Lanes) -> Lanes {
+		std::array::from_fn(|lane: usize| first[lane] - second[lane])
+	}
+
+	pub fn multiply(first: &Lanes, second: &Lanes) -> Lanes {
+		std::array::from_fn(|lane: usize| first[lane] * second[lane])
+	}
+
+	pub fn greater(values: &Lanes, bound: f64) -> [bool; LANES] {
+		std::array::from_fn(|lane: usize| values[lane] > bound)
+	}
+
+	pub fn less(values: &Lanes, bound: f64) -> [bool; LANES] {
+		std::array::from_fn(|lane: usize| values[lane] < bound)
+	}
+}
+
+/// # `Batch` of `LANES` complex numbers, iterated together in the `lanes`:
+/// SSE2 on x86_64, scalar elsewhere.
+#[derive(Debug, Clone, Copy)]
+struct Batch {
+	real: Lanes,
+	imaginary: Lanes,
+}
+
+impl Batch {
+	fn splat(z: complex::Algebraic) -> Batch {
+		Batch { real: [z.real; LANES], imaginary: [z.imaginary; LANES] }
+	}
+
+	/// The `LANES` first of `numbers`, padded with zeros.
+	fn from_slice(numbers: &[complex::Algebraic]) -> Batch {
+		let mut batch: Batch = Batch::splat(complex::Algebraic::new(0.0, 0.0));
+		for (lane, z) in numbers.iter().take(LANES).enumerate() {
+			batch.real[lane] = z.real;
+			batch.imaginary[lane] = z.imaginary;
+		}
+
+		batch
+	}
+
+	fn get(self: &Self, lane: usize) -> complex::Algebraic {
+		complex::Algebraic::new(self.real[lane], self.imaginary[lane])
+	}
+
+	fn add(self: &Self, other: &Batch) -> Batch {
+		Batch {
+			real: lanes::add(&self.real, &other.real),
+			imaginary: lanes::add(&self.imaginary, &other.imaginary),
+		}
+	}
+
+	fn subtract(self: &Self, other: &Batch) -> Batch {
+		Batch {
+			real: lanes::subtract(&self.real, &other.real),
+			imaginary: lanes::subtract(&self.imaginary, &other.imaginary),
+		}
+	}
+
+	/// (a + ib)(c + id) = ac - bd + i(ad + bc), rounded as the product of `complex::Algebraic`.
+	fn multiply(self: &Self, other: &Batch) -> Batch {
+		Batch {
+			real: lanes::subtract(
+				&lanes::multiply(&self.real, &other.real),
+				&lanes::multiply(&self.imaginary, &other.imaginary),
+			),
+			imaginary: lanes::add(
+				&lanes::multiply(&self.real, &other.imaginary),
+				&lanes::multiply(&self.imaginary, &other.real),
+			),
+		}
+	}
+
+	/// (a + ib)² = a² - b² + i(2a)b: 2ab rounded as ab + ba.
+	fn square(self: &Self) -> Batch {
+		Batch {
+			real: lanes::subtract(
+				&lanes::multiply(&self.real, &self.real),
+				&lanes::multiply(&self.imaginary, &self.imaginary),
+			),
+			imaginary: lanes::multiply(&lanes::add(&self.real, &self.real), &self.imaginary),
+		}
+	}
+
+	/// Power by binary exponentiation, as `functions::power_integer`.
+	fn power(self: &Self, degree: u32) -> Batch {
+		if degree == 2 {
+			return self.square();
+		}
+
+		let mut result: Batch = Batch::splat(complex::Algebraic::new(1.0, 0.0));
+		let mut base: Batch = *self;
+		let mut exponent: u32 = degree;
+
+		while exponent > 0 {
+			if exponent & 1 == 1 {
+				result = result.multiply(&base);
+			}
+			base = base.multiply(&base);
+			exponent >>= 1;
+		}
+
+		result
+	}
+
+	/// |z|², without square root.
+	fn norm(self: &Self) -> Lanes {
+		lanes::add(
+			&lanes::multiply(&self.real, &self.real),
+			&lanes::multiply(&self.imaginary, &self.imaginary),
+		)
+	}
+}
+
+/// # `Limit` of z^`degree` + c, for `LANES` pixels at once.
+/// Same sequences as `limit` with the `Escape` recording, from the lanes of `z0` and `c`: 
+/// moduli are compared squared, as in `limit`, into masks computed without branches. Only when a lane escapes
+/// or cycles, it is recorded, its multiplier computed along its cycle, and masked out, 
+/// until all of them are.
+fn limit_batch(
+	z0: Batch,
+	c: Batch,
+	degree: u32,
+	threshold: complex::Real,
+	iterations: usize,
+) -> [computations::State; LANES] {
+	let f = |z: complex::Algebraic, c: complex::Algebraic| functions::power_integer(z, degree as i32) + c;
+	let threshold_squared: complex::Real = threshold * threshold;
+	let tolerance_squared: complex::Real = PERIOD_TOLERANCE * PERIOD_TOLERANCE;
+	let derivative: Derivative = Derivative::Power(degree);
+
+	let mut states: [computations::State; LANES] = [computations::State::Stable; LANES];
+	let mut active: [bool; LANES] = [true; LANES];
+	let mut current: Batch = z0;
+	let mut counter: usize = 0;
+	// Brent's cycle detection, in step for all the lanes.
+	let mut saved: Batch = z0;
+	let mut power: usize = 1;
+	let mut lambda: usize = 0;
+
+	loop {
+		let outside: [bool; LANES] = lanes::greater(&current.norm(), threshold_squared);
+		let escaped: [bool; LANES] = std::array::from_fn(|lane: usize| active[lane] & outside[lane]);
+		if escaped.contains(&true) {
+			for lane in (0..LANES).filter(|lane: &usize| escaped[*lane]) {
+				states[lane] = computations::State::Divergent { iterations: counter, modulus: current.get(lane).absolute() };
+				active[lane] = false;
+			}
+		}
+		if counter >= iterations || !active.contains(&true) {
+			break;
+		}
+
+		current = current.power(degree).add(&c);
+		counter += 1;
+		lambda += 1;
+
+		let close: [bool; LANES] = lanes::less(&current.subtract(&saved).norm(), tolerance_squared);
+		let cycled: [bool; LANES] = std::array::from_fn(|lane: usize| active[lane] & close[lane]);
+		if cycled.contains(&true) {
+			for lane in (0..LANES).filter(|lane: &usize| cycled[*lane]) {
+				states[lane] = computations::State::Periodic {
+					iterations: counter,
+					period: lambda,
+					multiplier: multiplier(&f, &derivative, current.get(lane), c.get(lane), lambda),
+				};
+				active[lane] = false;
+			}
+		}
+		if lambda == power {
+			saved = current;
+			power *= 2;
+			lambda = 0;
 		}
+	}
+
+	// Still active: bounded.
+	states
+}
+
+/// # Limit for each in point of screen, of z^`degree` + c, by the batched kernel.
+/// Same as `limit_on_screen_mandelbrot` and `limit_on_screen_julia` with the `Escape` recording,
+/// for the sequences z^n + c: the pixels of a tile are iterated `LANES` at a time.
+/// `constant` is `z0` for `Mandelbrot`, `c` for `Julia`.
+pub fn limit_on_screen_power(
+	method: method::LimitMethod,
+	constant: complex::Algebraic,
+	degree: u32,
+	threshold: complex::Real, 
+	iterations: usize,
+	size: [usize; 2],
+	position: [complex::Real; 2],
+	zoom: complex::Real,
+	thread_count: usize,
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
+	control: &threading::Control,
+) -> Option<threading::Tiled<computations::State>> {
+	let screen_size: [complex::Real; 2] = [size[0] as complex::Real, size[1] as complex::Real];
+	let refinement: progressive::Refinement<computations::State> = refinement.clone();
+
+	threading::render_tiles(size, thread_count, control, move |tile: &threading::Tile| {
+		refinement.render_tile_batched(tile, |pixels: &[[usize; 2]]| {
+			let points: Vec<complex::Algebraic> = pixels
+				.iter()
+				.map(|[x, y]| {
+					let point: [complex::Real; 2] = geometry::position_from_pixel(
+						sample.pixel(*x, *y), 
+						screen_size, 
+						zoom, 
+						position,
+					);
+					complex::Algebraic::new(point[0], point[1])
+				})
+				.collect();
+
+			limit_points(method, constant, degree, threshold, iterations, &points)
+		})
+	})
+}
+
+/// # Limit of z^`degree` + c at each of the `points`, by the batched kernel.
+/// `constant` is `z0` for `Mandelbrot`, `c` for `Julia`.
+pub fn limit_points(
+	method: method::LimitMethod,
+	constant: complex::Algebraic,
+	degree: u32,
+	threshold: complex::Real,
+	iterations: usize,
+	points: &[complex::Algebraic],
+) -> Vec<computations::State> {
+	let constant: Batch = Batch::splat(constant);
+
+	points
+		.chunks(LANES)
+		.flat_map(|chunk: &[complex::Algebraic]| {
+			let batch: Batch = Batch::from_slice(chunk);
+			let states: [computations::State; LANES] = match method {
+				method::LimitMethod::Mandelbrot => limit_batch(constant, batch, degree, threshold, iterations),
+				method::LimitMethod::Julia => limit_batch(batch, constant, degree, threshold, iterations),
+			};
+			states.into_iter().take(chunk.len())
+		})
+		.collect()
+}
+
+#[test]
+fn test_limit_periodic() -> () {
+	let f = |z: complex::Algebraic, c: complex::Algebraic| z * z + c;
+	let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	let periodic = |c: complex::Real| match limit(
+		complex::Algebraic::new(c, 0.0), 
+		zero, 
+		f, 
+		&Derivative::Power(2),
+		2.0, 
+		1000, 
+		method::Recording::Escape, 
+		Default::default(), 
+		method::LimitMethod::Mandelbrot,
+	) {
+		computations::State::Periodic { period, multiplier, .. } => Option::Some((period, multiplier)),
+		_ => Option::None,
+	};
+
+	// Centers of the main cardioid and of the period 2 disk: superattracting.
+	assert_eq!(periodic(0.0), Option::Some((1, 0.0)));
+	assert_eq!(periodic(-1.0).map(|(period, _)| period), Option::Some(2));
+	assert!(periodic(-1.0).unwrap().1 < 1e-6);
+	// Fixed point z = (1 - sqrt(1 + 4 * 0.1)) / 2, of multiplier |2z|.
+	let (period, multiplier): (usize, complex::Real) = periodic(-0.1).unwrap();
+	assert_eq!(period, 1);
+	assert!((multiplier - (1.4_f64.sqrt() - 1.0)).abs() < 1e-9);
+	// Period 3 component, around c = -1.7549.
+	assert_eq!(periodic(-1.7549).map(|(period, _)| period), Option::Some(3));
+	assert!(periodic(1.0).is_none());
+}
+
+
+#[test]
+fn test_limit_estimated() -> () {
+	let f = |z: complex::Algebraic, c: complex::Algebraic| z * z + c;
+	let distance_by = |derivative: &Derivative, c: complex::Algebraic, z0: complex::Algebraic, method: method::LimitMethod| {
+		match limit_estimated(c, z0, f, derivative, 1e6, 100, method) {
+			computations::State::Estimated { distance, normal, .. } => {
+				assert!((normal.absolute() - 1.0).abs() < 1e-9);
+				distance
+			},
+			_ => panic!("(X) test_limit_estimated() Point not escaped."),
+		}
+	};
+	let distance = |c: complex::Algebraic, z0: complex::Algebraic, method: method::LimitMethod| {
+		distance_by(&Derivative::Power(2), c, z0, method)
+	};
+	let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+	// Closed form, symbolic and numerical derivatives of the same z² + c.
+	let symbolic: Derivative = Derivative::new(&expressions::parse("z^2 + 0.5c + 0.5c").unwrap());
+	assert!(matches!(symbolic, Derivative::Symbolic { .. }));
+	assert!(matches!(Derivative::new(&expressions::parse("z^3 + c").unwrap()), Derivative::Power(3)));
+	assert!(matches!(Derivative::new(&expressions::parse("conj(z)^2 + c").unwrap()), Derivative::Numerical));
+	let c: complex::Algebraic = complex::Algebraic::new(-0.7, 0.4);
+	let exact: complex::Real = distance(c, zero, method::LimitMethod::Mandelbrot);
+	assert!((distance_by(&symbolic, c, zero, method::LimitMethod::Mandelbrot) - exact).abs() < 1e-12 * exact);
+	assert!((distance_by(&Derivative::Numerical, c, zero, method::LimitMethod::Mandelbrot) - exact).abs() < 1e-4 * exact);
+
+	// Mandelbrot: c = 1 is 0.75 away from the cusp at 1/4. Within the bounds of the estimation.
+	let estimated: complex::Real = distance(complex::Algebraic::new(1.0, 0.0), zero, method::LimitMethod::Mandelbrot);
+	assert!(estimated > 0.75 / 4.0 && estimated < 0.75 * 4.0);
+	// Julia of c = 0, the unit circle: ln(2) from z0 = 2.
+	let estimated: complex::Real = distance(zero, complex::Algebraic::new(2.0, 0.0), method::LimitMethod::Julia);
+	assert!((estimated - 2.0_f64.ln()).abs() < 1e-6);
+}
+
+
+#[test]
+fn test_limit_on_screen_power() -> () {
+	let refinement: progressive::Refinement<computations::State> = progressive::Refinement::full();
+	let control: threading::Control = threading::Control::new();
+	let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+	for (degree, formula) in [(2, "z^2 + c"), (3, "c + z^3")] {
+		assert_eq!(crate::expressions::parse(formula).unwrap().power_of_z_plus_c(), Option::Some(degree));
+		let f = move |z: complex::Algebraic, c: complex::Algebraic| functions::power_integer(z, degree as i32) + c;
+		let generic: Vec<Vec<computations::State>> = limit_on_screen_mandelbrot(
+			zero, f, Derivative::Power(degree), 2.0, 200, method::Recording::Escape, Default::default(), [37, 23], [0.5, 0.0], 12.0, 2, 
+			&refinement, antialiasing::Sample::center(), &control,
+		).unwrap().grid;
+		let batched: Vec<Vec<computations::State>> = limit_on_screen_power(
+			method::LimitMethod::Mandelbrot, zero, degree, 2.0, 200, [37, 23], [0.5, 0.0], 12.0, 2, 
+			&refinement, antialiasing::Sample::center(), &control,
+		).unwrap().grid;
+
+		// Same states on every pixel: same operations, rounded the same.
+		for (y, (line, other)) in generic.iter().zip(batched.iter()).enumerate() {
+			for (x, (state, other)) in line.iter().zip(other.iter()).enumerate() {
+				assert_eq!(state, other, "z^{}, ({}; {}).", degree, x, y);
+			}
+		}
+	}
+	assert_eq!(crate::expressions::parse("z^2 + 2c").unwrap().power_of_z_plus_c(), Option::None);
+}
+
 
-		grid.push(line);
+#[test]
+#[ignore]
+fn bench_limit_on_screen_power() -> () {
+	// cargo test --release bench_limit_on_screen_power -- --ignored
+	let refinement: progressive::Refinement<computations::State> = progressive::Refinement::full();
+	let control: threading::Control = threading::Control::new();
+	let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	let f = |z: complex::Algebraic, c: complex::Algebraic| z * z + c;
+
+	for (position, zoom) in [([0.5, 0.0], 200.0), ([0.745, -0.11], 20000.0)] {
+		let start: std::time::Instant = std::time::Instant::now();
+		let scalar: Vec<Vec<computations::State>> = limit_on_screen_mandelbrot(
+			zero, f, Derivative::Power(2), 2.0, 2000, method::Recording::Escape, Default::default(), [800, 600], position, zoom, 1,
+			&refinement, antialiasing::Sample::center(), &control,
+		).unwrap().grid;
+		let scalar_time: std::time::Duration = start.elapsed();
+		let start: std::time::Instant = std::time::Instant::now();
+		let batched: Vec<Vec<computations::State>> = limit_on_screen_power(
+			method::LimitMethod::Mandelbrot, zero, 2, 2.0, 2000, [800, 600], position, zoom, 1,
+			&refinement, antialiasing::Sample::center(), &control,
+		).unwrap().grid;
+		let batched_time: std::time::Duration = start.elapsed();
+
+		let mismatches: usize = scalar
+			.iter()
+			.flatten()
+			.zip(batched.iter().flatten())
+			.filter(|(first, second)| first != second)
+			.count();
+		assert_eq!(mismatches, 0, "{:?}: {} mismatches.", position, mismatches);
+		assert!(
+			batched_time < scalar_time,
+			"{:?}: scalar {:?}, batched {:?}, x{:.2}.",
+			position, scalar_time, batched_time, scalar_time.as_secs_f64() / batched_time.as_secs_f64(),
+		);
 	}
-	grid
 }
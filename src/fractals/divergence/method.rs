@@ -3,6 +3,7 @@
 
 use std::{fmt, convert};
 
+use crate::structures::color;
 use crate::structures::combos::ComboMethod;
 
 /// # `LimitMethod` for any point of R².
@@ -58,3 +59,93 @@ impl convert::AsRef<str> for LimitMethod {
 		&self.to_static_str()
 	}
 }
+
+/// # `Recording` of the orbits, besides their escape time.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Recording {
+	/// Escape time, and cycles of the bounded orbits.
+	Escape,
+	/// Closest approach to an orbit trap.
+	Trap,
+	/// Derivative along the orbit, for the distance to the boundary.
+	Distance,
+}
+
+impl Recording {
+	/// Returns the lowercase name, as used in the exports.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			Recording::Escape => "escape",
+			Recording::Trap => "trap",
+			Recording::Distance => "distance",
+		}
+	}
+
+	/// Recording to compute under a `light`: the normal map needs the derivative of the orbits.
+	pub fn lit(self: &Self, light: &color::Light) -> Recording {
+		match (self, light.enabled) {
+			(Recording::Escape, true) => Recording::Distance,
+			_ => *self,
+		}
+	}
+
+	/// Boundary drawn on a render: only by the `Distance` recording.
+	pub fn boundary(self: &Self, boundary: &color::Boundary) -> Option<color::Boundary> {
+		match &self {
+			Recording::Distance => Option::Some(*boundary),
+			_ => Option::None,
+		}
+	}
+
+	/// Escape radius from the `threshold`. The distance is only estimated far out.
+	pub fn bailout(self: &Self, threshold: f64) -> f64 {
+		match &self {
+			Recording::Distance => threshold.max(color::BAILOUT_SMOOTH),
+			_ => threshold,
+		}
+	}
+}
+
+impl ComboMethod for Recording {
+	/// Return a `Vec` of all the recordings.
+	/// ```rust, no_run
+	/// 1. Escape time,
+	/// 2. Orbit trap,
+	/// 3. Distance estimation.
+	/// ```
+	fn list() -> Vec<Recording> {
+		vec![
+			Recording::Escape,
+			Recording::Trap,
+			Recording::Distance,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			Recording::Escape => 1,
+			Recording::Trap => 2,
+			Recording::Distance => 3,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			Recording::Escape => "1. Escape time",
+			Recording::Trap => "2. Orbit trap",
+			Recording::Distance => "3. Distance estimation",
+		}
+	}
+}
+
+impl fmt::Display for Recording {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for Recording {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
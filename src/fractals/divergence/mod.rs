@@ -3,10 +3,13 @@
 //! 
 //! Create fractal with the speed of sequences.
 
-mod maths;
+pub mod maths;
 pub mod texture;
 pub mod app;
 pub mod method;
+pub mod trap;
+pub mod perturbation;
+pub mod subdivision;
 
-pub use method::LimitMethod;
+pub use method::{LimitMethod, Recording};
 pub use texture::Divergent;
\ No newline at end of file
@@ -0,0 +1,424 @@
+//! # Complex sequences.
+//! src/fractals/divergence/perturbation.rs
+//!
+//! Perturbation: deep zooms of the built-in sequence z² + c.
+//! A reference orbit is computed once at arbitrary precision, from the center of the screen;
+//! each pixel only iterates its small difference to it, in floats,
+//! after skipping its first iterations with a series approximation.
+
+use std::fmt;
+use std::sync::Arc;
+
+use complex;
+use complex::Complex;
+
+use crate::structures::{computations, precision};
+use crate::fractals::{antialiasing, geometry, progressive, threading};
+use crate::fractals::divergence::method;
+
+/// Largest escape radius of the reference orbit, 2³¹: its next square, under 2⁶², and the center added
+/// stay within the integer part of `precision::Fixed`, whose overflow is lost.
+const REFERENCE_RADIUS: complex::Real = 2147483648.0;
+
+/// # `ReferenceOrbit` of z² + c, computed at arbitrary precision and rounded to floats.
+pub struct ReferenceOrbit {
+	orbit: Vec<complex::Algebraic>,
+}
+
+impl ReferenceOrbit {
+	/// Iterate z² + `c` from `z0`, at the precision of the inputs,
+	/// at most `iterations` times or until |z| > `threshold`, within the `REFERENCE_RADIUS`:
+	/// past it, the pixels are rebased on the start of the orbit.
+	pub fn new(
+		z0: [precision::Fixed; 2],
+		c: [precision::Fixed; 2],
+		threshold: complex::Real,
+		iterations: usize,
+	) -> ReferenceOrbit {
+		let radius: complex::Real = threshold.min(REFERENCE_RADIUS);
+		let [mut real, mut imaginary] = z0;
+		let mut orbit: Vec<complex::Algebraic> = Vec::with_capacity(iterations + 1);
+
+		loop {
+			let z: complex::Algebraic = complex::Algebraic::new(real.to_f64(), imaginary.to_f64());
+			orbit.push(z);
+			if orbit.len() > iterations || z.absolute() > radius {
+				break;
+			}
+
+			let real_next: precision::Fixed = real
+				.multiply(&real)
+				.subtract(&imaginary.multiply(&imaginary))
+				.add(&c[0]);
+			imaginary = real.multiply(&imaginary).double().add(&c[1]);
+			real = real_next;
+		}
+
+		ReferenceOrbit { orbit }
+	}
+
+	/// Iterates of the orbit, Z(0) included.
+	pub fn len(self: &Self) -> usize {
+		self.orbit.len()
+	}
+}
+
+/// Largest error of the series approximation, in pixels of the skipped iterate.
+const TOLERANCE: complex::Real = 0.001;
+
+/// # `Approximation` of a perturbed render: iterations skipped by the series, and its error.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Approximation {
+	/// Iterations of each pixel replaced by the series.
+	pub skipped: usize,
+	/// Bound of the error of the series, in pixels, from its first neglected term.
+	pub error: complex::Real,
+}
+
+impl fmt::Display for Approximation {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "Skipped = {} iterations (series, error < {:.1e} px);", self.skipped, self.error)
+	}
+}
+
+/// # `Series` approximation of the difference δ(n) to the reference orbit.
+/// A polynomial of the starting difference δ of a pixel, δc for `Mandelbrot`, δz(0) for `Julia`:
+/// ```math
+/// δ(n) ≈ A(n)δ + B(n)δ² + C(n)δ³
+/// ```
+/// From δ(n + 1) = 2Z(n)δ(n) + δ(n)² + δc, the coefficients follow the reference:
+/// ```math
+/// A(n + 1) = 2Z(n)A(n) + 1 (Mandelbrot) or 2Z(n)A(n) (Julia)
+/// B(n + 1) = 2Z(n)B(n) + A(n)²
+/// C(n + 1) = 2Z(n)C(n) + 2A(n)B(n)
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Series {
+	coefficients: [complex::Algebraic; 3],
+	approximation: Approximation,
+}
+
+impl Series {
+	/// No iteration skipped.
+	pub fn none(method: method::LimitMethod) -> Series {
+		let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+		Series {
+			coefficients: match method {
+				method::LimitMethod::Mandelbrot => [zero, zero, zero],
+				method::LimitMethod::Julia => [complex::Algebraic::new(1.0, 0.0), zero, zero],
+			},
+			approximation: Default::default(),
+		}
+	}
+
+	/// Skip as many iterations of the `reference` as the series stays within `TOLERANCE`,
+	/// for all the starting differences up to `radius`. A `pixel` is the spacing of the starting differences.
+	/// 
+	/// The error is the next term of the series, D(n)δ⁴, against the spacing of the pixels, |A(n)| `pixel`.
+	pub fn new(
+		reference: &ReferenceOrbit,
+		method: method::LimitMethod,
+		radius: complex::Real,
+		pixel: complex::Real,
+	) -> Series {
+		let two: complex::Algebraic = complex::Algebraic::new(2.0, 0.0);
+		let increment: complex::Algebraic = match method {
+			method::LimitMethod::Mandelbrot => complex::Algebraic::new(1.0, 0.0),
+			method::LimitMethod::Julia => complex::Algebraic::new(0.0, 0.0),
+		};
+		let mut series: Series = Series::none(method);
+		let [mut a, mut b, mut c] = series.coefficients;
+		let mut d: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+		// The last iterate skipped must be in the reference.
+		for index in 0..reference.len() - 1 {
+			let z: complex::Algebraic = two * reference.orbit[index];
+			let (a_next, b_next, c_next, d_next) = (
+				z * a + increment,
+				z * b + a * a,
+				z * c + two * a * b,
+				z * d + two * a * c + b * b,
+			);
+
+			let error: complex::Real = d_next.absolute() * radius.powi(4) / (a_next.absolute() * pixel);
+			// Also stops on overflows, as NaN.
+			if !(error <= TOLERANCE) {
+				break;
+			}
+
+			(a, b, c, d) = (a_next, b_next, c_next, d_next);
+			series = Series {
+				coefficients: [a, b, c],
+				approximation: Approximation { skipped: index + 1, error },
+			};
+		}
+
+		series
+	}
+
+	/// δ(n) of the starting difference `delta`, after the skipped iterations.
+	pub fn evaluate(self: &Self, delta: complex::Algebraic) -> complex::Algebraic {
+		let [a, b, c] = self.coefficients;
+
+		((c * delta + b) * delta + a) * delta
+	}
+
+	pub fn get_approximation(self: &Self) -> Approximation {
+		self.approximation
+	}
+}
+
+/// # `Limit` of a pixel, perturbed from the `reference` orbit.
+/// The pixel is at Z(`skipped`) + `delta`, with c = C + `delta_c`, and only its difference δ
+/// to the reference is iterated:
+/// ```math
+/// δ(n + 1) = (2Z(n) + δ(n))δ(n) + δc
+/// ```
+///
+/// Glitches, where the pixel passes closer to 0 than to the reference, lose the precision of δ:
+/// the pixel is then rebased on the start of the reference, with δ = z - Z(0).
+/// So it is too when the reference ends, escaped before the pixel.
+/// This rebase on the single reference is the only correction: no secondary reference
+/// is computed for the glitched regions.
+///
+/// Only the escape is recorded: `State::Divergent` or `State::Stable`.
+fn limit_perturbed(
+	reference: &ReferenceOrbit,
+	skipped: usize,
+	delta: complex::Algebraic,
+	delta_c: complex::Algebraic,
+	threshold: complex::Real,
+	iterations: usize,
+) -> computations::State {
+	let two: complex::Algebraic = complex::Algebraic::new(2.0, 0.0);
+	let mut delta: complex::Algebraic = delta;
+	// Index in the reference orbit, behind the iteration after a rebase.
+	let mut index: usize = skipped;
+	let mut counter: usize = skipped;
+
+	loop {
+		let z: complex::Algebraic = reference.orbit[index] + delta;
+		if z.absolute() > threshold {
+			return computations::State::Divergent { iterations: counter, modulus: z.absolute() };
+		}
+		if counter >= iterations {
+			return computations::State::Stable;
+		}
+
+		if z.absolute() < delta.absolute() || index + 1 >= reference.len() {
+			delta = z - reference.orbit[0];
+			index = 0;
+		}
+
+		delta = (two * reference.orbit[index] + delta) * delta + delta_c;
+		index += 1;
+		counter += 1;
+	}
+}
+
+/// # `Perturbation` of z² + c around a high-precision center: its reference orbit and series.
+pub struct Perturbation {
+	method: method::LimitMethod,
+	threshold: complex::Real,
+	iterations: usize,
+	zoom: complex::Real,
+	reference: Arc<ReferenceOrbit>,
+	series: Series,
+}
+
+impl Perturbation {
+	/// Compute the reference orbit from the `center`, at a precision following the `zoom`, 
+	/// and its `Series` over a screen of `size`, if `approximate`.
+	/// 
+	/// The reference starts at the center: it is `c` for `Mandelbrot`, with z0 = `constant`,
+	/// and `z0` for `Julia`, with c = `constant`.
+	pub fn new(
+		method: method::LimitMethod,
+		constant: complex::Algebraic,
+		threshold: complex::Real,
+		iterations: usize,
+		size: [usize; 2],
+		center: &[precision::Fixed; 2],
+		zoom: complex::Real,
+		approximate: bool,
+	) -> Perturbation {
+		let fraction: usize = precision::Fixed::fraction_for_zoom(zoom);
+		let center: [precision::Fixed; 2] = [center[0].with_fraction(fraction), center[1].with_fraction(fraction)];
+		let constant_fixed: [precision::Fixed; 2] = [
+			precision::Fixed::from_f64(constant.real, fraction),
+			precision::Fixed::from_f64(constant.imaginary, fraction),
+		];
+		let reference: ReferenceOrbit = match method {
+			method::LimitMethod::Mandelbrot => ReferenceOrbit::new(constant_fixed, center, threshold, iterations),
+			method::LimitMethod::Julia => ReferenceOrbit::new(center, constant_fixed, threshold, iterations),
+		};
+
+		// Farthest pixel from the center, with a sample anywhere in it.
+		let corner: [complex::Real; 2] = geometry::offset_from_pixel(
+			[0.0, 0.0], 
+			[size[0] as complex::Real, size[1] as complex::Real], 
+			zoom,
+		);
+		let radius: complex::Real = corner[0].hypot(corner[1]) + 1.0 / zoom;
+		let series: Series = match approximate {
+			true => Series::new(&reference, method, radius, 1.0 / zoom),
+			false => Series::none(method),
+		};
+
+		Perturbation { method, threshold, iterations, zoom, reference: Arc::new(reference), series }
+	}
+
+	pub fn get_approximation(self: &Self) -> Approximation {
+		self.series.get_approximation()
+	}
+
+	/// # Limit for each in point of screen, by perturbation.
+	/// Compute the limit of z² + c for each point in `size` [width, height], around the center,
+	/// by tiles on the `threading::pool()`, for the `refinement` pass at the sub-pixel `sample`.
+	/// `None` if `control` is cancelled.
+	pub fn limit_on_screen(
+		self: &Self,
+		size: [usize; 2],
+		thread_count: usize,
+		refinement: &progressive::Refinement<computations::State>,
+		sample: antialiasing::Sample,
+		control: &threading::Control,
+	) -> Option<threading::Tiled<computations::State>> {
+		let screen_size: [complex::Real; 2] = [size[0] as complex::Real, size[1] as complex::Real];
+		let refinement: progressive::Refinement<computations::State> = refinement.clone();
+		let reference: Arc<ReferenceOrbit> = self.reference.clone();
+		let (method, threshold, iterations, zoom, series) = (
+			self.method, 
+			self.threshold, 
+			self.iterations, 
+			self.zoom, 
+			self.series,
+		);
+		let skipped: usize = series.approximation.skipped;
+		let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+
+		threading::render_tiles(size, thread_count, control, move |tile: &threading::Tile| {
+			refinement.render_tile(tile, |x: usize, y: usize| {
+				let offset: [complex::Real; 2] = geometry::offset_from_pixel(sample.pixel(x, y), screen_size, zoom);
+				let offset: complex::Algebraic = complex::Algebraic::new(offset[0], offset[1]);
+				let delta_c: complex::Algebraic = match method {
+					method::LimitMethod::Mandelbrot => offset,
+					method::LimitMethod::Julia => zero,
+				};
+
+				limit_perturbed(&reference, skipped, series.evaluate(offset), delta_c, threshold, iterations)
+			})
+		})
+	}
+}
+
+#[test]
+fn test_perturbation_matches_direct() -> () {
+	let fraction: usize = precision::Fixed::fraction_for_zoom(1e3);
+	let center: [f64; 2] = [-0.75, 0.1];
+	let reference: ReferenceOrbit = ReferenceOrbit::new(
+		[precision::Fixed::zero(fraction), precision::Fixed::zero(fraction)],
+		[precision::Fixed::from_f64(center[0], fraction), precision::Fixed::from_f64(center[1], fraction)],
+		2.0,
+		200,
+	);
+
+	// Same escapes as iterating each point directly: at this zoom, the deltas keep the precision of floats.
+	let mut matching: usize = 0;
+	for y in 0..20 {
+		for x in 0..20 {
+			let offset: complex::Algebraic = complex::Algebraic::new((x as f64 - 10.0) / 1e3, (y as f64 - 10.0) / 1e3);
+			let c: complex::Algebraic = complex::Algebraic::new(center[0], center[1]) + offset;
+			let mut z: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+			let mut counter: usize = 0;
+			while counter < 200 && z.absolute() <= 2.0 {
+				z = z * z + c;
+				counter += 1;
+			}
+
+			let perturbed = limit_perturbed(&reference, 0, complex::Algebraic::new(0.0, 0.0), offset, 2.0, 200);
+			matching += match perturbed {
+				computations::State::Divergent { iterations, .. } => (z.absolute() > 2.0 && iterations == counter) as usize,
+				computations::State::Stable => (z.absolute() <= 2.0) as usize,
+				_ => 0,
+			};
+		}
+	}
+	assert_eq!(matching, 400, "{} of 400 pixels match.", matching);
+}
+
+
+#[test]
+fn test_reference_radius() -> () {
+	// An escape radius of 10¹⁸, whose square would overflow the integer part of the reference.
+	let fraction: usize = precision::Fixed::fraction_for_zoom(1e3);
+	let c: complex::Algebraic = complex::Algebraic::new(0.5, 0.5);
+	let reference: ReferenceOrbit = ReferenceOrbit::new(
+		[precision::Fixed::zero(fraction), precision::Fixed::zero(fraction)],
+		[precision::Fixed::from_f64(c.real, fraction), precision::Fixed::from_f64(c.imaginary, fraction)],
+		1e18,
+		200,
+	);
+
+	// Same orbit as in floats, up to the radius.
+	let mut z: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	for (index, iterate) in reference.orbit.iter().enumerate() {
+		assert!((*iterate - z).absolute() <= 1e-9 * z.absolute().max(1.0), "Z({}) = {:?}, not {:?}.", index, iterate, z);
+		z = z * z + c;
+	}
+	assert!(reference.orbit.last().unwrap().absolute() > REFERENCE_RADIUS);
+
+	// The pixels are rebased past it, and escape as in floats.
+	for offset in [complex::Algebraic::new(0.0, 0.0), complex::Algebraic::new(1e-3, -2e-3)] {
+		let mut z: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+		let mut counter: usize = 0;
+		while counter < 200 && z.absolute() <= 1e18 {
+			z = z * z + c + offset;
+			counter += 1;
+		}
+		match limit_perturbed(&reference, 0, complex::Algebraic::new(0.0, 0.0), offset, 1e18, 200) {
+			computations::State::Divergent { iterations, .. } => assert_eq!(iterations, counter),
+			state => panic!("{:?}", state),
+		}
+	}
+}
+
+#[test]
+fn test_series_approximation() -> () {
+	let fraction: usize = precision::Fixed::fraction_for_zoom(1e8);
+	let center: [precision::Fixed; 2] = [
+		precision::Fixed::from_f64(-1.25066, fraction),
+		precision::Fixed::from_f64(0.02012, fraction),
+	];
+	let constant: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	let render = |approximate: bool| -> (Approximation, Vec<Vec<computations::State>>) {
+		let perturbation: Perturbation = Perturbation::new(
+			method::LimitMethod::Mandelbrot, constant, 2.0, 1000, [40, 30], &center, 1e8, approximate,
+		);
+		let grid: Vec<Vec<computations::State>> = perturbation
+			.limit_on_screen([40, 30], 2, &progressive::Refinement::full(), antialiasing::Sample::center(), &threading::Control::new())
+			.unwrap()
+			.grid;
+
+		(perturbation.get_approximation(), grid)
+	};
+
+	let (approximation, approximated) = render(true);
+	let (_, iterated) = render(false);
+	assert!(approximation.skipped > 0 && approximation.error <= TOLERANCE, "{:?}", approximation);
+
+	// The same escapes: the series errs by less than `TOLERANCE` of a pixel, and this view is away from
+	// the chaotic orbits of the boundary, where such an error could change the escape time.
+	let escape = |state: &computations::State| match state {
+		computations::State::Divergent { iterations, .. } => Option::Some(*iterations),
+		_ => Option::None,
+	};
+	let matching: usize = approximated
+		.iter()
+		.flatten()
+		.zip(iterated.iter().flatten())
+		.filter(|(first, second)| escape(first) == escape(second))
+		.count();
+	assert_eq!(matching, 40 * 30, "{} of {} pixels match.", matching, 40 * 30);
+}
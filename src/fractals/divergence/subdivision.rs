@@ -0,0 +1,302 @@
+//! # Complex sequences.
+//! src/fractals/divergence/subdivision.rs
+//!
+//! Mariani–Silver rectangle subdivision, for the Mandelbrot sets of z^n + c.
+//! From z0 = 0, the points escaping after more than k iterations form a connected and full set, around c = 0:
+//! a rectangle whose border escapes in the same iterations, not around c = 0, or remains bounded, is the same inside.
+//! Only the borders are computed: the rectangles of uniform border are filled,
+//! the others are cut in two, until they are small enough to be computed whole.
+//! 
+//! The escape time is exact, but a filled pixel copies the whole state of a corner.
+//! So the escaped rectangles are only filled where the colors read the iterations, not the modulus,
+//! and the bounded ones where the interior is flat: their periods, found by Brent's detection
+//! at a tolerance, sometimes late or as a multiple, are not those of each pixel.
+
+use complex;
+
+use crate::structures::{color, computations};
+use crate::fractals::{antialiasing, geometry, progressive, threading};
+use crate::fractals::divergence::{maths, method};
+
+/// Side, in samples, of the rectangles computed whole instead of cut.
+const MINIMUM_SIDE: usize = 4;
+
+/// # `Rectangle` of samples of a tile, its bounds included.
+#[derive(Debug, Clone, Copy)]
+struct Rectangle {
+	start: [usize; 2],
+	end: [usize; 2],
+}
+
+impl Rectangle {
+	/// Samples of the border, each once.
+	fn border(self: &Self) -> Vec<[usize; 2]> {
+		let [x0, y0] = self.start;
+		let [x1, y1] = self.end;
+		let mut cells: Vec<[usize; 2]> = (x0..=x1).map(|x: usize| [x, y0]).collect();
+		if y1 > y0 {
+			cells.extend((x0..=x1).map(|x: usize| [x, y1]));
+		}
+		cells.extend((y0 + 1..y1).map(|y: usize| [x0, y]));
+		if x1 > x0 {
+			cells.extend((y0 + 1..y1).map(|y: usize| [x1, y]));
+		}
+
+		cells
+	}
+
+	/// Samples inside the border.
+	fn interior(self: &Self) -> Vec<[usize; 2]> {
+		(self.start[1] + 1..self.end[1])
+			.flat_map(|y: usize| (self.start[0] + 1..self.end[0]).map(move |x: usize| [x, y]))
+			.collect()
+	}
+
+	/// The `point`, in samples, is inside the border or within a sample of it.
+	fn surrounds(self: &Self, point: [complex::Real; 2]) -> bool {
+		(0..2).all(|axis: usize| {
+			self.start[axis] as complex::Real - 1.0 <= point[axis] && point[axis] <= self.end[axis] as complex::Real + 1.0
+		})
+	}
+
+	/// Cut across its longest side, the two halves sharing the middle line.
+	fn split(self: &Self) -> [Rectangle; 2] {
+		let axis: usize = match self.end[0] - self.start[0] >= self.end[1] - self.start[1] {
+			true => 0,
+			false => 1,
+		};
+		let middle: usize = (self.start[axis] + self.end[axis]) / 2;
+		let mut first: Rectangle = *self;
+		let mut second: Rectangle = *self;
+		first.end[axis] = middle;
+		second.start[axis] = middle;
+
+		[first, second]
+	}
+}
+
+/// # `Filling` of the rectangles of uniform border, where it gives the colors of their pixels computed.
+#[derive(Debug, Clone, Copy)]
+pub struct Filling {
+	/// Escaped in the same iterations.
+	escaped: bool,
+	/// Bounded, stable or periodic.
+	bounded: bool,
+}
+
+impl Filling {
+	/// The escaped rectangles are filled if the `color_mode` only reads the iterations,
+	/// the bounded ones if the `interior_mode` is flat.
+	pub fn new(color_mode: color::ColorMode, interior_mode: color::InteriorMode) -> Self {
+		Filling {
+			escaped: !color_mode.is_smooth(),
+			bounded: interior_mode == color::InteriorMode::Flat,
+		}
+	}
+
+	/// The two states escape in the same iterations, or are both bounded, and such rectangles are filled.
+	fn is_uniform(self: &Self, first: &computations::State, second: &computations::State) -> bool {
+		match (first, second) {
+			(
+				computations::State::Divergent { iterations, .. },
+				computations::State::Divergent { iterations: other, .. },
+			) => self.escaped && iterations == other,
+			(
+				computations::State::Stable | computations::State::Periodic { .. },
+				computations::State::Stable | computations::State::Periodic { .. },
+			) => self.bounded,
+			_ => false,
+		}
+	}
+
+	/// No rectangle is ever filled.
+	fn is_empty(self: &Self) -> bool {
+		!self.escaped && !self.bounded
+	}
+}
+
+/// Samples of a tile, of which only some are computed.
+struct Samples<E> {
+	cells: Vec<Vec<Option<computations::State>>>,
+	filling: Filling,
+	/// Where c = 0 is, in samples: the points escaping later are connected to it,
+	/// a rectangle surrounding it may hold them behind a border escaping sooner.
+	origin: [complex::Real; 2],
+	evaluate: E,
+}
+
+impl<E> Samples<E>
+where
+	E: FnMut(&[[usize; 2]]) -> Vec<computations::State>,
+{
+	/// Compute together the `cells` still unknown.
+	fn compute(self: &mut Self, cells: &[[usize; 2]]) -> () {
+		let unknown: Vec<[usize; 2]> = cells
+			.iter()
+			.filter(|[x, y]| self.cells[*y][*x].is_none())
+			.copied()
+			.collect();
+		let states: Vec<computations::State> = (self.evaluate)(&unknown);
+		for ([x, y], state) in unknown.into_iter().zip(states) {
+			self.cells[y][x] = Option::Some(state);
+		}
+	}
+
+	fn get(self: &Self, [x, y]: [usize; 2]) -> computations::State {
+		self.cells[y][x].expect("(X) fractals::divergence::subdivision::Samples::get() Sample not computed.")
+	}
+
+	/// Fill the whole `rectangle`, from its border computed.
+	fn subdivide(self: &mut Self, rectangle: Rectangle) -> () {
+		let mut rectangles: Vec<Rectangle> = vec![rectangle];
+
+		while let Option::Some(rectangle) = rectangles.pop() {
+			let border: Vec<[usize; 2]> = rectangle.border();
+			self.compute(&border);
+			let interior: Vec<[usize; 2]> = rectangle.interior();
+			if interior.is_empty() {
+				continue;
+			}
+
+			let first: computations::State = self.get(border[0]);
+			let is_escaped: bool = matches!(first, computations::State::Divergent { .. });
+			if !(is_escaped && rectangle.surrounds(self.origin))
+				&& border.iter().all(|cell: &[usize; 2]| self.filling.is_uniform(&first, &self.get(*cell))) {
+				for [x, y] in interior {
+					self.cells[y][x].get_or_insert(first);
+				}
+			} else if rectangle.end[0] - rectangle.start[0] <= MINIMUM_SIDE && rectangle.end[1] - rectangle.start[1] <= MINIMUM_SIDE {
+				self.compute(&interior);
+			} else {
+				rectangles.extend(rectangle.split());
+			}
+		}
+	}
+}
+
+/// # Limit for each in point of screen, of the Mandelbrot set of z^`degree` + c from z0 = 0, by rectangle subdivision.
+/// Same colors as `maths::limit_on_screen_power`, but the pixels inside a rectangle of uniform border,
+/// as told by the `filling`, are filled with the state of its first corner: its modulus, period and multiplier too.
+/// The anchors of each tile are subdivided, those reused from the previous pass are known from the start.
+/// A masked pass only samples some pixels, computed directly, as are all of them if nothing is filled.
+pub fn limit_on_screen_subdivided(
+	degree: u32,
+	threshold: complex::Real,
+	iterations: usize,
+	size: [usize; 2],
+	position: [complex::Real; 2],
+	zoom: complex::Real,
+	thread_count: usize,
+	refinement: &progressive::Refinement<computations::State>,
+	sample: antialiasing::Sample,
+	filling: Filling,
+	control: &threading::Control,
+) -> Option<threading::Tiled<computations::State>> {
+	let z0: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	if refinement.mask.is_some() || filling.is_empty() {
+		return maths::limit_on_screen_power(
+			method::LimitMethod::Mandelbrot, z0, degree, threshold, iterations, size, position, zoom, thread_count,
+			refinement, sample, control,
+		);
+	}
+
+	let screen_size: [complex::Real; 2] = [size[0] as complex::Real, size[1] as complex::Real];
+	let refinement: progressive::Refinement<computations::State> = refinement.clone();
+
+	threading::render_tiles(size, thread_count, control, move |tile: &threading::Tile| {
+		let step: usize = refinement.step.max(1);
+		let pixel = move |[x, y]: [usize; 2]| -> [usize; 2] { [tile.start[0] + x * step, tile.start[1] + y * step] };
+		let columns: usize = (tile.end[0] - tile.start[0]).div_ceil(step);
+		let rows: usize = (tile.end[1] - tile.start[1]).div_ceil(step);
+
+		let mut samples = Samples {
+			cells: (0..rows)
+				.map(|y: usize| (0..columns).map(|x: usize| {
+					let [x, y] = pixel([x, y]);
+					refinement.reused(x, y)
+				}).collect())
+				.collect(),
+			filling,
+			origin: [
+				(screen_size[0] / 2.0 + zoom * position[0] - tile.start[0] as complex::Real) / step as complex::Real,
+				(screen_size[1] / 2.0 + zoom * position[1] - tile.start[1] as complex::Real) / step as complex::Real,
+			],
+			evaluate: |cells: &[[usize; 2]]| {
+				let points: Vec<complex::Algebraic> = cells
+					.iter()
+					.map(|cell: &[usize; 2]| {
+						let [x, y] = pixel(*cell);
+						let point: [complex::Real; 2] = geometry::position_from_pixel(
+							sample.pixel(x, y),
+							screen_size,
+							zoom,
+							position,
+						);
+						complex::Algebraic::new(point[0], point[1])
+					})
+					.collect();
+				maths::limit_points(method::LimitMethod::Mandelbrot, z0, degree, threshold, iterations, &points)
+			},
+		};
+		samples.subdivide(Rectangle { start: [0, 0], end: [columns - 1, rows - 1] });
+
+		refinement.render_tile(tile, |x: usize, y: usize| {
+			samples.get([(x - tile.start[0]) / step, (y - tile.start[1]) / step])
+		})
+	})
+}
+
+
+#[test]
+fn test_subdivision_matches_brute_force() -> () {
+	let refinement: progressive::Refinement<computations::State> = progressive::Refinement::full();
+	let control: threading::Control = threading::Control::new();
+	let zero: complex::Algebraic = complex::Algebraic::new(0.0, 0.0);
+	let size: [usize; 2] = [160, 120];
+	let palette: crate::structures::palette::Palette = Default::default();
+
+	// The whole set within a tile, the set, the seahorse valley, and a minibrot on the antenna.
+	for (position, zoom) in [([0.51, 3.23], 10.0), ([0.5, 0.0], 40.0), ([0.745, -0.11], 2000.0), ([1.7687, 0.0], 40000.0)] {
+		let brute: Vec<Vec<computations::State>> = maths::limit_on_screen_mandelbrot(
+			zero, |z: complex::Algebraic, c: complex::Algebraic| z * z + c, maths::Derivative::Power(2), 2.0, 300, method::Recording::Escape, Default::default(), size, position, zoom, 4,
+			&refinement, antialiasing::Sample::center(), &control,
+		).unwrap().grid;
+
+		// Filled escape and interior, filled escape only, filled interior only, nothing filled.
+		for (color_mode, interior_mode) in [
+			(color::ColorMode::HSV, color::InteriorMode::Flat),
+			(color::ColorMode::GRAYSCALE, color::InteriorMode::Period),
+			(color::ColorMode::SMOOTH, color::InteriorMode::Flat),
+			(color::ColorMode::OKLCH, color::InteriorMode::Multiplier),
+		] {
+			let filling: Filling = Filling::new(color_mode, interior_mode);
+			let subdivided: Vec<Vec<computations::State>> = limit_on_screen_subdivided(
+				2, 2.0, 300, size, position, zoom, 4, &refinement, antialiasing::Sample::center(), filling, &control,
+			).unwrap().grid;
+
+			// Same escape time, and the same state where it can't be filled.
+			for (y, (line, other)) in brute.iter().zip(subdivided.iter()).enumerate() {
+				for (x, (state, other)) in line.iter().zip(other.iter()).enumerate() {
+					let is_exact: bool = match (state, other) {
+						(
+							computations::State::Divergent { iterations, .. },
+							computations::State::Divergent { iterations: other_iterations, .. },
+						) => iterations == other_iterations && (filling.escaped || state == other),
+						(computations::State::Stable | computations::State::Periodic { .. }, computations::State::Stable | computations::State::Periodic { .. }) => {
+							filling.bounded || state == other
+						},
+						_ => false,
+					};
+					assert!(is_exact, "{:?}, {}, ({}; {}): {:?}, {:?}.", position, color_mode, x, y, state, other);
+				}
+			}
+
+			// Same colors, pixel for pixel.
+			let color = |table: Vec<Vec<computations::State>>| crate::fractals::tables::state_table_to_data(
+				table, crate::support::defaults::COLOR_STABLE, crate::support::defaults::COLOR_DIVERGENT, 300, 2.0,
+				color_mode, &palette, interior_mode, Default::default(), Option::None, 1.0 / zoom,
+			).raw_pixels;
+			assert!(color(brute.clone()) == color(subdivided), "{:?}, {}, {:?}.", position, color_mode, interior_mode);
+		}
+	}
+}
@@ -5,16 +5,19 @@
 //! - Mandelbrot,
 //! - Julia.
 
-use std::{cell, error, rc, time};
+use std::{cell, error, path, rc, time};
+use std::sync::Arc;
 
 use glium::{self, backend};
 use imgui;
 use imgui_glium_renderer;
 use complex;
 
-use crate::structures::{color, configuration, computations};
+use crate::structures::{color, configuration, computations, palette, precision};
+use crate::structures::combos::ComboMethod;
 use crate::fractals;
-use crate::support::rendering;
+use crate::expressions;
+use crate::support::{export, rendering};
 
 
 /// # `Divergent`, drawing board for `imgui`.
@@ -22,34 +25,71 @@ pub struct Divergent<F>
 where
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
 {
+	/// Built-in function, used while `formula` is empty.
 	function: F,
+	/// Degree n of the built-in `function` if it is z^n + c, `None` if unknown.
+	degree: Option<u32>,
+	/// User-typed function, compiled from `formula`.
+	expression: Option<expressions::Expression>,
+	/// Error of the last compilation of `formula`, if any.
+	formula_error: Option<String>,
 	texture_id: Option<imgui::TextureId>,
 	
 	iterations_total: usize,
 	generation_time: Option<time::Duration>,
 	thread_count: usize,
+	tile_statistics: fractals::threading::TileStatistics,
+	/// Series approximation of the last perturbed render.
+	approximation: Option<fractals::divergence::perturbation::Approximation>,
+	/// Render in progress, in the background.
+	task: Option<fractals::threading::Background<fractals::textures::Rendered>>,
+	/// Step of the last pass shown, 0 before the first one.
+	pass_step: usize,
 
 	// Parameters.
 	/// Constant fixed point. E.g: `c` in `f(z) = z * z + c`.
 	pub constant: complex::Algebraic,
+	/// Text of the function `f(z, c)`. Empty to use the built-in `function`.
+	pub formula: String,
 	/// Size: [width, height].
 	pub size: [u32; 2],
 	pub information_size: [f32; 2],
 	pub scale: complex::Real,
-	pub position: [complex::Real; 2],
-	pub zoom: complex::Real,
+	/// Center, at arbitrary precision, and zoom.
+	pub viewport: fractals::geometry::Viewport,
 	pub iterations: usize,
 	pub threshold: complex::Real,
 	pub method_id: usize,
+	/// What is kept of the orbits.
+	pub recording: fractals::divergence::Recording,
+	/// Orbit trap of the `Trap` recording.
+	pub trap: fractals::divergence::trap::OrbitTrap,
+	/// Boundary drawn by the `Distance` recording.
+	pub boundary: color::Boundary,
+	/// Light of the normal map, with the `Escape` and `Distance` recordings.
+	pub light: color::Light,
+	/// Color of the bounded points.
+	pub interior_mode: color::InteriorMode,
+	/// Deep zoom of the built-in function, by perturbation of a reference orbit.
+	pub perturbation: bool,
+	/// Mariani–Silver rectangle subdivision, for the Mandelbrot sets of z^n + c.
+	pub subdivision: bool,
 
 	// Variables to check if state is modified.
 	constant_last: complex::Algebraic,
-	zoom_last: complex::Real,
-	position_last: [complex::Real; 2],
+	expression_last: Option<expressions::Expression>,
+	viewport_last: fractals::geometry::Viewport,
 	iterations_last: usize,
 	threshold_last: complex::Real,
 	method_id_last: usize,
 	scale_last: complex::Real,
+	recording_last: fractals::divergence::Recording,
+	trap_last: fractals::divergence::trap::OrbitTrap,
+	boundary_last: color::Boundary,
+	light_last: color::Light,
+	interior_mode_last: color::InteriorMode,
+	perturbation_last: bool,
+	subdivision_last: bool,
 
 	/// Graphics.
 	color_stable: color::Rgb,
@@ -63,6 +103,7 @@ where
 	/// Instantiate and returns a link to a new `Divergent`.
 	pub fn new(
 		function: F,
+		degree: Option<u32>,
 		constant: complex::Algebraic,
 		information_size: [f32; 2],
 		position: [complex::Real; 2], 
@@ -76,27 +117,48 @@ where
 	) -> rc::Rc<cell::RefCell<Divergent<F>>> {
 		rc::Rc::new(cell::RefCell::new(Divergent {
 			function,
+			degree,
+			expression: Option::None,
+			formula_error: Option::None,
 			iterations_total: 0usize,
 			texture_id: Option::None,
 			thread_count: 0,
+			tile_statistics: Default::default(),
+			approximation: Option::None,
+			task: Option::None,
+			pass_step: 0,
 			constant,
+			formula: String::new(),
 			size: [0, 0], 
 			information_size,
 			scale,
 			generation_time: Option::None,
-			position,
-			zoom,
+			viewport: fractals::geometry::Viewport::new(position, zoom),
 			iterations,
 			threshold,
 			method_id,
+			recording: fractals::divergence::Recording::Escape,
+			trap: Default::default(),
+			boundary: Default::default(),
+			light: Default::default(),
+			interior_mode: color::InteriorMode::Flat,
+			perturbation: false,
+			subdivision: false,
 
 			constant_last: Default::default(),
-			zoom_last: 1.0,
-			position_last: [0.0, 0.0],
+			expression_last: Option::None,
+			viewport_last: fractals::geometry::Viewport::new([0.0, 0.0], 1.0),
 			iterations_last: 0,
 			threshold_last: 0.0,
 			method_id_last: 0,
 			scale_last: 1.0,
+			recording_last: fractals::divergence::Recording::Escape,
+			trap_last: Default::default(),
+			boundary_last: Default::default(),
+			light_last: Default::default(),
+			interior_mode_last: color::InteriorMode::Flat,
+			perturbation_last: false,
+			subdivision_last: false,
 
 			color_stable,
 			color_divergent,
@@ -109,102 +171,680 @@ where
 	pub fn is_state_updated(self: &mut Self) -> bool {
 		let mut updated: bool = true;
 
-		if self.zoom_last != self.zoom {
-			self.zoom_last = self.zoom;
-		} else if self.position_last != self.position {
-			self.position_last = self.position;
+		if self.viewport_last != self.viewport {
+			self.viewport_last = self.viewport.clone();
 		} else if self.iterations_last != self.iterations {
 			self.iterations_last = self.iterations;
 		} else if self.threshold_last != self.threshold {
 			self.threshold_last = self.threshold;
 		} else if self.constant_last != self.constant {
 			self.constant_last = self.constant;
+		} else if self.expression_last != self.expression {
+			self.expression_last = self.expression.clone();
 		} else if self.method_id_last != self.method_id {
 			self.method_id_last = self.method_id;
 		} else if self.scale_last != self.scale {
 			self.scale_last = self.scale
+		} else if self.recording_last != self.recording {
+			self.recording_last = self.recording;
+		} else if self.trap_last != self.trap {
+			self.trap_last = self.trap;
+		} else if self.boundary_last != self.boundary {
+			self.boundary_last = self.boundary;
+		} else if self.light_last != self.light {
+			self.light_last = self.light;
+		} else if self.interior_mode_last != self.interior_mode {
+			self.interior_mode_last = self.interior_mode;
+		} else if self.perturbation_last != self.perturbation {
+			self.perturbation_last = self.perturbation;
+		} else if self.subdivision_last != self.subdivision {
+			self.subdivision_last = self.subdivision;
 		} else {	
 			updated = false;
 		}
 
 		updated
 	}
+
+	/// Compile `formula` into the function of the sequence.
+	/// 
+	/// An empty `formula` restores the built-in function. 
+	/// On error, the previous function is kept and the error is stored in `formula_error`.
+	pub fn compile_formula(self: &mut Self) -> () {
+		if self.formula.trim().is_empty() {
+			self.expression = Option::None;
+			self.formula_error = Option::None;
+			return;
+		}
+
+		match expressions::parse(&self.formula) {
+			Result::Ok(expression) => {
+				self.expression = Option::Some(expression);
+				self.formula_error = Option::None;
+			},
+			Result::Err(error) => {
+				self.formula_error = Option::Some(error.to_string());
+			},
+		}
+	}
+
+	/// Error of the last compilation of `formula`, if any.
+	pub fn get_formula_error(self: &Self) -> Option<&str> {
+		self.formula_error.as_deref()
+	}
 }
 
-impl<F> fractals::textures::Fractal for Divergent<F> 
+impl<F> Divergent<F> 
 where
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
 {
-	fn update_size(self: &mut Self, new_size: [u32; 2]) -> () {
-		self.size = new_size
+	/// Copy the parameters of the fractal into a `Generation`, which can run on any thread.
+	/// 
+	/// The threshold becomes the escape radius of `color_mode` and of the recording.
+	/// Perturbation only applies to the built-in function, and only records the escape.
+	fn generation(self: &mut Self, color_mode: color::ColorMode, palette: palette::Palette) -> Generation<F> {
+		self.thread_count = fractals::threading::determine_threads().into();
+		let perturbation: bool = self.is_perturbed();
+		let recording: fractals::divergence::Recording = match perturbation {
+			true => fractals::divergence::Recording::Escape,
+			false => self.recording.lit(&self.light),
+		};
+
+		Generation {
+			function: self.function.clone(),
+			expression: self.expression.clone(),
+			degree: self.sequence_degree(),
+			derivative: match (&self.expression, self.degree) {
+				(Option::Some(expression), _) => fractals::divergence::maths::Derivative::new(expression),
+				(Option::None, Option::Some(degree)) => fractals::divergence::maths::Derivative::Power(degree),
+				(Option::None, Option::None) => fractals::divergence::maths::Derivative::Numerical,
+			},
+			smoothing: self.smoothing_degree(),
+			constant: self.constant,
+			threshold: recording.bailout(color_mode.bailout(self.threshold)),
+			iterations: self.iterations,
+			recording,
+			trap: self.trap,
+			light: self.light,
+			boundary: recording.boundary(&self.boundary),
+			position: self.viewport.get_position(),
+			center: self.viewport.center.clone(),
+			method_id: self.method_id,
+			thread_count: self.thread_count,
+			color_stable: self.color_stable,
+			color_divergent: self.color_divergent,
+			palette,
+			interior_mode: self.interior_mode,
+			perturbation,
+			subdivision: match self.is_subdivided() {
+				true => Option::Some(fractals::divergence::subdivision::Filling::new(color_mode, self.interior_mode)),
+				false => Option::None,
+			},
+		}
 	}
 
-	fn register_texture<Facade>(
-        &mut self,
-        gl_context: &Facade,
-		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
-        textures: &mut imgui::Textures<imgui_glium_renderer::Texture>,
-    ) -> Result<(), Box<dyn error::Error>>
-    where
-        Facade: backend::Facade,
-    {	
-		let scale: complex::Real = global_settings.borrow().resolution_scale;
-		self.scale = scale;
-		let scaled_size: [usize; 2] = [
-			(self.size[0] as complex::Real / scale) as usize, 
-			(self.size[1] as complex::Real / scale) as usize
-		];
-		self.thread_count = fractals::threading::determine_threads().into();
-			
-		// Texture generation.
-		let generation_start: time::Instant = time::Instant::now();
+	/// Degree n of the sequence if it is z^n + c: of the `expression`, or else of the built-in function.
+	fn sequence_degree(self: &Self) -> Option<u32> {
+		match &self.expression {
+			Option::Some(expression) => expression.power_of_z_plus_c(),
+			Option::None => self.degree,
+		}
+	}
 
-		let table: Vec<Vec<computations::State>> = match self.method_id {
+	/// Degree of the sequence at infinity, of which the escape time is smoothed:
+	/// of the polynomial `expression`, or else of the built-in function. 2 if unknown.
+	fn smoothing_degree(self: &Self) -> f64 {
+		let degree: Option<usize> = match &self.expression {
+			Option::Some(expression) => expression.degree(),
+			Option::None => self.degree.map(|degree: u32| degree as usize),
+		};
+
+		match degree {
+			Option::Some(degree) if degree >= 2 => degree as f64,
+			_ => 2.0,
+		}
+	}
+
+	/// Perturbation is on, for the built-in function z² + c.
+	fn is_perturbed(self: &Self) -> bool {
+		self.perturbation && self.expression.is_none() && self.degree == Option::Some(2)
+	}
+
+	/// Rectangle subdivision is on, for the Mandelbrot set from z0 = 0, without perturbation.
+	fn is_subdivided(self: &Self) -> bool {
+		self.subdivision && self.method_id != 1 && self.constant == complex::Algebraic::new(0.0, 0.0) && !self.is_perturbed()
+	}
+
+	/// Parameters of a render of `size` and `zoom`, for the export sidecar.
+	fn parameters(
+		self: &Self, 
+		size: [usize; 2], 
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		antialiasing: fractals::antialiasing::Antialiasing,
+		adaptive: fractals::antialiasing::Adaptive,
+	) -> Vec<(&'static str, String)> {
+		let method: String = match fractals::divergence::LimitMethod::list().get(self.method_id) {
+			Option::Some(method) => method.to_string(),
+			Option::None => format!("{}", self.method_id),
+		};
+		// Decimals of the center, as precise as the pixels of the render.
+		let center: [String; 2] = fractals::geometry::Viewport { center: self.viewport.center.clone(), zoom }.to_decimals();
+
+		vec![
+			("family", String::from("\"divergence\"")),
+			("method", format!("\"{}\"", method)),
+			("formula", match &self.expression {
+				Option::Some(expression) => format!("\"{}\"", expression),
+				Option::None => String::from("\"built-in\""),
+			}),
+			("size", format!("[{}, {}]", size[0], size[1])),
+			("center", format!("[{}, {}]", center[0], center[1])),
+			("zoom", format!("{}", zoom)),
+			("constant", format!("[{}, {}]", self.constant.real, self.constant.imaginary)),
+			("iterations", format!("{}", self.iterations)),
+			("threshold", format!("{}", self.threshold)),
+			("color_mode", format!("\"{}\"", color_mode.to_name())),
+			("antialiasing", format!("\"{}\"", antialiasing.to_name())),
+			("adaptive", match antialiasing {
+				fractals::antialiasing::Antialiasing::Adaptive => format!(
+					"{{ threshold = {}, samples = {} }}", 
+					adaptive.threshold, 
+					adaptive.samples,
+				),
+				_ => String::from("\"none\""),
+			}),
+			("perturbation", format!("{}", self.is_perturbed())),
+			("subdivision", format!("{}", self.is_subdivided())),
+			("interior", format!("\"{}\"", self.interior_mode.to_name())),
+			("recording", format!("\"{}\"", self.recording.to_name())),
+			("trap", match self.recording {
+				fractals::divergence::Recording::Trap => format!(
+					"{{ shape = \"{}\", center = [{}, {}], radius = {}, angle = {} }}",
+					self.trap.shape.to_name(),
+					self.trap.center.real,
+					self.trap.center.imaginary,
+					self.trap.radius,
+					self.trap.angle,
+				),
+				_ => String::from("\"none\""),
+			}),
+			("light", match self.light.enabled {
+				true => format!("{{ angle = {}, height = {} }}", self.light.angle, self.light.height),
+				false => String::from("\"none\""),
+			}),
+			("boundary", match self.recording {
+				fractals::divergence::Recording::Distance => format!(
+					"{{ mode = \"{}\", thickness = {} }}",
+					self.boundary.mode.to_name(),
+					self.boundary.thickness,
+				),
+				_ => String::from("\"none\""),
+			}),
+		]
+	}
+}
+
+/// # `Generation` of a `Divergent` fractal.
+/// A copy of its parameters, to compute it in the background.
+struct Generation<F> {
+	function: F,
+	expression: Option<expressions::Expression>,
+	/// Degree n of the sequence if it is z^n + c.
+	degree: Option<u32>,
+	/// Exact derivatives of the `expression`, or else of the built-in function if its degree is known.
+	derivative: fractals::divergence::maths::Derivative,
+	/// Degree of the sequence at infinity, for the smooth escape time.
+	smoothing: f64,
+	constant: complex::Algebraic,
+	/// Escape radius.
+	threshold: complex::Real,
+	iterations: usize,
+	/// Recording computed, lit.
+	recording: fractals::divergence::Recording,
+	trap: fractals::divergence::trap::OrbitTrap,
+	light: color::Light,
+	boundary: Option<color::Boundary>,
+	/// Camera position, in floats.
+	position: [complex::Real; 2],
+	/// Center, at arbitrary precision, for the perturbation.
+	center: [precision::Fixed; 2],
+	method_id: usize,
+	thread_count: usize,
+	color_stable: color::Rgb,
+	color_divergent: color::Rgb,
+	palette: palette::Palette,
+	interior_mode: color::InteriorMode,
+	/// Compute the built-in function by perturbation, as z² + c.
+	perturbation: bool,
+	/// Compute the escape time of z^n + c by rectangle subdivision, filling the rectangles whose colors are exact.
+	subdivision: Option<fractals::divergence::subdivision::Filling>,
+}
+
+impl<F> Generation<F> 
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
+{
+	/// Compute the fractal on a grid of `size` [width, height], with `zoom`, into raw RGB `Data`.
+	/// 
+	/// Passes from `refinement` to the full resolution; each coarser one is given to `publish`.
+	/// The full resolution is supersampled with `antialiasing`: `Adaptive` gives it to `publish`
+	/// before sampling again its contrasted pixels.
+	/// `None` if `control` is cancelled.
+	fn run<P>(
+		self: &Self,
+		size: [usize; 2],
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		antialiasing: fractals::antialiasing::Antialiasing,
+		adaptive: fractals::antialiasing::Adaptive,
+		refinement: fractals::progressive::Refinement<computations::State>,
+		control: &fractals::threading::Control,
+		publish: P,
+	) -> Option<fractals::textures::Rendered> 
+	where
+		P: Fn(fractals::textures::Rendered) -> (),
+	{
+		// The reference orbit and its series serve all the passes and samples.
+		let perturbation: Option<fractals::divergence::perturbation::Perturbation> = match self.perturbation {
+			true => Option::Some(fractals::divergence::perturbation::Perturbation::new(
+				match self.method_id {
+					1 => fractals::divergence::LimitMethod::Julia,
+					_ => fractals::divergence::LimitMethod::Mandelbrot,
+				},
+				self.constant,
+				self.threshold,
+				self.iterations,
+				size,
+				&self.center,
+				zoom,
+				true,
+			)),
+			false => Option::None,
+		};
+
+		let mut rendered: fractals::textures::Rendered = self.passes(
+			size, 
+			zoom, 
+			color_mode, 
+			antialiasing, 
+			adaptive, 
+			refinement, 
+			perturbation.as_ref(), 
+			control, 
+			publish,
+		)?;
+		rendered.approximation = perturbation.map(|perturbation| perturbation.get_approximation());
+
+		Option::Some(rendered)
+	}
+
+	/// Passes of `run`, computed by `perturbation` if any.
+	fn passes<P>(
+		self: &Self,
+		size: [usize; 2],
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		antialiasing: fractals::antialiasing::Antialiasing,
+		adaptive: fractals::antialiasing::Adaptive,
+		mut refinement: fractals::progressive::Refinement<computations::State>,
+		perturbation: Option<&fractals::divergence::perturbation::Perturbation>,
+		control: &fractals::threading::Control,
+		publish: P,
+	) -> Option<fractals::textures::Rendered> 
+	where
+		P: Fn(fractals::textures::Rendered) -> (),
+	{
+		loop {
+			// Antialiased, the full resolution is computed sample by sample, below.
+			if refinement.is_full() && antialiasing.is_uniform() {
+				break;
+			}
+
+			let tiled: fractals::threading::Tiled<computations::State> = self.limit(
+				size, 
+				zoom, 
+				&refinement, 
+				fractals::antialiasing::Sample::center(), 
+				perturbation,
+				control,
+			)?;
+
+			if refinement.is_full() && antialiasing == fractals::antialiasing::Antialiasing::Adaptive {
+				publish(self.rendered(
+					fractals::threading::Tiled { grid: tiled.grid.clone(), statistics: tiled.statistics },
+					size,
+					zoom,
+					refinement.step,
+					color_mode,
+				));
+				control.restart_progress();
+				return self.resampled(tiled, size, zoom, color_mode, adaptive, perturbation, control);
+			}
+			if refinement.is_full() {
+				return Option::Some(self.rendered(tiled, size, zoom, refinement.step, color_mode));
+			}
+
+			let grid: Arc<Vec<Vec<computations::State>>> = Arc::new(tiled.grid);
+			publish(self.rendered(
+				fractals::threading::Tiled { grid: grid.as_ref().clone(), statistics: tiled.statistics },
+				size,
+				zoom,
+				refinement.step,
+				color_mode,
+			));
+			refinement = refinement.refine(grid);
+			control.restart_progress();
+		}
+
+		let mut statistics: fractals::threading::TileStatistics = fractals::threading::TileStatistics::default();
+		let data: computations::Data = fractals::antialiasing::supersample(
+			antialiasing,
+			control,
+			|sample: fractals::antialiasing::Sample| {
+				let tiled: fractals::threading::Tiled<computations::State> = self.limit(
+					size, 
+					zoom, 
+					&fractals::progressive::Refinement::full(), 
+					sample, 
+					perturbation,
+					control,
+				)?;
+				statistics = tiled.statistics;
+
+				Option::Some(self.rendered(tiled, size, zoom, 1, color_mode).data)
+			},
+		)?;
+
+		Option::Some(fractals::textures::Rendered { data, size, step: 1, statistics, approximation: Option::None })
+	}
+
+	/// Sample again the pixels of a full resolution `tiled` pass contrasted with their neighbors,
+	/// and average them. `None` if `control` is cancelled.
+	fn resampled(
+		self: &Self,
+		tiled: fractals::threading::Tiled<computations::State>,
+		size: [usize; 2],
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		adaptive: fractals::antialiasing::Adaptive,
+		perturbation: Option<&fractals::divergence::perturbation::Perturbation>,
+		control: &fractals::threading::Control,
+	) -> Option<fractals::textures::Rendered> {
+		let mut statistics: fractals::threading::TileStatistics = tiled.statistics;
+		let mut resampling: fractals::antialiasing::Resampling<computations::State>;
+		resampling = fractals::antialiasing::Resampling::new(adaptive, tiled.grid);
+
+		for (index, sample) in resampling.samples().into_iter().enumerate() {
+			if index > 0 {
+				control.restart_progress();
+			}
+			let tiled: fractals::threading::Tiled<computations::State> = self.limit(
+				size, 
+				zoom, 
+				&resampling.refinement(), 
+				sample, 
+				perturbation,
+				control,
+			)?;
+			statistics = tiled.statistics;
+			resampling.push(tiled.grid);
+		}
+
+		let data: computations::Data = resampling.average(
+			self.iterations,
+			|grid: Vec<Vec<computations::State>>| self.rendered(
+				fractals::threading::Tiled { grid, statistics },
+				size,
+				zoom,
+				1,
+				color_mode,
+			).data,
+		);
+
+		Option::Some(fractals::textures::Rendered { data, size, step: 1, statistics, approximation: Option::None })
+	}
+
+	/// Compute the divergence table of the `expression`, or else the `function`, at a `sample`
+	/// of the pixels of the `refinement` pass.
+	/// With a `perturbation`, the function is z² + c around a high-precision center.
+	/// The escape time of z^n + c is batched, or subdivided in rectangles.
+	fn limit(
+		self: &Self,
+		size: [usize; 2],
+		zoom: complex::Real,
+		refinement: &fractals::progressive::Refinement<computations::State>,
+		sample: fractals::antialiasing::Sample,
+		perturbation: Option<&fractals::divergence::perturbation::Perturbation>,
+		control: &fractals::threading::Control,
+	) -> Option<fractals::threading::Tiled<computations::State>> {
+		if let Option::Some(perturbation) = perturbation {
+			return perturbation.limit_on_screen(size, self.thread_count, refinement, sample, control);
+		}
+
+		if let Option::Some(degree) = self.escape_degree() {
+			if let Option::Some(filling) = self.subdivision {
+				return fractals::divergence::subdivision::limit_on_screen_subdivided(
+					degree,
+					self.threshold,
+					self.iterations,
+					size,
+					self.position,
+					zoom,
+					self.thread_count,
+					refinement,
+					sample,
+					filling,
+					control,
+				);
+			}
+
+			let method: fractals::divergence::LimitMethod = match self.method_id {
+				1 => fractals::divergence::LimitMethod::Julia,
+				_ => fractals::divergence::LimitMethod::Mandelbrot,
+			};
+			return fractals::divergence::maths::limit_on_screen_power(
+				method,
+				self.constant,
+				degree,
+				self.threshold,
+				self.iterations,
+				size,
+				self.position,
+				zoom,
+				self.thread_count,
+				refinement,
+				sample,
+				control,
+			);
+		}
+
+		match &self.expression {
+			Option::Some(expression) => {
+				let expression: expressions::Expression = expression.clone();
+				self.limit_table(move |z, c| expression.evaluate(z, c), size, zoom, refinement, sample, control)
+			},
+			Option::None => self.limit_table(self.function.clone(), size, zoom, refinement, sample, control),
+		}
+	}
+
+	/// Degree n of the escape time of z^n + c, for the batched kernel. `None` for the other sequences,
+	/// computed by their function.
+	fn escape_degree(self: &Self) -> Option<u32> {
+		match self.recording {
+			fractals::divergence::Recording::Escape => self.degree,
+			_ => Option::None,
+		}
+	}
+
+	/// Color a `tiled` pass of `step`, computed with `zoom`.
+	fn rendered(
+		self: &Self,
+		tiled: fractals::threading::Tiled<computations::State>,
+		size: [usize; 2],
+		zoom: complex::Real,
+		step: usize,
+		color_mode: color::ColorMode,
+	) -> fractals::textures::Rendered {
+		fractals::textures::Rendered {
+			data: fractals::tables::state_table_to_data(
+				tiled.grid, 
+				self.color_stable,
+				self.color_divergent,
+				self.iterations,
+				self.smoothing,
+				color_mode,
+				&self.palette,
+				self.interior_mode,
+				self.light,
+				self.boundary,
+				1.0 / zoom,
+			),
+			size,
+			step,
+			statistics: tiled.statistics,
+			approximation: Option::None,
+		}
+	}
+
+	/// Compute the divergence table of `function` on a grid of `size`, with `zoom`.
+	fn limit_table<G>(
+		self: &Self,
+		function: G,
+		size: [usize; 2],
+		zoom: complex::Real,
+		refinement: &fractals::progressive::Refinement<computations::State>,
+		sample: fractals::antialiasing::Sample,
+		control: &fractals::threading::Control,
+	) -> Option<fractals::threading::Tiled<computations::State>>
+	where
+		G: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
+	{
+		match self.method_id {
 			1 => fractals::divergence::maths::limit_on_screen_julia(
 				self.constant, 
-				self.function.clone(),
+				function,
+				self.derivative.clone(),
 				self.threshold, 
 				self.iterations, 
-				scaled_size,
+				self.recording,
+				self.trap,
+				size,
 				self.position,
-				self.zoom / scale,
+				zoom,
 				self.thread_count,
+				refinement,
+				sample,
+				control,
 			),
 		 	_ => fractals::divergence::maths::limit_on_screen_mandelbrot(
 				self.constant, 
-				self.function.clone(),
+				function,
+				self.derivative.clone(),
 				self.threshold, 
 				self.iterations, 
-				scaled_size,
+				self.recording,
+				self.trap,
+				size,
 				self.position,
-				self.zoom / scale,
+				zoom,
 				self.thread_count,
+				refinement,
+				sample,
+				control,
 			),
+		}
+	}
+}
+
+impl<F> fractals::textures::Fractal for Divergent<F> 
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Clone + Send + 'static,
+{
+	fn update_size(self: &mut Self, new_size: [u32; 2]) -> () {
+		self.size = new_size
+	}
+
+	fn start_render(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> () {
+		let progressive: bool = global_settings.borrow().progressive;
+		// Progressive passes replace the resolution scale.
+		let scale: complex::Real = match progressive {
+			true => 1.0,
+			false => global_settings.borrow().resolution_scale,
+		};
+		self.scale = scale;
+		let scaled_size: [usize; 2] = [
+			(self.size[0] as complex::Real / scale) as usize, 
+			(self.size[1] as complex::Real / scale) as usize
+		];
+		let zoom: complex::Real = self.viewport.zoom / scale;
+		let color_mode: color::ColorMode = global_settings.borrow().color_mode;
+		let antialiasing: fractals::antialiasing::Antialiasing = global_settings.borrow().antialiasing;
+		let adaptive: fractals::antialiasing::Adaptive = global_settings.borrow().adaptive;
+		let palette: palette::Palette = global_settings.borrow().palette.palette.clone();
+		let refinement: fractals::progressive::Refinement<computations::State> = match progressive {
+			true => fractals::progressive::Refinement::coarsest(),
+			false => fractals::progressive::Refinement::full(),
 		};
 
-		let data: computations::Data = fractals::tables::state_table_to_data(
-			table, 
-			self.color_stable,
-			self.color_divergent,
-			self.iterations,
-			global_settings.borrow().color_mode,
-		);
+		// Replacing the task drops, so cancels, the previous one.
+		let generation: Generation<F> = self.generation(color_mode, palette);
+		self.pass_step = 0;
+		self.task = Option::Some(fractals::threading::Background::spawn(
+			move |control: &fractals::threading::Control, publisher: &fractals::threading::Publisher<fractals::textures::Rendered>| {
+				generation.run(
+					scaled_size, 
+					zoom, 
+					color_mode, 
+					antialiasing, 
+					adaptive, 
+					refinement, 
+					control, 
+					|rendered: fractals::textures::Rendered| publisher.publish(rendered),
+				)
+			}
+		));
+	}
+
+	fn register_texture<Facade>(
+        &mut self,
+        gl_context: &Facade,
+        textures: &mut imgui::Textures<imgui_glium_renderer::Texture>,
+    ) -> Result<(), Box<dyn error::Error>>
+    where
+        Facade: backend::Facade,
+    {	
+		let poll: fractals::threading::Poll<fractals::textures::Rendered> = match &self.task {
+			Option::Some(task) => task.poll(),
+			Option::None => return Result::Ok(()),
+		};
+		let (rendered, elapsed, is_done) = match poll {
+			fractals::threading::Poll::Pending => return Result::Ok(()),
+			fractals::threading::Poll::Stopped => {
+				self.task = Option::None;
+				eprintln!("(!) Divergent::register_texture() Render stopped before the end.");
+				return Result::Ok(());
+			},
+			fractals::threading::Poll::Partial(rendered, elapsed) => (rendered, elapsed, false),
+			fractals::threading::Poll::Done(rendered, elapsed) => (rendered, elapsed, true),
+		};
+		self.pass_step = rendered.step;
 
-		self.iterations_total = data.iterations_total;
-		self.generation_time = Option::Some(generation_start.elapsed());
-		
-		// eprintln!(
-		// 	"\r(?) Divergent {} Rendering: zoom={} pos=({}; {})", 
-		// 	self.method_id,
-		// 	self.zoom,
-		// 	self.position[0],
-		// 	self.position[1],
-		// );
+		if is_done {
+			self.task = Option::None;
+			self.iterations_total = rendered.data.iterations_total;
+			self.generation_time = Option::Some(elapsed);
+			self.tile_statistics = rendered.statistics;
+			self.approximation = rendered.approximation;
+		}
 
 		let render_result: Result<imgui::TextureId, Box<dyn error::Error>> = rendering::render_texture(
 			self.texture_id, 
-			data.raw_pixels, 
-			scaled_size, 
+			rendered.data.raw_pixels, 
+			rendered.size, 
 			gl_context, 
 			textures,
 			rendering::ColorFormat::RGB,
@@ -216,22 +856,70 @@ render_texture error {}.", error);
 
 		self.texture_id = render_result.ok();
 
+		// A coarse pass is only shown.
+		if !is_done {
+			return Result::Ok(());
+		}
+
+		let center: [String; 2] = self.viewport.to_decimals();
 		eprintln!(
-			"\r* Divergent {}: t={} zoom={} pos=({}; {}) threads={}", 
+			"\r* Divergent {}: t={} zoom={} center=({}; {}) threads={}", 
 			self.method_id,
 			match self.generation_time {
 				Option::None => "()",
 				Option::Some(elapsed) => &format!("{:?}", elapsed),
 			}, 
-			self.zoom,
-			self.position[0],
-			self.position[1],
+			self.viewport.zoom,
+			center[0],
+			center[1],
 			self.thread_count,
 		);
 
 		Ok(())
 	}
 
+	fn export_image(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> Result<path::PathBuf, Box<dyn error::Error>> {
+		let (export_size, export_path, color_mode, antialiasing, adaptive, palette) = {
+			let settings = global_settings.borrow();
+			(
+				settings.export.size, 
+				path::PathBuf::from(&settings.export.path), 
+				settings.color_mode, 
+				settings.antialiasing, 
+				settings.adaptive, 
+				settings.palette.palette.clone(),
+			)
+		};
+		let size: [usize; 2] = [export_size[0] as usize, export_size[1] as usize];
+		if size[0] == 0 || size[1] == 0 {
+			return Result::Err("(X) Divergent::export_image() Export size must not be empty.".into());
+		}
+		let zoom: complex::Real = export::scaled_zoom(self.viewport.zoom, self.size[0], export_size[0]);
+
+		let rendered: fractals::textures::Rendered = self.generation(color_mode, palette)
+			.run(
+				size, 
+				zoom, 
+				color_mode, 
+				antialiasing, 
+				adaptive, 
+				fractals::progressive::Refinement::full(), 
+				&fractals::threading::Control::new(),
+				|_: fractals::textures::Rendered| (),
+			)
+			.ok_or("(X) Divergent::export_image() Render cancelled.")?;
+
+		export::save_rgb(&export_path, &rendered.data.raw_pixels, size)?;
+		export::save_sidecar(&export_path, &self.parameters(size, zoom, color_mode, antialiasing, adaptive))?;
+
+		eprintln!("* Divergent {}: exported {}", self.method_id, export_path.display());
+
+		Result::Ok(export_path)
+	}
+
 	/// Display the divergent fractal render and rendering information.
 	fn show_textures(
 		&self, 
@@ -254,22 +942,39 @@ render_texture error {}.", error);
             .size(self.information_size, imgui::Condition::FirstUseEver)
 			.position(information_position, imgui::Condition::FirstUseEver)
             .build(|| {
+				if let Option::Some(task) = &self.task {
+					let pass: String = match self.pass_step {
+						0 => String::from("Rendering"),
+						1 => String::from("Antialiasing"),
+						step => format!("Refining to 1/{}", step / 2),
+					};
+					ui.progress_bar(task.progress())
+						.overlay_text(format!("{}... {:.0}%", pass, task.progress() * 100.0))
+						.build();
+				}
+
 				if let Some(generation_time) = self.generation_time 
 					&& generation_time.as_millis() != 0
 				{
 					ui.text(format!("Size: ({}; {});
 Pixels = {:.0}; 
 Iterations = {};
-Time = {}ms;
+{}Time = {}ms;
 Speed = {} iterations/ ms;
-Threads = {}", 
+Threads = {};
+{}", 
 						self.size[0],
 						self.size[1],
 						(self.size[0] * self.size[1]) as complex::Real / self.scale,
 						self.iterations_total,
+						match self.approximation {
+							Option::Some(approximation) => format!("{}\n", approximation),
+							Option::None => String::new(),
+						},
 						generation_time.as_millis(),
 						self.iterations_total as u128 / generation_time.as_millis(),
 						self.thread_count,
+						self.tile_statistics,
 					));
 				} else {
 					ui.text(format!("(!) Error: no data."));
@@ -0,0 +1,144 @@
+//! # Complex sequences.
+//! src/fractals/divergence/trap.rs
+//!
+//! Orbit traps: shapes of the complex plane, measuring how close an orbit gets to them.
+
+use std::{convert, fmt};
+
+use complex;
+use complex::Complex;
+
+use crate::structures::combos::ComboMethod;
+
+/// # `TrapShape` of an `OrbitTrap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapShape {
+	/// The `center` point.
+	Point,
+	/// Line through the `center`, tilted by `angle`.
+	Line,
+	/// Two perpendicular lines through the `center`, tilted by `angle`.
+	Cross,
+	/// Circle of `radius` around the `center`.
+	Circle,
+}
+
+impl TrapShape {
+	/// Returns the name of the shape, as typed on the command-line.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			TrapShape::Point => "point",
+			TrapShape::Line => "line",
+			TrapShape::Cross => "cross",
+			TrapShape::Circle => "circle",
+		}
+	}
+}
+
+impl ComboMethod for TrapShape {
+	/// Return a `Vec` of all the shapes.
+	/// ```rust, no_run
+	/// 1. Point;
+	/// 2. Line;
+	/// 3. Cross;
+	/// 4. Circle.
+	/// ```
+	fn list() -> Vec<TrapShape> {
+		vec![
+			TrapShape::Point,
+			TrapShape::Line,
+			TrapShape::Cross,
+			TrapShape::Circle,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			TrapShape::Point => 1,
+			TrapShape::Line => 2,
+			TrapShape::Cross => 3,
+			TrapShape::Circle => 4,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			TrapShape::Point => "1. Point",
+			TrapShape::Line => "2. Line",
+			TrapShape::Cross => "3. Cross",
+			TrapShape::Circle => "4. Circle",
+		}
+	}
+}
+
+impl fmt::Display for TrapShape {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for TrapShape {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+/// # `OrbitTrap`: a `shape` placed on the complex plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitTrap {
+	pub shape: TrapShape,
+	pub center: complex::Algebraic,
+	/// Radius of the `Circle`.
+	pub radius: complex::Real,
+	/// Tilt of the `Line` and the `Cross`, in degrees.
+	pub angle: complex::Real,
+}
+
+impl Default for OrbitTrap {
+	/// Point at the origin.
+	fn default() -> Self {
+		OrbitTrap::new(TrapShape::Point, complex::Algebraic::new(0.0, 0.0), 1.0, 0.0)
+	}
+}
+
+impl OrbitTrap {
+	pub fn new(
+		shape: TrapShape,
+		center: complex::Algebraic,
+		radius: complex::Real,
+		angle: complex::Real,
+	) -> OrbitTrap {
+		OrbitTrap { shape, center, radius, angle }
+	}
+
+	/// Distance from `z` to the trap.
+	pub fn distance(self: &Self, z: complex::Algebraic) -> complex::Real {
+		let offset: complex::Algebraic = z - self.center;
+		let (sin, cos): (complex::Real, complex::Real) = self.angle.to_radians().sin_cos();
+		// Distances to the line of direction (cos; sin), and to its perpendicular.
+		let across: complex::Real = (offset.imaginary * cos - offset.real * sin).abs();
+		let along: complex::Real = (offset.real * cos + offset.imaginary * sin).abs();
+
+		match self.shape {
+			TrapShape::Point => offset.absolute(),
+			TrapShape::Line => across,
+			TrapShape::Cross => across.min(along),
+			TrapShape::Circle => (offset.absolute() - self.radius).abs(),
+		}
+	}
+}
+
+
+#[test]
+fn test_trap_distances() -> () {
+	let center: complex::Algebraic = complex::Algebraic::new(1.0, 1.0);
+	let z: complex::Algebraic = complex::Algebraic::new(4.0, 5.0);
+	let close = |trap: OrbitTrap, expected: complex::Real| assert!((trap.distance(z) - expected).abs() < 1e-12);
+
+	close(OrbitTrap::new(TrapShape::Point, center, 1.0, 0.0), 5.0);
+	close(OrbitTrap::new(TrapShape::Circle, center, 2.0, 0.0), 3.0);
+	// Horizontal line, then vertical one.
+	close(OrbitTrap::new(TrapShape::Line, center, 1.0, 0.0), 4.0);
+	close(OrbitTrap::new(TrapShape::Line, center, 1.0, 90.0), 3.0);
+	close(OrbitTrap::new(TrapShape::Cross, center, 1.0, 0.0), 3.0);
+}
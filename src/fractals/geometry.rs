@@ -1,6 +1,12 @@
 //! # Complex sequences.
 //! src/fractals/geometry.rs
 
+use std::error;
+
+use complex;
+
+use crate::structures::precision;
+
 /// From a `pixel` position, on a screen of `size`, with camera `zoom` and `position`,
 /// get the corresponding point in the complex plane.
 pub fn position_from_pixel(
@@ -9,8 +15,135 @@ pub fn position_from_pixel(
 	zoom: complex::Real,
 	camera_position: [complex::Real; 2],
 ) -> [complex::Real; 2] {
+	let offset: [complex::Real; 2] = offset_from_pixel(pixel, size, zoom);
+
 	[
-		(pixel[0] - size[0] / 2.0) / zoom - camera_position[0], 
-		(pixel[1] - size[1] / 2.0) / zoom - camera_position[1],
+		offset[0] - camera_position[0], 
+		offset[1] - camera_position[1],
 	]
 }
+
+/// From a `pixel` position, on a screen of `size`, with camera `zoom`, 
+/// get its offset to the center of the screen in the complex plane.
+pub fn offset_from_pixel(
+	pixel: [complex::Real; 2], 
+	size: [complex::Real; 2],
+	zoom: complex::Real,
+) -> [complex::Real; 2] {
+	[
+		(pixel[0] - size[0] / 2.0) / zoom, 
+		(pixel[1] - size[1] / 2.0) / zoom,
+	]
+}
+
+/// # `Viewport` of the complex plane: its `center` at arbitrary precision, and its `zoom`.
+/// The center is as precise as the pixels of the zoom need, so a location can be copied 
+/// and pasted as decimals at any depth. The zoom, in pixels per unit, is a float: its exponent 
+/// goes as deep as the offsets of the pixels to the center, computed as floats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+	pub center: [precision::Fixed; 2],
+	pub zoom: complex::Real,
+}
+
+impl Viewport {
+	/// Viewport from the float camera `position`, the opposite of the center, as used by `position_from_pixel`.
+	pub fn new(position: [complex::Real; 2], zoom: complex::Real) -> Viewport {
+		let fraction: usize = precision::Fixed::fraction_for_zoom(zoom);
+
+		Viewport {
+			center: [
+				precision::Fixed::from_f64(-position[0], fraction),
+				precision::Fixed::from_f64(-position[1], fraction),
+			],
+			zoom,
+		}
+	}
+
+	/// Parse a center `text` "real,imaginary" of decimals, e.g. copied by `to_text`, at `zoom`.
+	pub fn parse(text: &str, zoom: complex::Real) -> Result<Viewport, Box<dyn error::Error>> {
+		let fraction: usize = precision::Fixed::fraction_for_zoom(zoom);
+
+		match text.split_once(',') {
+			Option::Some((real, imaginary)) => Result::Ok(Viewport {
+				center: [precision::Fixed::parse(real, fraction)?, precision::Fixed::parse(imaginary, fraction)?],
+				zoom,
+			}),
+			Option::None => Result::Err(format!("(X) Expected `real,imaginary`, got `{}`.", text).into()),
+		}
+	}
+
+	/// Center as a text "real,imaginary", for `parse` and the `--center` option.
+	pub fn to_text(self: &Self) -> String {
+		let [real, imaginary] = self.to_decimals();
+
+		format!("{},{}", real, imaginary)
+	}
+
+	/// Camera position, rounded to floats, for `position_from_pixel`.
+	pub fn get_position(self: &Self) -> [complex::Real; 2] {
+		[-self.center[0].to_f64(), -self.center[1].to_f64()]
+	}
+
+	/// Decimals telling apart the thousandths of a pixel.
+	pub fn decimals(self: &Self) -> usize {
+		self.zoom.max(1.0).log10().ceil() as usize + 3
+	}
+
+	/// Center as decimals, [real, imaginary].
+	pub fn to_decimals(self: &Self) -> [String; 2] {
+		let decimals: usize = self.decimals();
+
+		[self.center[0].to_decimal(decimals), self.center[1].to_decimal(decimals)]
+	}
+
+	/// Point of the complex plane at a `pixel`, on a screen of `size`, at arbitrary precision.
+	pub fn point_from_pixel(
+		self: &Self, 
+		pixel: [complex::Real; 2], 
+		size: [complex::Real; 2],
+	) -> [precision::Fixed; 2] {
+		let fraction: usize = precision::Fixed::fraction_for_zoom(self.zoom);
+		let offset: [complex::Real; 2] = offset_from_pixel(pixel, size, self.zoom);
+
+		[
+			self.center[0].add(&precision::Fixed::from_f64(offset[0], fraction)),
+			self.center[1].add(&precision::Fixed::from_f64(offset[1], fraction)),
+		]
+	}
+
+	/// Move the center by an `offset` in the complex plane.
+	pub fn translate(self: &mut Self, offset: [complex::Real; 2]) -> () {
+		let fraction: usize = precision::Fixed::fraction_for_zoom(self.zoom);
+
+		for (coordinate, offset) in self.center.iter_mut().zip(offset) {
+			*coordinate = coordinate.add(&precision::Fixed::from_f64(offset, fraction));
+		}
+	}
+}
+
+
+#[test]
+fn test_viewport_decimals() -> () {
+	let mut viewport: Viewport = Viewport::new([0.75, -0.1], 1e20);
+	let center: [String; 2] = [
+		String::from("-0.74364388703715870475219150611477"),
+		String::from("0.13182590420531197049380646747207"),
+	];
+	viewport.center = [
+		precision::Fixed::parse(&center[0], 0).unwrap(), 
+		precision::Fixed::parse(&center[1], 0).unwrap(),
+	];
+
+	// Copied at the zoom, pasted at the same place.
+	let decimals: [String; 2] = viewport.to_decimals();
+	assert_eq!(decimals[0], center[0][..decimals[0].len()]);
+	assert_eq!(viewport.decimals(), 23);
+	assert_eq!(Viewport::parse(&viewport.to_text(), viewport.zoom).unwrap().to_text(), viewport.to_text());
+
+	// One pixel right of the center: the next one, at 1e-20.
+	let point: [precision::Fixed; 2] = viewport.point_from_pixel([501.0, 300.0], [1000.0, 600.0]);
+	viewport.translate([1e-20, 0.0]);
+	assert_eq!(point[0].to_decimal(23), viewport.center[0].to_decimal(23));
+	assert_eq!(point[0].to_decimal(23), "-0.74364388703715870474219");
+}
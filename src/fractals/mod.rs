@@ -3,7 +3,9 @@
 //! 
 //! Compute and generate the graphics.
 
-mod threading;
+pub mod threading;
+pub mod progressive;
+pub mod antialiasing;
 pub mod geometry;
 pub mod textures;
 pub mod tables;
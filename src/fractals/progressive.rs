@@ -0,0 +1,175 @@
+//! # Complex sequences.
+//! src/fractals/progressive.rs
+//!
+//! Progressive rendering: a coarse preview first, refined until the full resolution.
+//!
+//! Passes sample one pixel out of 8, 4, 2 then 1 in both directions. Each sample is spread
+//! over its block, and kept by the next passes: the whole render computes each pixel once.
+
+use std::sync::Arc;
+
+use crate::fractals::threading;
+
+/// Step of the first pass, in pixels: 1/8 of the resolution.
+/// Must divide `threading::TILE_SIZE`, so that blocks don't cross tiles.
+pub const COARSEST_STEP: usize = 8;
+
+/// # `Refinement`: the pass of a progressive render to compute.
+/// Only the pixels multiple of `step` are sampled, and copied over the `step`×`step`
+/// block they anchor.
+#[derive(Clone)]
+pub struct Refinement<T> {
+	/// Side of the blocks, in pixels. 1 is the full resolution.
+	pub step: usize,
+	/// Full grid of the previous pass, of step `2 * step`, whose samples are reused.
+	pub previous: Option<Arc<Vec<Vec<T>>>>,
+	/// Pixels to sample again at full resolution, the others are reused from `previous`.
+	pub mask: Option<Arc<Vec<Vec<bool>>>>,
+}
+
+impl<T> Refinement<T>
+where
+	T: Clone,
+{
+	/// A single pass, at full resolution.
+	pub fn full() -> Refinement<T> {
+		Refinement { step: 1, previous: Option::None, mask: Option::None }
+	}
+
+	/// The first pass of a progressive render.
+	pub fn coarsest() -> Refinement<T> {
+		Refinement { step: COARSEST_STEP, previous: Option::None, mask: Option::None }
+	}
+
+	pub fn is_full(self: &Self) -> bool {
+		self.step <= 1
+	}
+
+	/// The next pass, twice finer, reusing the samples of this pass' `grid`.
+	pub fn refine(self: &Self, grid: Arc<Vec<Vec<T>>>) -> Refinement<T> {
+		Refinement { step: (self.step / 2).max(1), previous: Option::Some(grid), mask: Option::None }
+	}
+
+	/// A full resolution pass over a `grid`, sampling again only the pixels of `mask`.
+	pub fn masked(grid: Arc<Vec<Vec<T>>>, mask: Arc<Vec<Vec<bool>>>) -> Refinement<T> {
+		Refinement { step: 1, previous: Option::Some(grid), mask: Option::Some(mask) }
+	}
+
+	/// Compute the lines of `tile` for this pass.
+	///
+	/// `sample` (x; y) is only called on anchors the previous pass didn't compute,
+	/// or on the pixels of the `mask`.
+	pub fn render_tile<S>(self: &Self, tile: &threading::Tile, mut sample: S) -> Vec<Vec<T>>
+	where
+		S: FnMut(usize, usize) -> T,
+	{
+		let step: usize = self.step.max(1);
+		let mut grid: Vec<Vec<T>> = Vec::with_capacity(tile.end[1] - tile.start[1]);
+
+		for y in tile.start[1]..tile.end[1] {
+			let anchor_y: usize = y - y % step;
+			let mut line: Vec<T> = Vec::with_capacity(tile.end[0] - tile.start[0]);
+
+			for x in tile.start[0]..tile.end[0] {
+				let anchor_x: usize = x - x % step;
+
+				let value: T = if anchor_y != y {
+					grid[anchor_y - tile.start[1]][x - tile.start[0]].clone()
+				} else if anchor_x != x {
+					line[anchor_x - tile.start[0]].clone()
+				} else {
+					match self.reused(x, y) {
+						Option::Some(value) => value,
+						Option::None => sample(x, y),
+					}
+				};
+				line.push(value);
+			}
+
+			grid.push(line);
+		}
+
+		grid
+	}
+
+	/// Compute the lines of `tile` for this pass, sampling its pixels together.
+	///
+	/// `sample_batch` gets all the pixels (x; y) `render_tile` would sample, in its order,
+	/// and returns their values.
+	pub fn render_tile_batched<S>(self: &Self, tile: &threading::Tile, sample_batch: S) -> Vec<Vec<T>>
+	where
+		S: FnOnce(&[[usize; 2]]) -> Vec<T>,
+	{
+		let step: usize = self.step.max(1);
+		let mut pixels: Vec<[usize; 2]> = Vec::new();
+		for y in (tile.start[1]..tile.end[1]).filter(|y| y % step == 0) {
+			for x in (tile.start[0]..tile.end[0]).filter(|x| x % step == 0) {
+				if self.reused(x, y).is_none() {
+					pixels.push([x, y]);
+				}
+			}
+		}
+
+		let mut values: std::vec::IntoIter<T> = sample_batch(&pixels).into_iter();
+		self.render_tile(tile, |_: usize, _: usize| {
+			values.next().expect("(X) fractals::progressive::Refinement::render_tile_batched() Missing sample.")
+		})
+	}
+
+	/// Value of the anchor (x; y) reused from the previous pass, `None` if this pass samples it.
+	pub fn reused(self: &Self, x: usize, y: usize) -> Option<T> {
+		match &self.previous {
+			Option::Some(previous) if !self.is_sampled(x, y) => Option::Some(previous[y][x].clone()),
+			_ => Option::None,
+		}
+	}
+
+	/// An anchor (x; y) of this pass is sampled, or else reused from the previous one.
+	fn is_sampled(self: &Self, x: usize, y: usize) -> bool {
+		let step: usize = self.step.max(1);
+
+		match &self.mask {
+			Option::Some(mask) => mask[y][x],
+			Option::None => x % (2 * step) != 0 || y % (2 * step) != 0,
+		}
+	}
+}
+
+
+#[test]
+fn test_refinement_samples_once() -> () {
+	use std::sync::atomic;
+
+	let size: [usize; 2] = [150, 70];
+	let samples: Arc<atomic::AtomicUsize> = Arc::new(atomic::AtomicUsize::new(0));
+	let mut refinement: Refinement<usize> = Refinement::coarsest();
+
+	let grid: Vec<Vec<usize>> = loop {
+		let refinement_local: Refinement<usize> = refinement.clone();
+		let samples_local: Arc<atomic::AtomicUsize> = samples.clone();
+		let tiled: threading::Tiled<usize> = threading::render_tiles(
+			size,
+			3,
+			&threading::Control::new(),
+			move |tile: &threading::Tile| refinement_local.render_tile(tile, |x, y| {
+				samples_local.fetch_add(1, atomic::Ordering::Relaxed);
+				y * size[0] + x
+			}),
+		).unwrap();
+
+		// Blocks of a coarse pass show their anchor.
+		if refinement.step == 4 {
+			assert_eq!(tiled.grid[7][6], 4 * size[0] + 4);
+		}
+		if refinement.is_full() {
+			break tiled.grid;
+		}
+		refinement = refinement.refine(Arc::new(tiled.grid));
+	};
+
+	let expected: Vec<Vec<usize>> = (0..size[1])
+		.map(|y| (0..size[0]).map(|x| y * size[0] + x).collect())
+		.collect();
+	assert!(grid == expected);
+	assert_eq!(samples.load(atomic::Ordering::Relaxed), size[0] * size[1]);
+}
@@ -8,7 +8,6 @@ use std::{rc, cell};
 use glium;
 use glium::backend::Facade;
 use imgui;
-use complex;
 
 use crate::structures::{configuration};
 use crate::fractals;
@@ -16,18 +15,12 @@ use crate::fractals::textures::Fractal;
 use crate::gui;
 
 /// Draw settings and texture of `Root`.
-pub fn draw<F, D>(
+pub fn draw(
 	settings_state: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
 	ui: &imgui::Ui,
 	// Rc<RefCell<Divergent<impl Fn(Algebraic, Algebraic) -> Algebraic>>>
-	root_texture: rc::Rc<cell::RefCell<fractals::root::Root<F, D>>>,
-	renderer: &mut imgui_glium_renderer::Renderer, 
-	display: &glium::Display<glium::glutin::surface::WindowSurface>,
-) -> () 
-where 
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+	root_texture: rc::Rc<cell::RefCell<fractals::root::Root>>,
+) -> () {
 	// ## Root.
 	gui::settings::show_settings_root(
 		[400.0, 600.0], 
@@ -35,8 +28,6 @@ where
 		settings_state.clone(), 
 		ui, 
 		root_texture.clone(), 
-		renderer, 
-		display
 	);
 
 	root_texture
@@ -45,31 +36,31 @@ where
 }
 
 /// Updated settings and texture of `Root`.
-pub fn update<F, D>(
-	root_texture: rc::Rc<cell::RefCell<fractals::root::Root<F, D>>>,
+pub fn update(
+	root_texture: rc::Rc<cell::RefCell<fractals::root::Root>>,
 	global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
 	_ui: &imgui::Ui,
 	renderer: &mut imgui_glium_renderer::Renderer, 
 	display: &glium::Display<glium::glutin::surface::WindowSurface>,
 	window_size: [u32; 2],
-) -> () 
-where 
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+) -> () {
 	root_texture
 		.borrow_mut()
 		.update_size(window_size);
 
-	// If a setting change, draw the fractal anew.
+	// If a setting change, draw the fractal anew, in the background.
 	if root_texture.borrow_mut().is_state_updated() {
 		root_texture
 			.borrow_mut()
-			.register_texture(
-				display.get_context(), 
-				global_settings.clone(),
-				renderer.textures(),
-			)
-			.expect("(!) gui::default::launch_default() Root: update: can't register texture.");
+			.start_render(global_settings.clone());
 	}
+
+	// Show the render once it is done.
+	root_texture
+		.borrow_mut()
+		.register_texture(
+			display.get_context(), 
+			renderer.textures(),
+		)
+		.expect("(!) gui::default::launch_default() Root: update: can't register texture.");
 }
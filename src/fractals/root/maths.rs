@@ -7,51 +7,54 @@ use complex;
 use complex::{Complex, ToComplex};
 
 use crate::structures::computations;
-use crate::fractals::{geometry};
+use crate::fractals::{antialiasing, geometry, progressive, threading};
+use crate::fractals::root::RootMethod;
+use crate::expressions;
 
 /// # `RootFinder`.
 /// Build and store the roots of a polynomial.  
-pub struct RootFinder<F, D> 
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
-	/// Function (mostly polynomial) we search the root.
-	function: F,
-	/// Derivate of the `function` we search the root.
-	derivative: D,
+/// 
+/// Roots of polynomials are solved before the scan, so their order (and colors) doesn't
+/// depend on the view. Other functions discover them from Newton's endpoints.
+#[derive(Clone)]
+pub struct RootFinder {
+	/// Function (mostly polynomial) we search the root, with its derivative.
+	function: expressions::Holomorphic,
 	threshold: complex::Real,
 	iterations: usize,
 	size: [usize; 2],
 	position: [complex::Real; 2],
 	zoom: complex::Real,
+	/// Factor `a` of the relaxed Newton's method.
+	relaxation: complex::Algebraic,
 	roots: Vec<complex::Algebraic>,
+	/// `roots` are all the roots of `function`, known before the scan.
+	roots_known: bool,
 
 }
 
-impl<F, D> RootFinder<F, D>
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+impl RootFinder {
 	pub fn new(
-		function: F,
-		derivative: D,
+		function: expressions::Holomorphic,
 		threshold: complex::Real,
 		iterations: usize,
 		size: [usize; 2],
 		position: [complex::Real; 2],
 		zoom: complex::Real,
-	) -> RootFinder<F, D> {
+		relaxation: complex::Algebraic,
+	) -> RootFinder {
+		let roots: Option<Vec<complex::Algebraic>> = function.roots();
+
 		RootFinder { 
 			function,
-			derivative,
 			threshold, 
 			iterations,
 			size,
 			position,
 			zoom,
-			roots: Vec::new(), 
+			relaxation,
+			roots_known: roots.is_some(),
+			roots: roots.unwrap_or_default(), 
 		}
 	}
 
@@ -64,9 +67,22 @@ where
 	}
 
 	/// Add a root to `roots` if the given `root` doesn't already exist.
+	/// If the roots are known, snap `root` to the closest one instead.
 	/// 
 	/// Return the root reference.
 	pub fn append_root(self: &mut Self, root: complex::Algebraic) -> complex::Algebraic {
+		if self.roots_known {
+			return self.roots
+				.iter()
+				.copied()
+				.min_by(|a, b| {
+					a.distance_to_squared(root)
+						.partial_cmp(&b.distance_to_squared(root))
+						.unwrap_or(std::cmp::Ordering::Equal)
+				})
+				.unwrap_or(root);
+		}
+
 		let mut reference: complex::Algebraic = root;
 
 		for known in &self.roots {
@@ -83,100 +99,181 @@ where
 		reference
 	}
 
-	/// # Newton's method.
-	/// Try to find a root of `function`:
+	/// # Iterative root finding.
+	/// Try to find a root of `function` with `method`:
 	/// - starting from `z0` complex,
 	/// - holomorphic dynamics with the sequence:
 	/// ```math
 	/// u(0) = z0
 	/// u(n + 1) = u(n) - T(u(n))
-	/// 
-	/// T(z) = f(z) / f'(z) 
 	/// ```
+	/// `T` depends on `method`, cf. `RootFinder::step`.
+	///
 	/// cf. Desmos: https://www.desmos.com/calculator/dhirelyn0y
-	fn newton_method(self: &mut Self, z0: complex::Algebraic) -> computations::IsRoot {
+	fn iterate(self: &Self, z0: complex::Algebraic, method: RootMethod) -> computations::IsRoot {
 		let mut z: complex::Algebraic = z0;
 		let mut count: usize = 0;
-		let mut current_fz: complex::Algebraic = (self.function)(z);
-		let mut current_dz: complex::Algebraic;
+		let mut current_fz: complex::Algebraic = self.function.evaluate(z);
+		// Second starting point of the secant method: one pixel aside.
+		let mut previous: complex::Algebraic = z0 + complex::Algebraic::new(1.0 / self.zoom, 0.0);
+		let mut previous_fz: complex::Algebraic = self.function.evaluate(previous);
 
-		while count < self.iterations 
-			&& current_fz.absolute_squared() > self.threshold * self.threshold 	
+		while count < self.iterations
+			&& current_fz.absolute_squared() > self.threshold * self.threshold
 		{
-			current_fz = (self.function)(z);
-			current_dz = (self.derivative)(z);
-			z = z - current_fz / current_dz;
+			let next: complex::Algebraic = z - self.step(method, z, current_fz, previous, previous_fz);
+			previous = z;
+			previous_fz = current_fz;
+			z = next;
+			current_fz = self.function.evaluate(z);
 			count += 1;
-		} 
-
-		current_fz = (self.function)(z);
+		}
 
+		// The endpoint is matched to `roots` by the caller.
 		if current_fz.absolute_squared() <= self.threshold * self.threshold {
-			let reference: complex::Algebraic = self.append_root(z);
-			computations::IsRoot::Yes{ root: reference, iterations: count }
+			computations::IsRoot::Yes{ root: z, iterations: count }
 		} else {
 			computations::IsRoot::No
 		}
 	}
 
+	/// Step `T(z)` of `method`, with `f = f(z)`:
+	/// ```math
+	/// Newton:        f / f'
+	/// Relaxed:       a f / f'
+	/// Halley:        2 f f' / (2 f'² - f f'')
+	/// Householder 3: (6 f f'² - 3 f² f'') / (6 f'³ - 6 f f' f'' + f² f''')
+	/// Schröder:      f f' / (f'² - f f'')
+	/// Secant:        f (z - z_prev) / (f - f(z_prev))
+	/// ```
+	fn step(
+		self: &Self,
+		method: RootMethod,
+		z: complex::Algebraic,
+		fz: complex::Algebraic,
+		previous: complex::Algebraic,
+		previous_fz: complex::Algebraic,
+	) -> complex::Algebraic {
+		let real = |value: complex::Real| complex::Algebraic::new(value, 0.0);
+
+		match method {
+			RootMethod::Newton | RootMethod::Position => fz / self.function.evaluate_derivative(z),
+			RootMethod::RelaxedNewton => self.relaxation * fz / self.function.evaluate_derivative(z),
+			RootMethod::Halley => {
+				let d1: complex::Algebraic = self.function.evaluate_derivative(z);
+				let d2: complex::Algebraic = self.function.evaluate_derivative_order(z, 2);
+
+				real(2.0) * fz * d1 / (real(2.0) * d1 * d1 - fz * d2)
+			},
+			RootMethod::Householder => {
+				let d1: complex::Algebraic = self.function.evaluate_derivative(z);
+				let d2: complex::Algebraic = self.function.evaluate_derivative_order(z, 2);
+				let d3: complex::Algebraic = self.function.evaluate_derivative_order(z, 3);
+
+				(real(6.0) * fz * d1 * d1 - real(3.0) * fz * fz * d2)
+					/ (real(6.0) * d1 * d1 * d1 - real(6.0) * fz * d1 * d2 + fz * fz * d3)
+			},
+			RootMethod::Schroder => {
+				let d1: complex::Algebraic = self.function.evaluate_derivative(z);
+				let d2: complex::Algebraic = self.function.evaluate_derivative_order(z, 2);
+
+				fz * d1 / (d1 * d1 - fz * d2)
+			},
+			RootMethod::Secant => fz * (z - previous) / (fz - previous_fz),
+		}
+	}
+
 	/// # Path of each in point of screen.
-	/// *Single threaded*.
-	/// 
-	/// Compute the limit for each point in `size` [width, height].
-	/// 
+	/// Compute the limit for each point in `size` [width, height], with an iterative `method`,
+	/// by tiles on at most `thread_count` workers of the `threading::pool()`.
+	///
 	/// It is Newton's like, which is:
 	/// - `z0` is `pixel.x + i*pixel.y`,
 	/// - returns a 2D table of "arrivals" `Vec<Vec<IsRoot>>`, coordinates of the root reached.
-	pub fn limit_on_screen_newton(self: &mut Self) -> Vec<Vec<computations::IsRoot>> {
-		let mut grid: Vec<Vec<computations::IsRoot>> = Vec::with_capacity(self.size[0] * self.size[1]);
+	///
+	/// Tiles only return raw endpoints: they are matched to `roots` afterwards, in the
+	/// order of a single thread, so the table doesn't depend on `thread_count`.
+	/// 
+	/// Only the pixels of the `refinement` pass are computed, the others are copied.
+	/// 
+	/// `None` if `control` is cancelled.
+	pub fn limit_on_screen_root(
+		self: &mut Self,
+		method: RootMethod,
+		thread_count: usize,
+		refinement: &progressive::Refinement<computations::IsRoot>,
+		sample: antialiasing::Sample,
+		control: &threading::Control,
+	) -> Option<threading::Tiled<computations::IsRoot>> {
+		let root_finder: RootFinder = self.clone();
+		let refinement: progressive::Refinement<computations::IsRoot> = refinement.clone();
+		let mut tiled: threading::Tiled<computations::IsRoot> = threading::render_tiles(
+			self.size,
+			thread_count,
+			control,
+			move |tile: &threading::Tile| root_finder.limit_on_screen_root_part(method, tile, &refinement, sample),
+		)?;
 
-		for y in 0..self.size[1] {
-			let mut line: Vec<computations::IsRoot> = Vec::with_capacity(self.size[0]); 
-			
-			for x in 0..self.size[0] {
-				let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
-					[x as complex::Real, y as complex::Real], 
-					[self.size[0] as complex::Real, self.size[1] as complex::Real], 
-					self.zoom, 
-					self.position
-				);
-				line.push(self.newton_method(complex::Algebraic::new(
-					complex_position[0], 
-					complex_position[1]
-				)));
+		for line in tiled.grid.iter_mut() {
+			for point in line.iter_mut() {
+				if let computations::IsRoot::Yes { root, .. } = point {
+					*root = self.append_root(*root);
+				}
 			}
-
-			grid.push(line);
 		}
 
-		grid
+		Option::Some(tiled)
+	}
+
+	/// Compute the raw endpoints of a screen `tile`, at the resolution of the `refinement` pass.
+	fn limit_on_screen_root_part(
+		self: &Self,
+		method: RootMethod,
+		tile: &threading::Tile,
+		refinement: &progressive::Refinement<computations::IsRoot>,
+		sample: antialiasing::Sample,
+	) -> Vec<Vec<computations::IsRoot>> {
+		refinement.render_tile(tile, |x: usize, y: usize| self.iterate(self.pixel_to_complex(sample.pixel(x, y)), method))
 	}
 
 	/// # Color the result of each point through `function`.
-	/// *Single threaded.*
+	/// By tiles on at most `thread_count` workers of the `threading::pool()`.
 	/// 
 	/// Use `Polar` complex representation:
 	/// - distance to origin is brightness (full white is 0);
 	/// - angle (theta, argument) is the color on the HSV wheel.
-	pub fn limit_on_screen_position(self: &Self) -> Vec<Vec<complex::Polar>> {
-		let mut grid: Vec<Vec<complex::Polar>> = Vec::with_capacity(self.size[0] * self.size[1]);
+	/// 
+	/// `None` if `control` is cancelled.
+	pub fn limit_on_screen_position(
+		self: &Self,
+		thread_count: usize,
+		sample: antialiasing::Sample,
+		control: &threading::Control,
+	) -> Option<threading::Tiled<complex::Polar>> {
+		let root_finder: RootFinder = self.clone();
+
+		threading::render_tiles(
+			self.size,
+			thread_count,
+			control,
+			move |tile: &threading::Tile| root_finder.limit_on_screen_position_part(tile.start, tile.end, sample),
+		)
+	}
 
-		for y in 0..self.size[1] {
-			let mut line: Vec<complex::Polar> = Vec::with_capacity(self.size[0]); 
+	/// Compute the `Polar` values of a screen part, from point `start` to `end`.
+	fn limit_on_screen_position_part(
+		self: &Self,
+		start: [usize; 2],
+		end: [usize; 2],
+		sample: antialiasing::Sample,
+	) -> Vec<Vec<complex::Polar>> {
+		let mut grid: Vec<Vec<complex::Polar>> = Vec::with_capacity(end[1] - start[1]);
+
+		for y in start[1]..end[1] {
+			let mut line: Vec<complex::Polar> = Vec::with_capacity(end[0] - start[0]); 
 			
-			for x in 0..self.size[0] {
-				let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
-					[x as complex::Real, y as complex::Real], 
-					[self.size[0] as complex::Real, self.size[1] as complex::Real], 
-					self.zoom, 
-					self.position
-				);
-				let z = complex::Algebraic::new(
-					complex_position[0], 
-					complex_position[1]
-				);
-
-				line.push((self.function)(z).to_polar())
+			for x in start[0]..end[0] {
+				line.push(self.function.evaluate(self.pixel_to_complex(sample.pixel(x, y))).to_polar())
 			}
 
 			grid.push(line);
@@ -184,4 +281,40 @@ where
 
 		grid
 	}
-}
\ No newline at end of file
+
+	/// Point of the complex plane under the `pixel` position.
+	fn pixel_to_complex(self: &Self, pixel: [complex::Real; 2]) -> complex::Algebraic {
+		let complex_position: [complex::Real; 2] = geometry::position_from_pixel(
+			pixel, 
+			[self.size[0] as complex::Real, self.size[1] as complex::Real], 
+			self.zoom, 
+			self.position
+		);
+
+		complex::Algebraic::new(complex_position[0], complex_position[1])
+	}
+}
+
+#[test]
+fn test_threads_match_single_thread() -> () {
+	// Not a polynomial: roots are discovered during the scan.
+	let function: expressions::Holomorphic = expressions::Holomorphic::parse("sin(z) - 0.5").unwrap();
+	let new_root_finder = || RootFinder::new(
+		function.clone(),
+		1e-3,
+		40,
+		[200, 150],
+		[0.3, -0.1],
+		8.0,
+		complex::Algebraic::new(1.0, 0.0),
+	);
+
+	let mut single: RootFinder = new_root_finder();
+	let expected: Vec<Vec<computations::IsRoot>> = single.limit_on_screen_root(RootMethod::Newton, 1, &progressive::Refinement::full(), antialiasing::Sample::center(), &threading::Control::new()).unwrap().grid;
+	for thread_count in [2, 3, 7] {
+		let mut multiple: RootFinder = new_root_finder();
+		assert!(multiple.limit_on_screen_root(RootMethod::Newton, thread_count, &progressive::Refinement::full(), antialiasing::Sample::center(), &threading::Control::new()).unwrap().grid == expected);
+		assert!(multiple.get_roots() == single.get_roots());
+	}
+	assert!(single.get_roots().len() > 1);
+}
@@ -11,26 +11,71 @@ pub enum RootMethod {
 	/// Use the Newton method (`f(x)/f'(x)`)to find a root.
 	Newton,
 	/// Color map of the complex plane through the function.
-	Position
+	Position,
+	/// Halley's method, cubic convergence (uses `f''`).
+	Halley,
+	/// Householder's method of order 3, quartic convergence (uses `f''` and `f'''`).
+	Householder,
+	/// Schröder's method, quadratic convergence even on multiple roots.
+	Schroder,
+	/// Secant method, without derivative.
+	Secant,
+	/// Newton's method relaxed by a complex factor `a`: `z - a f(z)/f'(z)`.
+	RelaxedNewton,
+}
+
+impl RootMethod {
+	/// Returns the name of the method, as typed on the command-line.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			RootMethod::Newton => "newton",
+			RootMethod::Position => "position",
+			RootMethod::Halley => "halley",
+			RootMethod::Householder => "householder",
+			RootMethod::Schroder => "schroder",
+			RootMethod::Secant => "secant",
+			RootMethod::RelaxedNewton => "relaxed",
+		}
+	}
+
+	/// Returns `true` if the method iterates towards roots, `false` for `Position`.
+	pub fn is_iterative(self: &Self) -> bool {
+		*self != RootMethod::Position
+	}
 }
 
 impl ComboMethod for RootMethod {
 	/// Return a `Vec` of all the methods.
 	/// ```rust, no_run
 	/// 1. Newton;
-	/// 2. Position.
+	/// 2. Position;
+	/// 3. Halley;
+	/// 4. Householder;
+	/// 5. Schröder;
+	/// 6. Secant;
+	/// 7. Relaxed Newton.
 	/// ```
 	fn list() -> Vec<RootMethod> {
 		vec![
 			RootMethod::Newton,
 			RootMethod::Position,
+			RootMethod::Halley,
+			RootMethod::Householder,
+			RootMethod::Schroder,
+			RootMethod::Secant,
+			RootMethod::RelaxedNewton,
 		]
 	}
-	
+
 	fn id(self: &Self) -> u32 {
 		match &self {
 			RootMethod::Newton => 1,
 			RootMethod::Position => 2,
+			RootMethod::Halley => 3,
+			RootMethod::Householder => 4,
+			RootMethod::Schroder => 5,
+			RootMethod::Secant => 6,
+			RootMethod::RelaxedNewton => 7,
 		}
 	}
 
@@ -39,6 +84,11 @@ impl ComboMethod for RootMethod {
 		match &self {
 			RootMethod::Newton => "1. Newton",
 			RootMethod::Position => "2. Position",
+			RootMethod::Halley => "3. Halley",
+			RootMethod::Householder => "4. Householder (order 3)",
+			RootMethod::Schroder => "5. Schröder",
+			RootMethod::Secant => "6. Secant",
+			RootMethod::RelaxedNewton => "7. Relaxed Newton",
 		}
 	}
 }
@@ -53,4 +103,4 @@ impl convert::AsRef<str> for RootMethod {
 	fn as_ref(self: &Self) -> &str {
 		&self.to_static_str()
 	}
-}
\ No newline at end of file
+}
@@ -3,7 +3,7 @@
 //! 
 //! Create fractals with the destination of sequence.
 
-mod maths;
+pub mod maths;
 pub mod method;
 pub mod texture;
 pub mod app;
@@ -3,66 +3,73 @@
 //!
 //! Draw the texture for a Newton fractal.
 
-use std::{cell, error, rc, time};
+use std::{cell, error, path, rc, time};
+use std::sync::Arc;
 
 use glium::{self, backend};
 use imgui;
 use imgui_glium_renderer;
 use complex;
 
-use crate::structures::{configuration, color, computations};
+use crate::structures::{configuration, color, computations, palette};
+use crate::structures::combos::ComboMethod;
 use crate::fractals;
-use crate::support::rendering;
+use crate::expressions;
+use crate::support::{export, rendering};
 
 
 /// # `Root`, drawing board for `imgui`.
-pub struct Root<F, D> 
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
-	function: F,
-	derivative: D,
+pub struct Root {
+	/// Function we search the roots, with its derivative.
+	function: expressions::Holomorphic,
+	/// Error of the last compilation of `formula`, if any.
+	formula_error: Option<String>,
 	texture_id: Option<imgui::TextureId>,
 	
 	iterations_total: usize,
 	generation_time: Option<time::Duration>,
+	thread_count: usize,
+	tile_statistics: fractals::threading::TileStatistics,
+	/// Render in progress, in the background, with the number of roots found.
+	task: Option<fractals::threading::Background<(fractals::textures::Rendered, usize)>>,
+	/// Step of the last pass shown, 0 before the first one.
+	pass_step: usize,
 
 	// Parameters.
 	pub size: [u32; 2],
 	/// Size: [width, height].
 	pub information_size: [f32; 2],
 	pub scale: complex::Real,
-	pub position: [complex::Real; 2],
-	pub zoom: complex::Real,
+	/// Center, at arbitrary precision, and zoom.
+	pub viewport: fractals::geometry::Viewport,
 	pub iterations: usize,
 	pub threshold: complex::Real,
 	pub method_id: usize,
 	pub degree0: f64,
+	/// Factor `a` of the relaxed Newton's method.
+	pub relaxation: complex::Algebraic,
+	/// Text of the function `f(z)`.
+	pub formula: String,
 
 	// Variables to check if state is modified.
-	zoom_last: complex::Real,
-	position_last: [complex::Real; 2],
+	function_last: expressions::Holomorphic,
+	viewport_last: fractals::geometry::Viewport,
 	iterations_last: usize,
 	threshold_last: complex::Real,
 	method_id_last: usize,
 	scale_last: complex::Real,
 	degree0_last: f64,
+	relaxation_last: complex::Algebraic,
 
 	/// Graphics.
 	color_no_root: color::Rgb,
 
 }
 
-impl<F, D> Root<F, D> 
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+impl Root {
 	/// Instantiate and returns a link to a new `Root`.
 	pub fn new(
-		function: F,
-		derivative: D,
+		function: expressions::Holomorphic,
 		information_size: [f32; 2],
 		position: [complex::Real; 2], 
 		scale: complex::Real,
@@ -71,30 +78,36 @@ where
 		threshold: complex::Real,
 		method_id: usize,
 		color_no_root: color::Rgb,
-	) -> rc::Rc<cell::RefCell<Root<F, D>>> {
+	) -> rc::Rc<cell::RefCell<Root>> {
 		rc::Rc::new(cell::RefCell::new(Root {
+			formula: function.get_function().to_string(),
+			function_last: function.clone(),
 			function,
-			derivative,
+			formula_error: Option::None,
 			iterations_total: 0usize,
 			texture_id: Option::None,
 			size: [0, 0],
 			information_size, 
 			scale,
 			generation_time: Option::None,
-			position,
-			zoom,
+			thread_count: 0,
+			tile_statistics: Default::default(),
+			task: Option::None,
+			pass_step: 0,
+			viewport: fractals::geometry::Viewport::new(position, zoom),
 			iterations,
 			threshold,
 			method_id,
 			degree0: 0.0,
+			relaxation: complex::Algebraic::new(1.0, 0.0),
 
-			zoom_last: 1.0,
-			position_last: [0.0, 0.0],
+			viewport_last: fractals::geometry::Viewport::new([0.0, 0.0], 1.0),
 			iterations_last: 0,
 			threshold_last: 0.0,
 			method_id_last: 0,
 			scale_last: 1.0,
 			degree0_last: 0.0,
+			relaxation_last: complex::Algebraic::new(1.0, 0.0),
 
 			color_no_root,
 		}))
@@ -106,10 +119,8 @@ where
 	pub fn is_state_updated(self: &mut Self) -> bool {
 		let mut updated: bool = true;
 
-		if self.zoom_last != self.zoom {
-			self.zoom_last = self.zoom;
-		} else if self.position_last != self.position {
-			self.position_last = self.position;
+		if self.viewport_last != self.viewport {
+			self.viewport_last = self.viewport.clone();
 		} else if self.iterations_last != self.iterations {
 			self.iterations_last = self.iterations;
 		} else if self.threshold_last != self.threshold {
@@ -120,81 +131,411 @@ where
 			self.scale_last = self.scale;
 		} else if self.degree0_last != self.degree0 {
 			self.degree0_last = self.degree0;
+		} else if self.relaxation_last != self.relaxation {
+			self.relaxation_last = self.relaxation;
+		} else if self.function_last != self.function {
+			self.function_last = self.function.clone();
 		} else {	
 			updated = false;
 		}
 
 		updated
 	}
+
+	/// Compile `formula` into the function, and derive it.
+	/// 
+	/// On error, the previous function is kept and the error is stored in `formula_error`.
+	pub fn compile_formula(self: &mut Self) -> () {
+		match expressions::Holomorphic::parse(&self.formula) {
+			Result::Ok(function) => {
+				self.function = function;
+				self.formula_error = Option::None;
+			},
+			Result::Err(error) => {
+				self.formula_error = Option::Some(error.to_string());
+			},
+		}
+	}
+
+	/// Error of the last compilation of `formula`, if any.
+	pub fn get_formula_error(self: &Self) -> Option<&str> {
+		self.formula_error.as_deref()
+	}
+
+	pub fn get_function(self: &Self) -> &expressions::Holomorphic {
+		&self.function
+	}
+
+	/// Copy the parameters of the fractal into a `Generation`, which can run on any thread.
+	fn generation(self: &mut Self, palette: palette::Palette) -> Generation {
+		self.thread_count = fractals::threading::determine_threads().into();
+
+		Generation {
+			function: self.function.clone(),
+			threshold: self.threshold,
+			iterations: self.iterations,
+			position: self.viewport.get_position(),
+			method_id: self.method_id,
+			degree0: self.degree0,
+			relaxation: self.relaxation,
+			thread_count: self.thread_count,
+			color_no_root: self.color_no_root,
+			palette,
+		}
+	}
+
+	/// Parameters of a render of `size` and `zoom`, for the export sidecar.
+	fn parameters(
+		self: &Self, 
+		size: [usize; 2], 
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		antialiasing: fractals::antialiasing::Antialiasing,
+		adaptive: fractals::antialiasing::Adaptive,
+	) -> Vec<(&'static str, String)> {
+		let method: String = match fractals::root::RootMethod::list().get(self.method_id) {
+			Option::Some(method) => method.to_string(),
+			Option::None => format!("{}", self.method_id),
+		};
+		// Decimals of the center, as precise as the pixels of the render.
+		let center: [String; 2] = fractals::geometry::Viewport { center: self.viewport.center.clone(), zoom }.to_decimals();
+
+		vec![
+			("family", String::from("\"root\"")),
+			("method", format!("\"{}\"", method)),
+			("formula", format!("\"{}\"", self.function.get_function())),
+			("size", format!("[{}, {}]", size[0], size[1])),
+			("center", format!("[{}, {}]", center[0], center[1])),
+			("zoom", format!("{}", zoom)),
+			("iterations", format!("{}", self.iterations)),
+			("threshold", format!("{}", self.threshold)),
+			("degree0", format!("{}", self.degree0)),
+			("relaxation", format!("[{}, {}]", self.relaxation.real, self.relaxation.imaginary)),
+			("color_mode", format!("\"{}\"", color_mode.to_name())),
+			("antialiasing", format!("\"{}\"", antialiasing.to_name())),
+			("adaptive", match antialiasing {
+				fractals::antialiasing::Antialiasing::Adaptive => format!(
+					"{{ threshold = {}, samples = {} }}", 
+					adaptive.threshold, 
+					adaptive.samples,
+				),
+				_ => String::from("\"none\""),
+			}),
+		]
+	}
+}
+
+/// # `Generation` of a `Root` fractal.
+/// A copy of its parameters, to compute it in the background.
+struct Generation {
+	function: expressions::Holomorphic,
+	threshold: complex::Real,
+	iterations: usize,
+	position: [complex::Real; 2],
+	method_id: usize,
+	degree0: f64,
+	relaxation: complex::Algebraic,
+	thread_count: usize,
+	color_no_root: color::Rgb,
+	palette: palette::Palette,
+}
+
+impl Generation {
+	/// Compute the fractal on a grid of `size` [width, height], with `zoom`, into raw RGB `Data`.
+	/// 
+	/// Passes from `refinement` to the full resolution; each coarser one is given to `publish`.
+	/// The roots found are kept from a pass to the next.
+	/// `Position` is a single evaluation per pixel, always rendered at once.
+	/// The full resolution is supersampled with `antialiasing`: `Adaptive` gives it to `publish`
+	/// before sampling again its contrasted pixels, and is a single sample for `Position`.
+	/// 
+	/// Returns the render and the number of roots found, `None` if `control` is cancelled.
+	fn run<P>(
+		self: &Self,
+		size: [usize; 2],
+		zoom: complex::Real,
+		color_mode: color::ColorMode,
+		antialiasing: fractals::antialiasing::Antialiasing,
+		adaptive: fractals::antialiasing::Adaptive,
+		mut refinement: fractals::progressive::Refinement<computations::IsRoot>,
+		control: &fractals::threading::Control,
+		publish: P,
+	) -> Option<(fractals::textures::Rendered, usize)> 
+	where
+		P: Fn((fractals::textures::Rendered, usize)) -> (),
+	{
+		let mut root_finder: fractals::root::maths::RootFinder = fractals::root::maths::RootFinder::new(
+			self.function.clone(), 
+			self.threshold,
+			self.iterations, 
+			size, 
+			self.position, 
+			zoom,
+			self.relaxation,
+		);
+
+		let method: fractals::root::RootMethod = fractals::root::RootMethod::list()
+			.get(self.method_id)
+			.copied()
+			.unwrap_or(fractals::root::RootMethod::Newton);
+
+		let mut statistics: fractals::threading::TileStatistics = fractals::threading::TileStatistics::default();
+
+		if method == fractals::root::RootMethod::Position {
+			let data: computations::Data = fractals::antialiasing::supersample(
+				antialiasing,
+				control,
+				|sample: fractals::antialiasing::Sample| {
+					let tiled: fractals::threading::Tiled<complex::Polar> = root_finder
+						.limit_on_screen_position(self.thread_count, sample, control)?;
+					statistics = tiled.statistics;
+					// Converters accumulate their data: one per sample.
+					let mut position_converter: fractals::tables::PositionConverter;
+					position_converter = fractals::tables::PositionConverter::new(self.degree0);
+
+					Option::Some(position_converter.convert(tiled.grid))
+				},
+			)?;
+
+			return Option::Some((
+				fractals::textures::Rendered { data, size, step: 1, statistics, approximation: Option::None },
+				root_finder.get_roots().len(),
+			));
+		}
+
+		loop {
+			// Antialiased, the full resolution is computed sample by sample, below.
+			if refinement.is_full() && antialiasing.is_uniform() {
+				break;
+			}
+
+			let tiled: fractals::threading::Tiled<computations::IsRoot> = root_finder
+				.limit_on_screen_root(method, self.thread_count, &refinement, fractals::antialiasing::Sample::center(), control)?;
+
+			if refinement.is_full() && antialiasing == fractals::antialiasing::Antialiasing::Adaptive {
+				publish(self.rendered(
+					&root_finder,
+					fractals::threading::Tiled { grid: tiled.grid.clone(), statistics: tiled.statistics },
+					size,
+					refinement.step,
+					color_mode,
+				));
+				control.restart_progress();
+				return self.resampled(root_finder, method, tiled, size, color_mode, adaptive, control);
+			}
+			if refinement.is_full() {
+				return Option::Some(self.rendered(&root_finder, tiled, size, refinement.step, color_mode));
+			}
+
+			let grid: Arc<Vec<Vec<computations::IsRoot>>> = Arc::new(tiled.grid);
+			publish(self.rendered(
+				&root_finder,
+				fractals::threading::Tiled { grid: grid.as_ref().clone(), statistics: tiled.statistics },
+				size,
+				refinement.step,
+				color_mode,
+			));
+			refinement = refinement.refine(grid);
+			control.restart_progress();
+		}
+
+		// The colors depend on all the roots: every sample is found before any is colored.
+		let mut grids: Vec<fractals::threading::Tiled<computations::IsRoot>> = Vec::new();
+		for (index, sample) in antialiasing.samples().into_iter().enumerate() {
+			if index > 0 {
+				control.restart_progress();
+			}
+			grids.push(root_finder.limit_on_screen_root(
+				method, 
+				self.thread_count, 
+				&fractals::progressive::Refinement::full(), 
+				sample, 
+				control,
+			)?);
+		}
+
+		let mut grids = grids.into_iter();
+		let data: computations::Data = fractals::antialiasing::supersample(
+			antialiasing,
+			&fractals::threading::Control::new(),
+			|_: fractals::antialiasing::Sample| {
+				let tiled: fractals::threading::Tiled<computations::IsRoot> = grids.next()?;
+				statistics = tiled.statistics;
+
+				Option::Some(self.rendered(&root_finder, tiled, size, 1, color_mode).0.data)
+			},
+		)?;
+
+		Option::Some((
+			fractals::textures::Rendered { data, size, step: 1, statistics, approximation: Option::None },
+			root_finder.get_roots().len(),
+		))
+	}
+
+	/// Sample again the pixels of a full resolution `tiled` pass contrasted with their neighbors,
+	/// with the `method` of `root_finder`, and average them.
+	/// 
+	/// Returns the render and the number of roots found, `None` if `control` is cancelled.
+	fn resampled(
+		self: &Self,
+		mut root_finder: fractals::root::maths::RootFinder,
+		method: fractals::root::RootMethod,
+		tiled: fractals::threading::Tiled<computations::IsRoot>,
+		size: [usize; 2],
+		color_mode: color::ColorMode,
+		adaptive: fractals::antialiasing::Adaptive,
+		control: &fractals::threading::Control,
+	) -> Option<(fractals::textures::Rendered, usize)> {
+		let mut statistics: fractals::threading::TileStatistics = tiled.statistics;
+		let mut resampling: fractals::antialiasing::Resampling<computations::IsRoot>;
+		resampling = fractals::antialiasing::Resampling::new(adaptive, tiled.grid);
+
+		for (index, sample) in resampling.samples().into_iter().enumerate() {
+			if index > 0 {
+				control.restart_progress();
+			}
+			let tiled: fractals::threading::Tiled<computations::IsRoot> = root_finder
+				.limit_on_screen_root(method, self.thread_count, &resampling.refinement(), sample, control)?;
+			statistics = tiled.statistics;
+			resampling.push(tiled.grid);
+		}
+
+		// The colors depend on all the roots: the samples are colored once all are found.
+		let data: computations::Data = resampling.average(
+			self.iterations,
+			|grid: Vec<Vec<computations::IsRoot>>| self.rendered(
+				&root_finder,
+				fractals::threading::Tiled { grid, statistics },
+				size,
+				1,
+				color_mode,
+			).0.data,
+		);
+
+		Option::Some((
+			fractals::textures::Rendered { data, size, step: 1, statistics, approximation: Option::None },
+			root_finder.get_roots().len(),
+		))
+	}
+
+	/// Color a `tiled` pass of `step`, with the roots of `root_finder`.
+	/// 
+	/// Returns the render and the number of roots found.
+	fn rendered(
+		self: &Self,
+		root_finder: &fractals::root::maths::RootFinder,
+		tiled: fractals::threading::Tiled<computations::IsRoot>,
+		size: [usize; 2],
+		step: usize,
+		color_mode: color::ColorMode,
+	) -> (fractals::textures::Rendered, usize) {
+		let mut newton_converter: fractals::tables::NewtonConverter;
+		newton_converter = fractals::tables::NewtonConverter::new(
+			root_finder.get_roots(),
+			root_finder.get_threshold(),
+			self.color_no_root,
+			self.iterations,
+			color_mode,
+			self.palette.clone(),
+		);
+
+		(
+			fractals::textures::Rendered {
+				data: newton_converter.convert(tiled.grid),
+				size,
+				step,
+				statistics: tiled.statistics,
+				approximation: Option::None,
+			},
+			root_finder.get_roots().len(),
+		)
+	}
 }
 
-impl<F, D> fractals::textures::Fractal for Root<F, D> 
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+impl fractals::textures::Fractal for Root {
 	fn update_size(self: &mut Self, new_size: [u32; 2]) -> () {
 		self.size = new_size
 	}
 	
+	fn start_render(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> () {
+		let progressive: bool = global_settings.borrow().progressive;
+		// Progressive passes replace the resolution scale.
+		let scale: complex::Real = match progressive {
+			true => 1.0,
+			false => global_settings.borrow().resolution_scale,
+		};
+		self.scale = scale;
+		let scaled_size: [usize; 2] = [
+			(self.size[0] as complex::Real / scale) as usize, 
+			(self.size[1] as complex::Real / scale) as usize,
+		];
+		let zoom: complex::Real = self.viewport.zoom / scale;
+		let color_mode: color::ColorMode = global_settings.borrow().color_mode;
+		let antialiasing: fractals::antialiasing::Antialiasing = global_settings.borrow().antialiasing;
+		let adaptive: fractals::antialiasing::Adaptive = global_settings.borrow().adaptive;
+		let palette: palette::Palette = global_settings.borrow().palette.palette.clone();
+		let refinement: fractals::progressive::Refinement<computations::IsRoot> = match progressive {
+			true => fractals::progressive::Refinement::coarsest(),
+			false => fractals::progressive::Refinement::full(),
+		};
+
+		// Replacing the task drops, so cancels, the previous one.
+		let generation: Generation = self.generation(palette);
+		self.pass_step = 0;
+		self.task = Option::Some(fractals::threading::Background::spawn(
+			move |control: &fractals::threading::Control, publisher: &fractals::threading::Publisher<(fractals::textures::Rendered, usize)>| {
+				generation.run(
+					scaled_size, 
+					zoom, 
+					color_mode, 
+					antialiasing, 
+					adaptive, 
+					refinement, 
+					control, 
+					|result: (fractals::textures::Rendered, usize)| publisher.publish(result),
+				)
+			}
+		));
+	}
+
 	fn register_texture<Facade>(
         &mut self,
         gl_context: &Facade,
-		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
         textures: &mut imgui::Textures<imgui_glium_renderer::Texture>,
     ) -> Result<(), Box<dyn error::Error>>
     where
         Facade: backend::Facade,
     {	
-		let scale: complex::Real = global_settings.borrow().resolution_scale;
-		self.scale = scale;
-		let scaled_size: [usize; 2] = [
-			(self.size[0] as complex::Real / scale) as usize, 
-			(self.size[1] as complex::Real / scale) as usize,
-		];
-		let mut root_finder: fractals::root::maths::RootFinder<&F, &D> = fractals::root::maths::RootFinder::new(
-			&self.function, 
-			&self.derivative, 
-			self.threshold,
-			self.iterations, 
-			scaled_size, 
-			self.position, 
-			self.zoom / scale,
-		);
-			
-		// Texture generation.
-		let generation_start: time::Instant = time::Instant::now();
-
-		let data: computations::Data = match self.method_id {
-			0 => {
-				let table: Vec<Vec<computations::IsRoot>> = root_finder.limit_on_screen_newton();
-				let mut newton_converter: fractals::tables::NewtonConverter;
-				newton_converter = fractals::tables::NewtonConverter::new(
-					root_finder.get_roots(),
-					root_finder.get_threshold(),
-					self.color_no_root,
-					self.iterations,
-					global_settings.borrow().color_mode,
-				);
-
-				newton_converter.convert(table)
-			},
-			_ => {
-				let table: Vec<Vec<complex::Polar>> = root_finder.limit_on_screen_position();
-				let mut position_converter: fractals::tables::PositionConverter;
-				position_converter = fractals::tables::PositionConverter::new(self.degree0);
-
-				position_converter.convert(table)
+		let poll: fractals::threading::Poll<(fractals::textures::Rendered, usize)> = match &self.task {
+			Option::Some(task) => task.poll(),
+			Option::None => return Result::Ok(()),
+		};
+		let ((rendered, root_count), elapsed, is_done) = match poll {
+			fractals::threading::Poll::Pending => return Result::Ok(()),
+			fractals::threading::Poll::Stopped => {
+				self.task = Option::None;
+				eprintln!("(!) Root::register_texture() Render stopped before the end.");
+				return Result::Ok(());
 			},
+			fractals::threading::Poll::Partial(result, elapsed) => (result, elapsed, false),
+			fractals::threading::Poll::Done(result, elapsed) => (result, elapsed, true),
 		};
+		self.pass_step = rendered.step;
 
-		self.iterations_total = data.iterations_total;
-		self.generation_time = Option::Some(generation_start.elapsed());
+		if is_done {
+			self.task = Option::None;
+			self.iterations_total = rendered.data.iterations_total;
+			self.generation_time = Option::Some(elapsed);
+			self.tile_statistics = rendered.statistics;
+		}
 		
 		let render_result = rendering::render_texture(
 			self.texture_id, 
-			data.raw_pixels, 
-			scaled_size, 
+			rendered.data.raw_pixels, 
+			rendered.size, 
 			gl_context, 
 			textures,
 			rendering::ColorFormat::RGB,
@@ -206,18 +547,24 @@ render_texture error {}.", error);
 
 		self.texture_id = render_result.ok();
 
-		let root_count: usize = root_finder.get_roots().len();
+		// A coarse pass is only shown.
+		if !is_done {
+			return Result::Ok(());
+		}
+
+		let center: [String; 2] = self.viewport.to_decimals();
 		eprintln!(
-			"* Root {}: t={} zoom={} pos=({}; {}) roots={}", 
+			"* Root {}: t={} zoom={} center=({}; {}) roots={} threads={}", 
 			self.method_id,
 			match self.generation_time {
 				Option::None => "()",
 				Option::Some(elapsed) => &format!("{:?}", elapsed),
 			}, 
-			self.zoom,
-			self.position[0],
-			self.position[1],
+			self.viewport.zoom,
+			center[0],
+			center[1],
 			root_count,
+			self.thread_count,
 		);
 
 		if root_count > 23 {
@@ -227,6 +574,48 @@ render_texture error {}.", error);
 		Ok(())
 	}
 
+	fn export_image(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> Result<path::PathBuf, Box<dyn error::Error>> {
+		let (export_size, export_path, color_mode, antialiasing, adaptive, palette) = {
+			let settings = global_settings.borrow();
+			(
+				settings.export.size, 
+				path::PathBuf::from(&settings.export.path), 
+				settings.color_mode, 
+				settings.antialiasing, 
+				settings.adaptive, 
+				settings.palette.palette.clone(),
+			)
+		};
+		let size: [usize; 2] = [export_size[0] as usize, export_size[1] as usize];
+		if size[0] == 0 || size[1] == 0 {
+			return Result::Err("(X) Root::export_image() Export size must not be empty.".into());
+		}
+		let zoom: complex::Real = export::scaled_zoom(self.viewport.zoom, self.size[0], export_size[0]);
+
+		let (rendered, _) = self.generation(palette)
+			.run(
+				size, 
+				zoom, 
+				color_mode, 
+				antialiasing, 
+				adaptive, 
+				fractals::progressive::Refinement::full(), 
+				&fractals::threading::Control::new(),
+				|_: (fractals::textures::Rendered, usize)| (),
+			)
+			.ok_or("(X) Root::export_image() Render cancelled.")?;
+
+		export::save_rgb(&export_path, &rendered.data.raw_pixels, size)?;
+		export::save_sidecar(&export_path, &self.parameters(size, zoom, color_mode, antialiasing, adaptive))?;
+
+		eprintln!("* Root {}: exported {}", self.method_id, export_path.display());
+
+		Result::Ok(export_path)
+	}
+
 	/// Display the root fractal render and rendering information.
 	fn show_textures(&self, ui: &imgui::Ui, information_position: [f32; 2]) {
         let draw_list_background: imgui::DrawListMut<'_> = ui.get_background_draw_list();
@@ -242,6 +631,17 @@ render_texture error {}.", error);
             .size(self.information_size, imgui::Condition::FirstUseEver)
 			.position(information_position, imgui::Condition::FirstUseEver)
             .build(|| {
+				if let Option::Some(task) = &self.task {
+					let pass: String = match self.pass_step {
+						0 => String::from("Rendering"),
+						1 => String::from("Antialiasing"),
+						step => format!("Refining to 1/{}", step / 2),
+					};
+					ui.progress_bar(task.progress())
+						.overlay_text(format!("{}... {:.0}%", pass, task.progress() * 100.0))
+						.build();
+				}
+
 				if let Some(generation_time) = self.generation_time 
 					&& generation_time.as_millis() != 0
 				{
@@ -249,13 +649,17 @@ render_texture error {}.", error);
 Pixels = {:.0}; 
 Iterations = {};
 Time = {:?}ms;
-Speed = {} iterations/ ms;", 
+Speed = {} iterations/ ms;
+Threads = {};
+{}", 
 						self.size[0],
 						self.size[1],
 						(self.size[0] * self.size[1]) as complex::Real / self.scale,
 						self.iterations_total,
 						generation_time.as_millis(),
 						self.iterations_total as u128 / generation_time.as_millis(),
+						self.thread_count,
+						self.tile_statistics,
 					));
 				} else {
 					ui.text(format!("(!) Error: no data."));
@@ -5,50 +5,229 @@ use std::ops::Rem;
 
 use complex::{self, Complex};
 
-use crate::structures::{color, computations};
+use crate::structures::{color, computations, palette};
+
+/// Normalized, fractional, iteration count of a point escaped after `iterations` at |z| = `modulus`,
+/// for a sequence of `degree` d at infinity:
+/// ```math
+/// n + 1 - ln(ln |z|) / ln(d)
+/// ```
+/// Continuous across the bands of the integer count: one more iteration raises |z| to the power d.
+fn smooth_iterations(iterations: usize, modulus: f64, degree: f64) -> f64 {
+	if modulus <= 1.0 {
+		return iterations as f64;
+	}
+
+	(iterations as f64 + 1.0 - modulus.ln().ln() / degree.ln()).max(0.0)
+}
+
+/// Closeness in ]0; 1] of an orbit to its trap, from the `distance` of its closest approach.
+fn trap_weight(distance: f64) -> f64 {
+	(-4.0 * distance).exp()
+}
+
+/// # `Histogram` of the fractional iteration counts of the divergent points of a table.
+/// `cumulative[n]` is the fraction of divergent points of a fractional count below `n + 1`:
+/// binned in the same unit as the counts looked up by `weight`.
+struct Histogram {
+	cumulative: Vec<f64>,
+}
+
+impl Histogram {
+	/// Histogram of the `table`, smoothed for a sequence of `degree` at infinity.
+	fn new(table: &Vec<Vec<computations::State>>, iterations_max: usize, degree: f64) -> Histogram {
+		let mut counts: Vec<usize> = vec![0; iterations_max + 1];
+		let mut total: usize = 0;
+
+		for line in table {
+			for state in line {
+				if let computations::State::Divergent{ iterations, modulus }
+					| computations::State::Estimated{ iterations, modulus, .. } = state
+				{
+					let smooth: f64 = smooth_iterations(*iterations, *modulus, degree);
+					counts[(smooth.floor() as usize).min(iterations_max)] += 1;
+					total += 1;
+				}
+			}
+		}
+
+		let mut cumulative: Vec<f64> = Vec::with_capacity(counts.len());
+		let mut sum: usize = 0;
+		for count in counts {
+			sum += count;
+			cumulative.push(if total > 0 { sum as f64 / total as f64 } else { 0.0 });
+		}
+
+		Histogram { cumulative }
+	}
+
+	/// Equalized weight in [0; 1] of a fractional iteration count `smooth`,
+	/// interpolated between the cumulative fractions of its neighbouring counts.
+	fn weight(self: &Self, smooth: f64) -> f64 {
+		let last: usize = self.cumulative.len() - 1;
+		let index: usize = (smooth.max(0.0).floor() as usize).min(last);
+		let fraction: f64 = (smooth - index as f64).clamp(0.0, 1.0);
+		let start: f64 = if index > 0 { self.cumulative[index - 1] } else { 0.0 };
+
+		start + (self.cumulative[index] - start) * fraction
+	}
+}
+
+/// Color of a point escaped after `iterations` at |z| = `modulus`, with `color_mode`,
+/// for a sequence of `degree`.
+fn escape_color(
+	iterations: usize,
+	modulus: f64,
+	degree: f64,
+	divergent: color::Rgb,
+	iterations_max: usize,
+	color_mode: color::ColorMode,
+	palette: &palette::Palette,
+	histogram: &Option<Histogram>,
+) -> color::Rgb {
+	let weight: f64 = iterations as f64 / iterations_max as f64;
+
+	match color_mode {
+		color::ColorMode::HISTOGRAM => {
+			let weight: f64 = match histogram {
+				Option::Some(histogram) => histogram.weight(smooth_iterations(iterations, modulus, degree)),
+				Option::None => weight,
+			};
+			palette.color_at(weight)
+		},
+		color::ColorMode::PALETTE => {
+			palette.color_at(smooth_iterations(iterations, modulus, degree) / iterations_max as f64)
+		},
+		color::ColorMode::OKLCH => {
+			let weight: f64 = (smooth_iterations(iterations, modulus, degree) / iterations_max as f64).clamp(0.0, 1.0);
+			color::Oklch::new(
+				0.3 + 0.6 * weight,
+				0.12,
+				weight * 360.0,
+			).to_rgb()
+		},
+		color::ColorMode::SMOOTH => {
+			let weight: f64 = (smooth_iterations(iterations, modulus, degree) / iterations_max as f64).clamp(0.0, 1.0);
+			color::Hsv::new(
+				weight * 359.9, 
+				1.0 - weight, 
+				1.0 - weight,
+			).to_rgb()
+		},
+		color::ColorMode::HSV => {
+			color::Hsv::new(
+				weight * 359.9, 
+				1.0 - weight, 
+				1.0 - weight,
+			).to_rgb()
+		},
+		color::ColorMode::GRAYSCALE => {
+			color::Rgb::new(
+				(divergent.red as f64 * weight) as u8,
+				(divergent.green as f64 * weight) as u8,
+				(divergent.blue as f64 * weight) as u8,
+			)
+		},
+	}
+}
 
 /// Convert a 2D `table`: `Vec<Vec<State>>` into `Vec<u8>` of raw `data`. 
+/// 
+/// Points of estimated distance are lit by the `light`, then covered by the `boundary`, if any, 
+/// drawn with the `stable` color; `pixel_size` is the side of a pixel on the complex plane.
+/// The escape time is smoothed for a sequence of `degree` at infinity.
 pub fn state_table_to_data(
 	table: Vec<Vec<computations::State>>, 
 	stable: color::Rgb, 
 	divergent: color::Rgb,
 	iterations_max: usize,
+	degree: f64,
 	color_mode: color::ColorMode,
+	palette: &palette::Palette,
+	interior_mode: color::InteriorMode,
+	light: color::Light,
+	boundary: Option<color::Boundary>,
+	pixel_size: f64,
 ) -> computations::Data {
 	let mut data: Vec<u8> = Vec::new();
 	let mut iterations_total: usize = 0;
+	let histogram: Option<Histogram> = match color_mode {
+		color::ColorMode::HISTOGRAM => Option::Some(Histogram::new(&table, iterations_max, degree)),
+		_ => Option::None,
+	};
 
 	for line in table {
 		for state in line {
 			match state {
-				computations::State::Divergent{ iterations } => {
-					let weight: f64 = iterations as f64 / iterations_max as f64;
-
-					match color_mode {
-						color::ColorMode::HSV => {
-							let color: color::Rgb = color::Hsv::new(
-								weight * 359.9, 
-								1.0 - weight, 
-								1.0 - weight,
-							).to_rgb();
-							data.push(color.red);
-							data.push(color.green);
-							data.push(color.blue);
-						},
-						color::ColorMode::GRAYSCALE => {
-							let color: color::Rgb = color::Rgb::new(
-								(divergent.red as f64 * weight) as u8,
-								(divergent.green as f64 * weight) as u8,
-								(divergent.blue as f64 * weight) as u8,
-							);
-							data.push(color.red);
-							data.push(color.green);
-							data.push(color.blue);
-						}
+				computations::State::Divergent{ iterations, modulus } => {
+					let color: color::Rgb = escape_color(
+						iterations, 
+						modulus, 
+						degree,
+						divergent, 
+						iterations_max, 
+						color_mode, 
+						palette, 
+						&histogram,
+					);
+					data.push(color.red);
+					data.push(color.green);
+					data.push(color.blue);
+
+					iterations_total += iterations;
+				},
+				computations::State::Estimated{ iterations, modulus, distance, normal } => {
+					let mut color: color::Rgb = escape_color(
+						iterations, 
+						modulus, 
+						degree,
+						divergent, 
+						iterations_max, 
+						color_mode, 
+						palette, 
+						&histogram,
+					);
+					if light.enabled {
+						color = light.shade(&color, [normal.real, normal.imaginary]);
+					}
+					if let Option::Some(boundary) = &boundary {
+						color = color::ColorSpace::Oklab.interpolate(&color, &stable, boundary.coverage(distance / pixel_size));
 					}
 
+					data.push(color.red);
+					data.push(color.green);
+					data.push(color.blue);
+
+					iterations_total += iterations;
+				},
+				computations::State::Periodic{ iterations, period, multiplier } => {
+					let color: color::Rgb = interior_mode.color(period, multiplier, &stable, &divergent);
+					data.push(color.red);
+					data.push(color.green);
+					data.push(color.blue);
+
 					iterations_total += iterations;
 				},
+				computations::State::Trapped{ distance, closest, escaped } => {
+					let weight: f64 = trap_weight(distance);
+					let hue: f64 = (closest as f64 / iterations_max as f64).clamp(0.0, 1.0) * 359.9;
+
+					let color: color::Rgb = match color_mode {
+						color::ColorMode::PALETTE | color::ColorMode::HISTOGRAM => palette.color_at(weight),
+						color::ColorMode::OKLCH => color::Oklch::new(0.15 + 0.75 * weight, 0.12, hue).to_rgb(),
+						color::ColorMode::HSV | color::ColorMode::SMOOTH => color::Hsv::new(hue, 1.0 - weight, weight).to_rgb(),
+						color::ColorMode::GRAYSCALE => color::Rgb::new(
+							(divergent.red as f64 * weight) as u8,
+							(divergent.green as f64 * weight) as u8,
+							(divergent.blue as f64 * weight) as u8,
+						),
+					};
+					data.push(color.red);
+					data.push(color.green);
+					data.push(color.blue);
+
+					iterations_total += escaped.unwrap_or(iterations_max);
+				},
 				computations::State::Stable => {
 					data.push(stable.red);
 					data.push(stable.green);
@@ -72,6 +251,7 @@ pub struct NewtonConverter {
 	no_root_color: color::Rgb,
 	iterations_max: usize,
 	color_mode: color::ColorMode,
+	palette: palette::Palette,
 	data: Vec<u8>,
 	iterations_total: usize,
 	loss_counter: usize,
@@ -85,6 +265,7 @@ impl NewtonConverter {
 		no_root_color: color::Rgb,
 		iterations_max: usize,
 		color_mode: color::ColorMode,
+		palette: palette::Palette,
 	) -> NewtonConverter {
 		NewtonConverter {
 			roots,
@@ -92,6 +273,7 @@ impl NewtonConverter {
 			no_root_color,
 			iterations_max,
 			color_mode,
+			palette,
 			data: Vec::new(),
 			iterations_total: 0,
 			loss_counter: 0,
@@ -127,7 +309,7 @@ impl NewtonConverter {
 				let weight: f64 = iterations as f64 / self.iterations_max as f64;
 				let root_slider: f64 = root_id as f64 / self.roots.len() as f64;
 				let color: color::Rgb = match self.color_mode {
-					color::ColorMode::HSV => {
+					color::ColorMode::HSV | color::ColorMode::SMOOTH => {
 						color::Hsv::new(
 							root_slider * 360.0, 
 							1.0 - weight, 
@@ -136,7 +318,21 @@ impl NewtonConverter {
 					},
 					color::ColorMode::GRAYSCALE => {
 						color::Grayscale::new(root_slider).to_rgb()
-					}
+					},
+					color::ColorMode::PALETTE | color::ColorMode::HISTOGRAM => {
+						self.palette.space.interpolate(
+							&self.palette.color_at(root_slider),
+							&color::Rgb::new(0, 0, 0),
+							weight,
+						)
+					},
+					color::ColorMode::OKLCH => {
+						color::Oklch::new(
+							0.8 * (1.0 - weight),
+							0.13 * (1.0 - weight),
+							root_slider * 360.0,
+						).to_rgb()
+					},
 				};
 
 				self.data.push(color.red);
@@ -235,3 +431,66 @@ impl PositionConverter {
 		}
 	}
 }
+
+
+#[test]
+fn test_smooth_iterations_continuous() -> () {
+	// One more iteration of z^d raises |z| to the power d: the fractional count doesn't move.
+	for degree in [2, 3, 5] {
+		for modulus in [3.0, 40.0, 1e6] {
+			let before: f64 = smooth_iterations(7, modulus, degree as f64);
+			let after: f64 = smooth_iterations(8, modulus.powi(degree), degree as f64);
+			assert!((before - after).abs() < 1e-9);
+		}
+	}
+	assert!(smooth_iterations(7, 1e6, 2.0) < smooth_iterations(7, 3.0, 2.0));
+}
+
+
+#[test]
+fn test_histogram_equalized() -> () {
+	// Deep zoom: every point escapes between 500 and 503 iterations out of 1000,
+	// at |z| = e^1.5, of a fractional count in [n; n + 1[.
+	let table: Vec<Vec<computations::State>> = (500..504)
+		.map(|iterations| vec![computations::State::Divergent{ iterations, modulus: 1.5_f64.exp() }; 10])
+		.collect();
+	let histogram: Histogram = Histogram::new(&table, 1000, 2.0);
+
+	assert_eq!(histogram.weight(499.0), 0.0);
+	assert_eq!(histogram.weight(500.5), 0.125);
+	assert_eq!(histogram.weight(502.0), 0.5);
+	assert_eq!(histogram.weight(503.0), 0.75);
+	assert_eq!(histogram.weight(1000.0), 1.0);
+}
+
+
+#[test]
+fn test_histogram_colors_band() -> () {
+	// Deep zoom: every point escapes between 500 and 503 iterations out of 1000, past the smooth bailout.
+	let table: Vec<Vec<computations::State>> = (500..504)
+		.map(|iterations| (1..=10)
+			.map(|step| computations::State::Divergent{ iterations, modulus: 1e6_f64.powf(1.0 + step as f64 / 10.0) })
+			.collect())
+		.collect();
+	// From black to white on the first half of the gradient: the red channel is the weight.
+	let palette: palette::Palette = palette::Palette::new(
+		vec![
+			palette::Stop::new(0.0, color::Rgb::new(0, 0, 0)),
+			palette::Stop::new(0.5, color::Rgb::new(255, 255, 255)),
+		],
+		palette::Interpolation::Linear,
+		color::ColorSpace::Srgb,
+		0.0,
+		2.0,
+	);
+	let data: computations::Data = state_table_to_data(
+		table, color::Rgb::new(0, 0, 0), color::Rgb::new(255, 255, 255), 1000, 2.0,
+		color::ColorMode::HISTOGRAM, &palette, color::InteriorMode::Flat, Default::default(), Option::None, 1.0,
+	);
+
+	// The weights are equalized over the whole of [0; 1].
+	let weights: Vec<f64> = data.raw_pixels.chunks(3).map(|pixel: &[u8]| pixel[0] as f64 / 255.0).collect();
+	let minimum: f64 = weights.iter().cloned().fold(f64::INFINITY, f64::min);
+	let maximum: f64 = weights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+	assert!(minimum < 0.05 && maximum > 0.95, "Weights in [{}; {}].", minimum, maximum);
+}
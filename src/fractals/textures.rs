@@ -1,30 +1,58 @@
 //! # Complex sequences.
 //! src/fractals/textures.rs
 
-use std::{error, rc, cell};
+use std::{error, path, rc, cell};
 
 use glium; 
 
-use crate::structures::{configuration};
+use crate::structures::{configuration, computations};
+use crate::fractals::{divergence, threading};
+
+/// # `Rendered` fractal, from a background render.
+pub struct Rendered {
+	pub data: computations::Data,
+	/// Size: [width, height].
+	pub size: [usize; 2],
+	/// Step of the progressive pass, in pixels: 1 at full resolution.
+	pub step: usize,
+	pub statistics: threading::TileStatistics,
+	/// Iterations skipped by the series of a perturbed render.
+	pub approximation: Option<divergence::perturbation::Approximation>,
+}
 
 /// # `Fractal` texture trait: update and register.
 pub trait Fractal {
 	fn update_size(self: &mut Self, new_size: [u32; 2]) -> ();
 
-	/// Generate and register the fractal texture.
-	/// 
-	/// Put some `size` if the size need to be updated.
+	/// Start generating the fractal texture in the background.
+	/// The render in progress, if any, is cancelled.
+	fn start_render(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> ();
+
+	/// Register the fractal texture, once the background render is done.
+	/// Does nothing while it is in progress.
 	/// 
 	/// Source: `imgui-examples`, `custom_texture`
 	fn register_texture<Facade>(
         &mut self,
         gl_context: &Facade,
-		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
         textures: &mut imgui::Textures<imgui_glium_renderer::Texture>,
     ) -> Result<(), Box<dyn error::Error>>
     where
         Facade: glium::backend::Facade;
 
+	/// Render the current viewport at the export size of `global_settings`,
+	/// independently of the window size and `resolution_scale`.
+	/// Write the image and a sidecar of the parameters used.
+	/// 
+	/// Returns the path of the written image.
+	fn export_image(
+		self: &mut Self,
+		global_settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	) -> Result<path::PathBuf, Box<dyn error::Error>>;
+
 	/// Calls `window` method on `ui`, to display the texture. 
 	/// 
 	/// Source: `imgui-examples`, `custom_texture`
@@ -1,28 +1,429 @@
 //! # Complex sequences.
 //! src/fractals/threading.rs
+//!
+//! Long-lived worker pool, and tiled screen rendering on it.
+//!
+//! The screen is cut in small `Tile`s, handed out to the workers through an atomic counter:
+//! a worker done with a fast tile (outside of the set) takes the next one, instead of idling
+//! while another finishes a slow band.
+//!
+//! A whole render can run in the `Background`, and be cancelled through its `Control`.
+//! It may `publish` intermediate results, e.g. the coarse passes of a progressive render.
 
-use std::{thread, num};
+use std::{fmt, num, panic, thread, time};
+use std::sync::{atomic, mpsc, Arc, Mutex, OnceLock};
+
+/// Side of a square `Tile`, in pixels.
+pub const TILE_SIZE: usize = 64;
+
+/// # `Tile`: rectangle of the screen, from `start` (included) to `end` (excluded).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+	/// Index of the tile, in row-major order.
+	pub id: usize,
+	pub start: [usize; 2],
+	pub end: [usize; 2],
+}
+
+/// Cut a screen of `size` [width, height] in tiles of `tile_size`, in row-major order.
+pub fn tiles(size: [usize; 2], tile_size: usize) -> Vec<Tile> {
+	let tile_size: usize = tile_size.max(1);
+	let mut tiles: Vec<Tile> = Vec::new();
+
+	for y in (0..size[1]).step_by(tile_size) {
+		for x in (0..size[0]).step_by(tile_size) {
+			tiles.push(Tile {
+				id: tiles.len(),
+				start: [x, y],
+				end: [(x + tile_size).min(size[0]), (y + tile_size).min(size[1])],
+			});
+		}
+	}
+
+	tiles
+}
 
 /// # Named tuple `GenerationPart`.
 /// Useful while work is divided between threads.
 pub struct GenerationPart<T> {
 	pub thread_id: usize,
-	/// (`start`, `end`).
-	#[allow(dead_code)]
-	pub bounding_box: ([usize; 2], [usize; 2]),
+	pub tile: Tile,
 	pub data: Vec<Vec<T>>,
+	/// Time spent computing `data`.
+	pub duration: time::Duration,
 }
 
 impl<T> GenerationPart<T> {
-	pub fn new(thread_id: usize, bounding_box: ([usize; 2], [usize; 2]), data: Vec<Vec<T>>) -> GenerationPart<T> {
-		GenerationPart { 
-			thread_id, 
-			bounding_box, 
-			data 
+	pub fn new(thread_id: usize, tile: Tile, data: Vec<Vec<T>>, duration: time::Duration) -> GenerationPart<T> {
+		GenerationPart {
+			thread_id,
+			tile,
+			data,
+			duration,
+		}
+	}
+}
+
+/// # `TileStatistics` of a tiled render.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TileStatistics {
+	pub tile_count: usize,
+	pub tile_size: usize,
+	pub worker_count: usize,
+	pub fastest: time::Duration,
+	pub slowest: time::Duration,
+	/// `start` of the slowest tile.
+	pub slowest_tile: [usize; 2],
+	/// Sum of the tiles times.
+	pub total: time::Duration,
+}
+
+impl TileStatistics {
+	/// Average time of a tile.
+	pub fn average(self: &Self) -> time::Duration {
+		if self.tile_count == 0 {
+			time::Duration::ZERO
+		} else {
+			self.total / self.tile_count as u32
+		}
+	}
+
+	/// Add the time of one `tile`.
+	fn record(self: &mut Self, tile: &Tile, duration: time::Duration) -> () {
+		if self.tile_count == 0 || duration < self.fastest {
+			self.fastest = duration;
+		}
+		if self.tile_count == 0 || duration > self.slowest {
+			self.slowest = duration;
+			self.slowest_tile = tile.start;
+		}
+
+		self.tile_count += 1;
+		self.total += duration;
+	}
+}
+
+impl fmt::Display for TileStatistics {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			formatter,
+			"Tiles = {} ({} px, {} workers);
+Tile time = {:?} min, {:?} avg, {:?} max at ({}; {});",
+			self.tile_count,
+			self.tile_size,
+			self.worker_count,
+			self.fastest,
+			self.average(),
+			self.slowest,
+			self.slowest_tile[0],
+			self.slowest_tile[1],
+		)
+	}
+}
+
+/// # `Tiled` result: the whole `grid`, and how its tiles went.
+pub struct Tiled<T> {
+	pub grid: Vec<Vec<T>>,
+	pub statistics: TileStatistics,
+}
+
+/// # `Control` of a render, shared with its workers: cancellation and progress.
+#[derive(Clone, Default)]
+pub struct Control {
+	cancelled: Arc<atomic::AtomicBool>,
+	/// Tiles done.
+	done: Arc<atomic::AtomicUsize>,
+	/// Tiles to do.
+	total: Arc<atomic::AtomicUsize>,
+}
+
+impl Control {
+	pub fn new() -> Control {
+		Default::default()
+	}
+
+	/// Ask the workers to stop after their current tile.
+	pub fn cancel(self: &Self) -> () {
+		self.cancelled.store(true, atomic::Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(self: &Self) -> bool {
+		self.cancelled.load(atomic::Ordering::Relaxed)
+	}
+
+	/// Count the tiles anew, e.g. between the passes of a progressive render.
+	/// Only call while no tile is being rendered.
+	pub fn restart_progress(self: &Self) -> () {
+		self.done.store(0, atomic::Ordering::Relaxed);
+		self.total.store(0, atomic::Ordering::Relaxed);
+	}
+
+	/// Fraction of the tiles done, in [0; 1].
+	pub fn progress(self: &Self) -> f32 {
+		let total: usize = self.total.load(atomic::Ordering::Relaxed);
+		if total == 0 {
+			0.0
+		} else {
+			self.done.load(atomic::Ordering::Relaxed) as f32 / total as f32
+		}
+	}
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// # `WorkerPool`: threads kept alive between frames, running `Job`s.
+pub struct WorkerPool {
+	sender: Mutex<mpsc::Sender<Job>>,
+	size: usize,
+}
+
+impl WorkerPool {
+	/// Spawn `size` workers, waiting for jobs.
+	pub fn new(size: usize) -> WorkerPool {
+		let size: usize = size.max(1);
+		let (sender, receiver) = mpsc::channel::<Job>();
+		let receiver: Arc<Mutex<mpsc::Receiver<Job>>> = Arc::new(Mutex::new(receiver));
+
+		for worker_id in 0..size {
+			let receiver_local: Arc<Mutex<mpsc::Receiver<Job>>> = receiver.clone();
+
+			thread::Builder::new()
+				.name(format!("fractal-worker-{}", worker_id))
+				.spawn(move || loop {
+					// The lock is released as soon as a job is received.
+					let job: Job = match receiver_local.lock() {
+						Result::Ok(guard) => match guard.recv() {
+							Result::Ok(job) => job,
+							Result::Err(_) => break,
+						},
+						Result::Err(_) => break,
+					};
+
+					// Keep the worker alive if a job panics.
+					if panic::catch_unwind(panic::AssertUnwindSafe(job)).is_err() {
+						eprintln!("(!) fractals::threading::WorkerPool worker {} Job panicked.", worker_id);
+					}
+				})
+				.expect("(X) fractals::threading::WorkerPool::new() Couldn't spawn a worker.");
+		}
+
+		WorkerPool {
+			sender: Mutex::new(sender),
+			size,
+		}
+	}
+
+	pub fn get_size(self: &Self) -> usize {
+		self.size
+	}
+
+	/// Queue a `job` for the next free worker.
+	pub fn execute(self: &Self, job: Job) -> () {
+		self.sender
+			.lock()
+			.expect("(X) fractals::threading::WorkerPool::execute() Poisoned sender.")
+			.send(job)
+			.expect("(X) fractals::threading::WorkerPool::execute() Workers are gone.");
+	}
+}
+
+/// The pool shared by every render, with one worker per core.
+pub fn pool() -> &'static WorkerPool {
+	static POOL: OnceLock<WorkerPool> = OnceLock::new();
+
+	POOL.get_or_init(|| WorkerPool::new(determine_threads().into()))
+}
+
+/// # Render a screen of `size` [width, height] by tiles, on the shared `pool`.
+/// At most `thread_count` workers take tiles, one after the other, and `compute` each one
+/// into its lines. The tiles are then glued back into one grid.
+///
+/// Returns `None` if `control` is cancelled before the end.
+///
+/// Must not be called from a job of the pool itself: it waits for the other jobs.
+pub fn render_tiles<T, G>(
+	size: [usize; 2],
+	thread_count: usize,
+	control: &Control,
+	compute: G,
+) -> Option<Tiled<T>>
+where
+	T: Send + 'static,
+	G: Fn(&Tile) -> Vec<Vec<T>> + Clone + Send + 'static,
+{
+	let tiles: Arc<Vec<Tile>> = Arc::new(tiles(size, TILE_SIZE));
+	control.total.fetch_add(tiles.len(), atomic::Ordering::Relaxed);
+	let next: Arc<atomic::AtomicUsize> = Arc::new(atomic::AtomicUsize::new(0));
+	let worker_count: usize = thread_count.clamp(1, pool().get_size()).min(tiles.len().max(1));
+	let (sender, receiver) = mpsc::channel::<GenerationPart<T>>();
+
+	for thread_id in 0..worker_count {
+		let tiles_local: Arc<Vec<Tile>> = tiles.clone();
+		let next_local: Arc<atomic::AtomicUsize> = next.clone();
+		let sender_local: mpsc::Sender<GenerationPart<T>> = sender.clone();
+		let compute_local: G = compute.clone();
+		let control_local: Control = control.clone();
+
+		pool().execute(Box::new(move || loop {
+			if control_local.is_cancelled() {
+				break;
+			}
+
+			let index: usize = next_local.fetch_add(1, atomic::Ordering::Relaxed);
+			let tile: Tile = match tiles_local.get(index) {
+				Option::Some(tile) => *tile,
+				Option::None => break,
+			};
+
+			let tile_start: time::Instant = time::Instant::now();
+			let data: Vec<Vec<T>> = compute_local(&tile);
+			let part: GenerationPart<T> = GenerationPart::new(thread_id, tile, data, tile_start.elapsed());
+			control_local.done.fetch_add(1, atomic::Ordering::Relaxed);
+
+			if sender_local.send(part).is_err() {
+				break;
+			}
+		}));
+	}
+	drop(sender);
+
+	// Collect results.
+	let mut statistics: TileStatistics = TileStatistics {
+		tile_size: TILE_SIZE,
+		worker_count,
+		..Default::default()
+	};
+	let mut parts: Vec<Option<Vec<Vec<T>>>> = (0..tiles.len()).map(|_| Option::None).collect();
+	for part in receiver.iter() {
+		statistics.record(&part.tile, part.duration);
+		parts[part.tile.id] = Option::Some(part.data);
+	}
+	if control.is_cancelled() {
+		return Option::None;
+	}
+	if statistics.tile_count != tiles.len() {
+		panic!(
+			"(X) fractals::threading::render_tiles() Received {} tiles out of {}.",
+			statistics.tile_count,
+			tiles.len(),
+		);
+	}
+
+	// Aggregate results: each band of tiles gives `TILE_SIZE` lines.
+	let mut grid: Vec<Vec<T>> = Vec::with_capacity(size[1]);
+	let columns: usize = size[0].div_ceil(TILE_SIZE).max(1);
+	let mut parts = parts.into_iter().flatten();
+	for band_start in (0..size[1]).step_by(TILE_SIZE) {
+		let band_height: usize = (size[1] - band_start).min(TILE_SIZE);
+		let mut band: Vec<std::vec::IntoIter<Vec<T>>> = (&mut parts)
+			.take(columns)
+			.map(|lines| lines.into_iter())
+			.collect();
+
+		for _ in 0..band_height {
+			let mut line: Vec<T> = Vec::with_capacity(size[0]);
+			for lines in band.iter_mut() {
+				line.extend(lines.next().unwrap_or_default());
+			}
+			grid.push(line);
+		}
+	}
+
+	Option::Some(Tiled { grid, statistics })
+}
+
+/// # `Poll` of a `Background` render.
+pub enum Poll<R> {
+	Pending,
+	/// The latest intermediate result, and the time it took so far.
+	Partial(R, time::Duration),
+	/// The result, and the time it took.
+	Done(R, time::Duration),
+	/// Cancelled, or failed.
+	Stopped,
+}
+
+/// Message of a `Background` job: a result, the time it took, and `true` if it is the last one.
+type Message<R> = (R, time::Duration, bool);
+
+/// # `Publisher` of the intermediate results of a `Background` job.
+pub struct Publisher<R> {
+	sender: mpsc::Sender<Message<R>>,
+	start: time::Instant,
+}
+
+impl<R> Publisher<R> {
+	/// Send an intermediate `result`. Lost if nobody waits for this render anymore.
+	pub fn publish(self: &Self, result: R) -> () {
+		let _ = self.sender.send((result, self.start.elapsed(), false));
+	}
+}
+
+/// # `Background` render: a `job` run on its own thread, which drives the pool.
+/// Dropping it cancels the job.
+pub struct Background<R> {
+	control: Control,
+	receiver: mpsc::Receiver<Message<R>>,
+}
+
+impl<R> Background<R>
+where
+	R: Send + 'static,
+{
+	/// Start `job`, which returns `None` once cancelled through its `Control`.
+	/// It can `publish` intermediate results before.
+	pub fn spawn<J>(job: J) -> Background<R>
+	where
+		J: FnOnce(&Control, &Publisher<R>) -> Option<R> + Send + 'static,
+	{
+		let control: Control = Control::new();
+		let control_local: Control = control.clone();
+		let (sender, receiver) = mpsc::channel::<Message<R>>();
+
+		thread::spawn(move || {
+			let publisher: Publisher<R> = Publisher { sender, start: time::Instant::now() };
+			if let Option::Some(result) = job(&control_local, &publisher) {
+				// The receiver may be gone: nobody waits for this render anymore.
+				let _ = publisher.sender.send((result, publisher.start.elapsed(), true));
+			}
+		});
+
+		Background { control, receiver }
+	}
+
+	pub fn cancel(self: &Self) -> () {
+		self.control.cancel();
+	}
+
+	pub fn progress(self: &Self) -> f32 {
+		self.control.progress()
+	}
+
+	/// Check, without waiting, if the job is over, or has published since the last poll.
+	/// Older intermediate results are skipped.
+	pub fn poll(self: &Self) -> Poll<R> {
+		let mut latest: Poll<R> = Poll::Pending;
+
+		loop {
+			match self.receiver.try_recv() {
+				Result::Ok((result, elapsed, true)) => return Poll::Done(result, elapsed),
+				Result::Ok((result, elapsed, false)) => latest = Poll::Partial(result, elapsed),
+				Result::Err(mpsc::TryRecvError::Empty) => return latest,
+				Result::Err(mpsc::TryRecvError::Disconnected) => return match latest {
+					Poll::Pending => Poll::Stopped,
+					partial => partial,
+				},
+			}
 		}
 	}
 }
 
+impl<R> Drop for Background<R> {
+	fn drop(self: &mut Self) -> () {
+		self.control.cancel();
+	}
+}
+
 /// Always return an `usize`.
 pub fn determine_threads() -> num::NonZero<usize> {
 	match thread::available_parallelism() {
@@ -33,3 +434,45 @@ pub fn determine_threads() -> num::NonZero<usize> {
 		}
 	}
 }
+
+
+#[test]
+fn test_render_tiles() -> () {
+	let size: [usize; 2] = [150, 70];
+	let expected: Vec<Vec<usize>> = (0..size[1])
+		.map(|y| (0..size[0]).map(|x| y * size[0] + x).collect())
+		.collect();
+
+	for thread_count in [1, 3, 64] {
+		let tiled: Tiled<usize> = render_tiles(size, thread_count, &Control::new(), move |tile: &Tile| {
+			(tile.start[1]..tile.end[1])
+				.map(|y| (tile.start[0]..tile.end[0]).map(|x| y * size[0] + x).collect())
+				.collect()
+		}).unwrap();
+
+		assert!(tiled.grid == expected);
+		assert_eq!(tiled.statistics.tile_count, tiles(size, TILE_SIZE).len());
+	}
+}
+
+#[test]
+fn test_background_cancel() -> () {
+	let background: Background<usize> = Background::spawn(|control: &Control, _: &Publisher<usize>| {
+		render_tiles([640, 640], 2, control, |tile: &Tile| {
+			thread::sleep(time::Duration::from_millis(5));
+			vec![vec![0usize; tile.end[0] - tile.start[0]]; tile.end[1] - tile.start[1]]
+		}).map(|tiled| tiled.grid.len())
+	});
+	background.cancel();
+
+	let start: time::Instant = time::Instant::now();
+	loop {
+		match background.poll() {
+			Poll::Pending | Poll::Partial(_, _) => thread::sleep(time::Duration::from_millis(1)),
+			Poll::Done(_, _) => panic!("(X) test_background_cancel() A cancelled render finished."),
+			Poll::Stopped => break,
+		}
+		assert!(start.elapsed() < time::Duration::from_secs(5));
+	}
+	assert!(background.progress() < 1.0);
+}
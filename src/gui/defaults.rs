@@ -15,6 +15,7 @@ use complex;
 use crate::structures::{color, configuration};
 use crate::support;
 use crate::fractals;
+use crate::expressions;
 use crate::gui::{debug, grid, mouse};
 
 
@@ -29,7 +30,8 @@ const WINDOW_SIZE: [u32; 2] = [1024, 768];
 pub fn launch_default() -> () {
 	// Workers.
 	let divergent_texture = fractals::divergence::Divergent::new(
-		|z, c| z * z + c,
+		support::defaults::polynomial2_c,
+		Option::Some(support::defaults::POLYNOMIAL2_C_DEGREE),
 		complex::Algebraic::new(0.0, 0.0),
 		[400.0, 100.0], 
 		[0.0, 0.0],
@@ -38,15 +40,15 @@ pub fn launch_default() -> () {
 		50,
 		2.0,
 		0,
-		color::Rgb::new(0, 5, 15),
-		color::Rgb::new(255, 250, 240),
+		support::defaults::COLOR_STABLE,
+		support::defaults::COLOR_DIVERGENT,
 	);
 	// Necessary for the closure.
 	let divergent_texture_update = divergent_texture.clone();
 	
 	let root_texture = fractals::root::Root::new(
-		|z: complex::Algebraic| z * z * z + complex::ONE,
-		|z: complex::Algebraic| complex::Algebraic::new(3.0, 0.0) * z * z,
+		expressions::Holomorphic::parse(support::defaults::ROOT_FORMULA)
+			.expect("(X) gui::defaults::launch_default() Invalid default root formula."),
 		[400.0, 100.0],
 		[0.0, 0.0],
 		1.0,
@@ -54,7 +56,7 @@ pub fn launch_default() -> () {
 		50,
 		1.0,
 		0,
-		color::Rgb::new(0, 0, 0),
+		support::defaults::COLOR_NO_ROOT,
 	);
 	let root_texture_update = root_texture.clone();
 
@@ -110,15 +112,11 @@ pub fn launch_default() -> () {
 					settings_state.clone(), 
 					ui, 
 					divergent_texture.clone(), 
-					renderer, 
-					display
 				),
 				2 => fractals::root::app::draw(
 					settings_state.clone(), 
 					ui, 
 					root_texture.clone(), 
-					renderer, 
-					display
 				),
 				_ => panic!(
 					"(X) gui::defaults::launch_default() `method` ({}) not implemented",
@@ -152,28 +150,24 @@ pub fn launch_default() -> () {
 			match &settings_state_update.borrow().method_id {
 				0 => {},
 				1 => {
-					let viewport_update: support::rendering::ViewportSettings = mouse::listen(
+					let viewport_update: fractals::geometry::Viewport = mouse::listen(
 						&ui,
 						window_size,
-						divergent_texture_update.borrow().position,
-						divergent_texture_update.borrow().zoom,
+						&divergent_texture_update.borrow().viewport,
 						divergent_texture_update.borrow().scale,
 					);
 
-					divergent_texture_update.borrow_mut().position = viewport_update.position;
-					divergent_texture_update.borrow_mut().zoom = viewport_update.zoom;
+					divergent_texture_update.borrow_mut().viewport = viewport_update;
 				},
 				2 => {
 					let viewport_update = mouse::listen(
 						&ui,
 						window_size,
-						root_texture_update.borrow().position,
-						root_texture_update.borrow().zoom,
+						&root_texture_update.borrow().viewport,
 						root_texture_update.borrow().scale,
 					);
 
-					root_texture_update.borrow_mut().position = viewport_update.position;
-					root_texture_update.borrow_mut().zoom = viewport_update.zoom;
+					root_texture_update.borrow_mut().viewport = viewport_update;
 				},
 				_ => panic!("(X) `method` ({}) not implemented. ", settings_state_update.borrow().method_id),
 			}
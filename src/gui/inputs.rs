@@ -6,6 +6,9 @@
 use imgui;
 use complex;
 
+use crate::structures::precision;
+use crate::fractals::geometry;
+
 /// Build a header and 2 sliders for a `complex::Algebraic` number.
 pub fn complex_2_sliders(
 	ui: &imgui::Ui, 
@@ -21,28 +24,44 @@ pub fn complex_2_sliders(
 		.build(&mut number.imaginary);
 }
 
-/// Increment a `value` on click.
-pub fn button_increment(
+/// Edit the `axis` coordinate of the center of a `viewport` as decimals, 
+/// moved by a pixel with "-" and "+" buttons.
+pub fn button_decimal(
 	ui: &imgui::Ui,
-	value: &mut complex::Real,
-	increment: complex::Real,
-	label: &str
+	viewport: &mut geometry::Viewport,
+	axis: usize,
+	label: &str,
 ) -> () {
-	if ui.button(label) {
-		*value += increment;
+	let mut increment: [complex::Real; 2] = [0.0, 0.0];
+	increment[axis] = 1.0 / viewport.zoom;
+
+	if ui.button(format!("{} (-)", label)) {
+		viewport.translate([-increment[0], -increment[1]]);
+	}
+	// Applied on enter; `imgui` keeps the text while it is edited.
+	let mut text: String = viewport.to_decimals()[axis].clone();
+	if ui.input_text(label, &mut text).enter_returns_true(true).build() {
+		match precision::Fixed::parse(&text, viewport.center[axis].get_fraction()) {
+			Result::Ok(coordinate) => viewport.center[axis] = coordinate,
+			Result::Err(error) => eprintln!("(!) gui::inputs::button_decimal() {}", error),
+		}
+	}
+	if ui.button(format!("{} (+)", label)) {
+		viewport.translate(increment);
 	}
 }
 
-/// Update a value using "-" and "+" buttons.
-pub fn button_slider(
-	ui: &imgui::Ui,
-	value: &mut complex::Real,
-	increment: complex::Real,
-	label: &str,
-) -> () {
-	button_increment(ui, value, -increment, &format!("{} (-)", label));
-	ui.slider_config(label, 10.0, -10.0)
-		.build(value);
-		//.build(value);
-	button_increment(ui, value, increment, &format!("{} (+)", label));
-}
\ No newline at end of file
+/// Copy the center of a `viewport` to the clipboard, or paste it, as "real,imaginary".
+pub fn center_clipboard(ui: &imgui::Ui, viewport: &mut geometry::Viewport) -> () {
+	if ui.button("Copy center") {
+		ui.set_clipboard_text(viewport.to_text());
+	}
+	ui.same_line();
+	if ui.button("Paste center") {
+		match ui.clipboard_text().map(|text| geometry::Viewport::parse(&text, viewport.zoom)) {
+			Option::Some(Result::Ok(pasted)) => *viewport = pasted,
+			Option::Some(Result::Err(error)) => eprintln!("(!) gui::inputs::center_clipboard() {}", error),
+			Option::None => eprintln!("(!) gui::inputs::center_clipboard() Empty clipboard."),
+		}
+	}
+}
@@ -13,15 +13,15 @@
 use imgui;
 use complex;
 
-use crate::support::rendering;
+use crate::structures::precision;
+use crate::fractals::geometry;
 
 pub fn listen(
 	ui: &imgui::Ui, 
 	window_size: (u32, u32),
-	position: [complex::Real; 2], 
-	zoom: complex::Real,
+	viewport: &geometry::Viewport, 
 	_scale: complex::Real,
-) -> rendering::ViewportSettings {
+) -> geometry::Viewport {
 	let scroll_strength: complex::Real = 1.5;
 
 	let mouse_position: [complex::Real; 2] = [
@@ -29,21 +29,23 @@ pub fn listen(
 		ui.io().mouse_pos[1] as complex::Real
 	];
 	let mouse_scroll: complex::Real = ui.io().mouse_wheel.into();
-	let mut viewport: rendering::ViewportSettings = rendering::ViewportSettings { position, zoom };
+	let zoom: complex::Real = viewport.zoom;
+	let mut viewport: geometry::Viewport = viewport.clone();
 
 	if ui.is_mouse_clicked(imgui::MouseButton::Middle) {
-		let selection_position: [complex::Real; 2] = [
-			-(mouse_position[0] - window_size.0 as complex::Real / 2.0) / zoom + position[0],
-			-(mouse_position[1] - window_size.1 as complex::Real / 2.0) / zoom + position[1],
-		];
+		// Exact, at any depth.
+		let selection_center: [precision::Fixed; 2] = viewport.point_from_pixel(
+			mouse_position, 
+			[window_size.0 as complex::Real, window_size.1 as complex::Real],
+		);
+		viewport.center = selection_center;
+		let decimals: [String; 2] = viewport.to_decimals();
 
 		println!(
-			"(?) gui::mouse::listen() Left clicked: [{:.2}, {:.2}], Selection: [{:.2}, {:.2}]",
+			"(?) gui::mouse::listen() Left clicked: [{:.2}, {:.2}], Selection: [{}, {}]",
 			mouse_position[0], mouse_position[1],
-			selection_position[0], selection_position[1],
+			decimals[0], decimals[1],
 		);
-		
-		viewport.position = selection_position;
 	} if mouse_scroll != 0.0 {
 		viewport.zoom = zoom * scroll_strength.powf(mouse_scroll);
 		println!("(?) gui::mouse::listen() Mouse scrolled: {}", mouse_scroll);
@@ -7,14 +7,13 @@ use std::{
 	rc,
 	cell,
 	borrow,
+	path,
 };
 
-use glium;
-use glium::backend::Facade;
 use imgui;
 use complex;
 
-use crate::structures::{configuration, color};
+use crate::structures::{configuration, color, palette};
 use crate::structures::combos::ComboMethod;
 use crate::fractals;
 use crate::fractals::textures::Fractal;
@@ -55,6 +54,287 @@ fn color_mode_selector(
 	settings.borrow_mut().color_mode_id = color_mode_id;
 }
 
+/// Palette editor, when the color mode is `PALETTE`: interpolation, cycling, stops and file.
+/// 
+/// Returns `true` if the palette changed.
+fn palette_editor(
+	ui: &imgui::Ui, 
+	settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>
+) -> bool {
+	if !settings.borrow().color_mode.uses_palette() {
+		return false;
+	}
+
+	let mut changed: bool = false;
+	let mut global_settings = settings.borrow_mut();
+	let configuration::PaletteSettings { palette: gradient, path: file, status } = &mut global_settings.palette;
+
+	ui.separator();
+	ui.text_wrapped("## Palette");
+
+	// Interpolation and cycling.
+	let mut interpolation_id: usize = palette::Interpolation::list()
+		.iter()
+		.position(|interpolation| *interpolation == gradient.interpolation)
+		.unwrap_or(0);
+	if ui.combo(
+		"Interpolation",
+		&mut interpolation_id,
+		&palette::Interpolation::list(),
+		| interpolation: &palette::Interpolation | borrow::Cow::Borrowed(interpolation.as_ref()),
+	) {
+		gradient.interpolation = palette::Interpolation::list()[interpolation_id];
+		changed = true;
+	}
+	let mut space_id: usize = color::ColorSpace::list()
+		.iter()
+		.position(|space| *space == gradient.space)
+		.unwrap_or(0);
+	if ui.combo(
+		"Color space",
+		&mut space_id,
+		&color::ColorSpace::list(),
+		| space: &color::ColorSpace | borrow::Cow::Borrowed(space.as_ref()),
+	) {
+		gradient.space = color::ColorSpace::list()[space_id];
+		changed = true;
+	}
+	changed |= ui.slider_config("Offset", 0.0, 1.0)
+		.build(&mut gradient.offset);
+	changed |= ui.slider_config("Period", 0.01, 10.0)
+		.flags(imgui::SliderFlags::LOGARITHMIC)
+		.build(&mut gradient.period);
+
+	// Stops: color, position, removal.
+	let stop_count: usize = gradient.stops.len();
+	let mut removed: Option<usize> = Option::None;
+	let mut moved: bool = false;
+	for (index, stop) in gradient.stops.iter_mut().enumerate() {
+		let mut components: [f32; 3] = [
+			stop.color.red as f32 / 255.0,
+			stop.color.green as f32 / 255.0,
+			stop.color.blue as f32 / 255.0,
+		];
+		if ui.color_edit3(format!("Stop {}", index), &mut components) {
+			stop.color = color::Rgb::new(
+				(components[0] * 255.0).round() as u8,
+				(components[1] * 255.0).round() as u8,
+				(components[2] * 255.0).round() as u8,
+			);
+			changed = true;
+		}
+
+		changed |= ui.slider_config(format!("Position##{}", index), 0.0, 1.0)
+			.build(&mut stop.position);
+		// Sort once the slider is released, not to swap stops under the cursor.
+		moved |= ui.is_item_deactivated_after_edit();
+
+		if stop_count > 2 {
+			ui.same_line();
+			if ui.button(format!("Remove##{}", index)) {
+				removed = Option::Some(index);
+			}
+		}
+	}
+	if let Option::Some(index) = removed {
+		gradient.stops.remove(index);
+		changed = true;
+	}
+	if moved {
+		gradient.sort();
+		changed = true;
+	}
+	if ui.button("Add stop") {
+		gradient.insert_stop();
+		changed = true;
+	}
+
+	// File.
+	ui.input_text("Palette path", file)
+		.build();
+	if ui.button("Save palette") {
+		*status = Option::Some(match gradient.save(path::Path::new(file.as_str())) {
+			Result::Ok(()) => format!("Saved `{}`.", file),
+			Result::Err(error) => format!("{}", error),
+		});
+	}
+	ui.same_line();
+	if ui.button("Load palette") {
+		*status = Option::Some(match palette::Palette::load(path::Path::new(file.as_str())) {
+			Result::Ok(loaded) => {
+				*gradient = loaded;
+				changed = true;
+				format!("Loaded `{}`.", file)
+			},
+			Result::Err(error) => format!("{}", error),
+		});
+	}
+	if let Option::Some(status) = status {
+		ui.text_wrapped(status.as_str());
+	}
+
+	changed
+}
+
+/// Progressive rendering toggle; the manual resolution scale is only used without it.
+/// Antialiasing of the full resolution, and the settings of the adaptive one.
+fn resolution_controls(
+	ui: &imgui::Ui, 
+	settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>
+) -> () {
+	ui.checkbox("Progressive rendering", &mut settings.borrow_mut().progressive);
+
+	if !settings.borrow().progressive {
+		// Scale.
+		ui.slider_config("Resolution scale", 1.0, 10.0)
+			.build(&mut settings.borrow_mut().resolution_scale);
+	}
+
+	let mut antialiasing_id: usize = fractals::antialiasing::Antialiasing::list()
+		.iter()
+		.position(|antialiasing| *antialiasing == settings.borrow().antialiasing)
+		.unwrap_or(0);
+	if ui.combo(
+		"Antialiasing",
+		&mut antialiasing_id,
+		&fractals::antialiasing::Antialiasing::list(),
+		| antialiasing: &fractals::antialiasing::Antialiasing | borrow::Cow::Borrowed(antialiasing.as_ref()),
+	) {
+		settings.borrow_mut().antialiasing = fractals::antialiasing::Antialiasing::list()[antialiasing_id];
+	}
+
+	if settings.borrow().antialiasing == fractals::antialiasing::Antialiasing::Adaptive {
+		// Iterations of difference with a neighbor.
+		ui.slider_config("Contrast threshold", 0.0, 50.0)
+			.build(&mut settings.borrow_mut().adaptive.threshold);
+		ui.slider_config("Max samples", 2_usize, 64_usize)
+			.build(&mut settings.borrow_mut().adaptive.samples);
+	}
+}
+
+/// Export section: output resolution, path and the "Export PNG..." button.
+/// 
+/// On click, re-render `fractal` at the export resolution and write it to disk.
+fn export_controls<T>(
+	ui: &imgui::Ui, 
+	settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
+	fractal: rc::Rc<cell::RefCell<T>>,
+) -> ()
+where 
+	T: Fractal,
+{
+	ui.separator();
+	ui.text_wrapped("## Export");
+
+	ui.input_scalar_n("Export size", &mut settings.borrow_mut().export.size)
+		.build();
+	ui.input_text("Export path", &mut settings.borrow_mut().export.path)
+		.build();
+
+	if ui.button("Export PNG...") {
+		let export_result = fractal
+			.borrow_mut()
+			.export_image(settings.clone());
+
+		settings.borrow_mut().export.status = Option::Some(match export_result {
+			Result::Ok(path) => format!("Saved `{}`.", path.display()),
+			Result::Err(error) => format!("{}", error),
+		});
+	}
+
+	if let Option::Some(status) = &settings.borrow().export.status {
+		ui.text_wrapped(status);
+	}
+}
+
+/// Recording of the orbits of a `divergent` fractal: escape time, orbit trap and its placement,
+/// or distance estimation and its boundary. Then the light of the normal map.
+fn recording_controls<F>(
+	ui: &imgui::Ui,
+	divergent: &mut fractals::divergence::Divergent<F>,
+) -> ()
+where
+	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic,
+{
+	let mut recording_id: usize = fractals::divergence::Recording::list()
+		.iter()
+		.position(|recording| *recording == divergent.recording)
+		.unwrap_or(0);
+	if ui.combo(
+		"Recording",
+		&mut recording_id,
+		&fractals::divergence::Recording::list(),
+		| recording: &fractals::divergence::Recording | borrow::Cow::Borrowed(recording.as_ref()),
+	) {
+		divergent.recording = fractals::divergence::Recording::list()[recording_id];
+	}
+
+	match divergent.recording {
+		fractals::divergence::Recording::Trap => {
+			let trap: &mut fractals::divergence::trap::OrbitTrap = &mut divergent.trap;
+
+			let mut shape_id: usize = fractals::divergence::trap::TrapShape::list()
+				.iter()
+				.position(|shape| *shape == trap.shape)
+				.unwrap_or(0);
+			if ui.combo(
+				"Trap shape",
+				&mut shape_id,
+				&fractals::divergence::trap::TrapShape::list(),
+				| shape: &fractals::divergence::trap::TrapShape | borrow::Cow::Borrowed(shape.as_ref()),
+			) {
+				trap.shape = fractals::divergence::trap::TrapShape::list()[shape_id];
+			}
+			gui::inputs::complex_2_sliders(ui, "Trap center", -2.0, 2.0, &mut trap.center);
+			match trap.shape {
+				fractals::divergence::trap::TrapShape::Circle => {
+					ui.slider_config("Trap radius", 0.0, 2.0)
+						.build(&mut trap.radius);
+				},
+				fractals::divergence::trap::TrapShape::Line | fractals::divergence::trap::TrapShape::Cross => {
+					ui.slider_config("Trap angle", 0.0, 180.0)
+						.build(&mut trap.angle);
+				},
+				fractals::divergence::trap::TrapShape::Point => {},
+			}
+		},
+		fractals::divergence::Recording::Distance => {
+			let boundary: &mut color::Boundary = &mut divergent.boundary;
+
+			let mut mode_id: usize = color::BoundaryMode::list()
+				.iter()
+				.position(|mode| *mode == boundary.mode)
+				.unwrap_or(0);
+			if ui.combo(
+				"Boundary",
+				&mut mode_id,
+				&color::BoundaryMode::list(),
+				| mode: &color::BoundaryMode | borrow::Cow::Borrowed(mode.as_ref()),
+			) {
+				boundary.mode = color::BoundaryMode::list()[mode_id];
+			}
+			// Thickness in pixels, whatever the zoom.
+			ui.slider_config("Thickness (px)", 0.25, 16.0)
+				.flags(imgui::SliderFlags::LOGARITHMIC)
+				.build(&mut boundary.thickness);
+		},
+		fractals::divergence::Recording::Escape => {},
+	}
+
+	// Normal map, from the derivative of the orbits: not with traps.
+	if divergent.recording != fractals::divergence::Recording::Trap {
+		let light: &mut color::Light = &mut divergent.light;
+
+		ui.checkbox("Lighting", &mut light.enabled);
+		if light.enabled {
+			ui.slider_config("Light angle", 0.0, 360.0)
+				.build(&mut light.angle);
+			ui.slider_config("Light height", 0.0, 5.0)
+				.build(&mut light.height);
+		}
+	}
+}
+
 /// Show a settings window to read and modify values of the current fractal.
 /// 
 /// Modify a `Divergent`.
@@ -65,8 +345,6 @@ pub fn show_settings_divergent<F>(
 	ui: &imgui::Ui,
 	// Rc<RefCell<Divergent<impl Fn(Algebraic, Algebraic) -> Algebraic>>>
 	divergent_texture: rc::Rc<cell::RefCell<fractals::divergence::Divergent<F>>>,
-	renderer: &mut imgui_glium_renderer::Renderer, 
-	display: &glium::Display<glium::glutin::surface::WindowSurface>,
 ) -> () 
 where
 	F: Fn(complex::Algebraic, complex::Algebraic) -> complex::Algebraic + Copy + Send + 'static,
@@ -76,14 +354,10 @@ where
 		.size(window_size, imgui::Condition::FirstUseEver)
 		.position(window_position, imgui::Condition::FirstUseEver)
 		.build(|| {
-			let current_zoom = divergent_texture.borrow().zoom.clone();
+			let center: [String; 2] = divergent_texture.borrow().viewport.to_decimals();
 
 			ui.text("## Info");
-			ui.text(format!(
-				"- Camera: ({}; {})", 
-				divergent_texture.borrow().position[0], 
-				divergent_texture.borrow().position[1]
-			));
+			ui.text_wrapped(format!("- Center: ({}; {})", center[0], center[1]));
 			
 			ui.separator();
 
@@ -98,44 +372,63 @@ where
 				| limit: &fractals::divergence::LimitMethod | borrow::Cow::Borrowed(limit.as_ref()),
 			);
 			color_mode_selector(ui, settings.clone());
+			if palette_editor(ui, settings.clone()) {
+				divergent_texture
+					.borrow_mut()
+					.start_render(settings.clone());
+			}
+
+			// Formula of the sequence, compiled on each edit.
+			if ui.input_text("f(z, c)", &mut divergent_texture.borrow_mut().formula)
+				.hint("z^2 + c (built-in)")
+				.build()
+			{
+				divergent_texture.borrow_mut().compile_formula();
+			}
+			if let Option::Some(error) = divergent_texture.borrow().get_formula_error() {
+				ui.text_colored([1.0, 0.35, 0.35, 1.0], error);
+			}
+
+			// Deep zoom, of the built-in function only.
+			let is_built_in: bool = divergent_texture.borrow().formula.trim().is_empty();
+			if is_built_in {
+				ui.checkbox("Perturbation (deep zoom)", &mut divergent_texture.borrow_mut().perturbation);
+				if divergent_texture.borrow().perturbation {
+					ui.text_disabled("(?) z^2 + c, escape time only.");
+				}
+			}
+			let is_perturbed: bool = is_built_in && divergent_texture.borrow().perturbation;
+
+			// Rectangle subdivision, of the escape time of the Mandelbrot sets.
+			if !is_perturbed {
+				ui.checkbox("Rectangle subdivision", &mut divergent_texture.borrow_mut().subdivision);
+				if divergent_texture.borrow().subdivision {
+					ui.text_disabled("(?) Mandelbrot of z^n + c from z0 = 0, escape time only.");
+					ui.text_disabled("(?) Fills the escape in grayscale or HSV, the interior if flat.");
+				}
+			}
 
 			// Force update.
 			if ui.button("Force update.") {
 				divergent_texture
 					.borrow_mut()
-					.register_texture(
-						display.get_context(), 
-						settings.clone(),
-						renderer.textures(), 
-					)
-					.expect("(!) gui::default::launch_default() run_ui: can't register texture.");
+					.start_render(settings.clone());
 			}
 
-			// Scale.
-			ui.slider_config("Resolution scale", 1.0, 10.0)
-				.build(&mut settings.borrow_mut().resolution_scale);
+			resolution_controls(ui, settings.clone());
 
-			// Zoom slider.
-			ui.slider_config("Zoom", 1.0, 100000.0)
+			// Zoom slider, deeper by perturbation.
+			ui.slider_config("Zoom", 1.0, if is_perturbed { 1.0e15 } else { 100000.0 })
 				.flags(
 					imgui::SliderFlags::LOGARITHMIC
 					| imgui::SliderFlags::NO_ROUND_TO_FORMAT
-				).build(&mut divergent_texture.borrow_mut().zoom);
+				).build(&mut divergent_texture.borrow_mut().viewport.zoom);
 
-			// (x; y).
-			ui.text("Position");
-			gui::inputs::button_slider(
-				ui, 
-				&mut divergent_texture.borrow_mut().position[0], 
-				1.0 / current_zoom, 
-				"position(x)"
-			);
-			gui::inputs::button_slider(
-				ui, 
-				&mut divergent_texture.borrow_mut().position[1], 
-				1.0 / current_zoom, 
-				"position(y)"
-			);
+			// (x; y), as exact decimals.
+			ui.text("Center");
+			gui::inputs::button_decimal(ui, &mut divergent_texture.borrow_mut().viewport, 0, "center(x)");
+			gui::inputs::button_decimal(ui, &mut divergent_texture.borrow_mut().viewport, 1, "center(y)");
+			gui::inputs::center_clipboard(ui, &mut divergent_texture.borrow_mut().viewport);
 
 			ui.new_line();
 
@@ -156,38 +449,46 @@ where
 				5.0, 
 				&mut divergent_texture.borrow_mut().constant
 			);
+
+			// Color of the bounded points.
+			let mut interior_id: usize = color::InteriorMode::list()
+				.iter()
+				.position(|mode| *mode == divergent_texture.borrow().interior_mode)
+				.unwrap_or(0);
+			if ui.combo(
+				"Interior",
+				&mut interior_id,
+				&color::InteriorMode::list(),
+				| mode: &color::InteriorMode | borrow::Cow::Borrowed(mode.as_ref()),
+			) {
+				divergent_texture.borrow_mut().interior_mode = color::InteriorMode::list()[interior_id];
+			}
+
+			recording_controls(ui, &mut divergent_texture.borrow_mut());
+
+			export_controls(ui, settings.clone(), divergent_texture.clone());
 		});
 }
 
 /// Show a settings window to read and modify values of the current fractal.
 /// 
 /// Modify a `Root`.
-pub fn show_settings_root<F, D>(
+pub fn show_settings_root(
 	window_size: [f32; 2],
 	window_position: [f32; 2],
 	settings: rc::Rc<cell::RefCell<configuration::GlobalSettings>>,
 	ui: &imgui::Ui,
-	root_texture: rc::Rc<cell::RefCell<fractals::root::Root<F, D>>>,
-	renderer: &mut imgui_glium_renderer::Renderer, 
-	display: &glium::Display<glium::glutin::surface::WindowSurface>,
-) -> () 
-where
-	F: Fn(complex::Algebraic) -> complex::Algebraic,
-	D: Fn(complex::Algebraic) -> complex::Algebraic,
-{
+	root_texture: rc::Rc<cell::RefCell<fractals::root::Root>>,
+) -> () {
 	// Window: settings.
 	ui.window("Settings.")
 		.size(window_size, imgui::Condition::FirstUseEver)
 		.position(window_position, imgui::Condition::FirstUseEver)
 		.build(|| {
-			let current_zoom: complex::Real = root_texture.borrow().zoom;
+			let center: [String; 2] = root_texture.borrow().viewport.to_decimals();
 
 			ui.text("## Info");
-			ui.text(format!(
-				"- Camera: ({}; {})", 
-				root_texture.borrow().position[0], 
-				root_texture.borrow().position[1]
-			));
+			ui.text_wrapped(format!("- Center: ({}; {})", center[0], center[1]));
 
 			ui.separator();
 
@@ -202,50 +503,59 @@ where
 				| limit: &fractals::root::RootMethod | borrow::Cow::Borrowed(limit.as_ref()),
 			);
 
-			if root_texture.borrow().method_id == 0 {
+			// Function, derived on each edit.
+			if ui.input_text("f(z)", &mut root_texture.borrow_mut().formula)
+				.hint("z^3 + 1")
+				.build()
+			{
+				root_texture.borrow_mut().compile_formula();
+			}
+			if let Option::Some(error) = root_texture.borrow().get_formula_error() {
+				ui.text_colored([1.0, 0.35, 0.35, 1.0], error);
+			}
+			ui.text_wrapped(format!("f'(z) = {}", root_texture.borrow().get_function().get_derivative()));
+			match root_texture.borrow().get_function().get_polynomial() {
+				Option::Some(polynomial) => ui.text(format!("- Polynomial of degree {}.", polynomial.degree())),
+				Option::None => ui.text("- Roots found on screen."),
+			};
+
+			let method: Option<fractals::root::RootMethod> = fractals::root::RootMethod::list()
+				.get(root_texture.borrow().method_id)
+				.copied();
+			let is_iterative: bool = method.map_or(false, |method| method.is_iterative());
+
+			if is_iterative {
 				color_mode_selector(ui, settings.clone());
+				if palette_editor(ui, settings.clone()) {
+					root_texture.borrow_mut()
+						.start_render(settings.clone());
+				}
 			}
 
 			// Force update.
 			if ui.button("Force update.") {
 				root_texture.borrow_mut()
-					.register_texture(
-						display.get_context(), 
-						settings.clone(),
-						renderer.textures(), 
-					)
-					.expect("(!) gui::default::launch_default() run_ui: can't register texture.");
+					.start_render(settings.clone());
 			}
 
-			// Scale.
-			ui.slider_config("Resolution scale", 1.0, 10.0)
-				.build(&mut settings.borrow_mut().resolution_scale);
+			resolution_controls(ui, settings.clone());
 
 			// Zoom slider.
 			ui.slider_config("Zoom", 1.0, 100000.0)
 				.flags(
 					imgui::SliderFlags::LOGARITHMIC
 					| imgui::SliderFlags::NO_ROUND_TO_FORMAT
-				).build(&mut root_texture.borrow_mut().zoom);
+				).build(&mut root_texture.borrow_mut().viewport.zoom);
 
-			// (x; y).
-			ui.text("Position");
-			gui::inputs::button_slider(
-				ui, 
-				&mut root_texture.borrow_mut().position[0], 
-				1.0 / current_zoom, 
-				"position(x)"
-			);
-			gui::inputs::button_slider(
-				ui, 
-				&mut root_texture.borrow_mut().position[1], 
-				1.0 / current_zoom, 
-				"position(y)"
-			);
+			// (x; y), as exact decimals.
+			ui.text("Center");
+			gui::inputs::button_decimal(ui, &mut root_texture.borrow_mut().viewport, 0, "center(x)");
+			gui::inputs::button_decimal(ui, &mut root_texture.borrow_mut().viewport, 1, "center(y)");
+			gui::inputs::center_clipboard(ui, &mut root_texture.borrow_mut().viewport);
 
 			ui.new_line();
 
-			if root_texture.borrow().method_id == 0 {
+			if is_iterative {
 				// Iterations.
 				ui.slider_config("Iteration", 1_usize, 250_usize)
 					.build(&mut root_texture.borrow_mut().iterations);
@@ -254,11 +564,24 @@ where
 				ui.slider_config("Threshold", 0.00000001, 2.0)
 					.flags(imgui::SliderFlags::NO_ROUND_TO_FORMAT)
 					.build(&mut root_texture.borrow_mut().threshold);
-			} else if root_texture.borrow().method_id == 1 {
+
+				if method == Option::Some(fractals::root::RootMethod::RelaxedNewton) {
+					// Relaxation factor `a`.
+					gui::inputs::complex_2_sliders(
+						ui,
+						"Relaxation a",
+						-2.0,
+						2.0,
+						&mut root_texture.borrow_mut().relaxation,
+					);
+				}
+			} else if method == Option::Some(fractals::root::RootMethod::Position) {
 				// Degree 0.
 				ui.slider_config("Degree 0", 0_f64, 360_f64)
 					.build(&mut root_texture.borrow_mut().degree0);
 			}
+
+			export_controls(ui, settings.clone(), root_texture.clone());
 		});
 }
 
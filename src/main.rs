@@ -1,17 +1,35 @@
 //! # Complex sequences.
 //! src/main.rs  
 
+use std::{env, process};
+
 mod structures;
 mod support;
 mod gui;
 mod fractals;
+mod expressions;
+mod cli;
 
 /// Basic interactivity entry point.
+/// 
+/// With the `render` command, draw a fractal to an image file without any window.
 fn main() {
     println!("# Complex sequences.");
 
-    // We use closure preset. 
-    gui::defaults::launch_default();
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.first().map(String::as_str) {
+        Option::Some("render") => {
+            if let Result::Err(error) = cli::render::run(&arguments[1..]) {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        },
+        _ => {
+            // We use closure preset. 
+            gui::defaults::launch_default();
+        },
+    }
 
     eprintln!("(?) main::main() End !");
 }
@@ -3,6 +3,8 @@
 
 use std::{fmt, convert};
 
+use crate::structures::combos::ComboMethod;
+
 /// # Float `Grayscale`.
 /// Value:
 /// - `0.0` is pitch black;
@@ -26,7 +28,7 @@ impl Grayscale {
 }
 
 /// # `Rgb`: red, green, blue.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rgb {
 	pub red: u8,
 	pub green: u8,
@@ -104,15 +106,367 @@ impl Hsv {
 }
 
 
+/// # `LinearRgb`: red, green, blue in [0; 1], without the sRGB gamma.
+/// Light adds up linearly in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb {
+	pub red: f64,
+	pub green: f64,
+	pub blue: f64,
+}
+
+impl LinearRgb {
+	pub fn new(red: f64, green: f64, blue: f64) -> LinearRgb {
+		LinearRgb { red, green, blue }
+	}
+
+	/// Remove the sRGB transfer function of `rgb`.
+	pub fn from_rgb(rgb: &Rgb) -> LinearRgb {
+		let decode = |component: u8| -> f64 {
+			let value: f64 = component as f64 / 255.0;
+			if value <= 0.04045 {
+				value / 12.92
+			} else {
+				((value + 0.055) / 1.055).powf(2.4)
+			}
+		};
+
+		LinearRgb::new(decode(rgb.red), decode(rgb.green), decode(rgb.blue))
+	}
+
+	/// Apply the sRGB transfer function. Out of gamut components are clamped.
+	pub fn to_rgb(self: &Self) -> Rgb {
+		let encode = |value: f64| -> u8 {
+			let value: f64 = value.clamp(0.0, 1.0);
+			let encoded: f64 = if value <= 0.0031308 {
+				value * 12.92
+			} else {
+				1.055 * value.powf(1.0 / 2.4) - 0.055
+			};
+			(encoded * 255.0).round() as u8
+		};
+
+		Rgb::new(encode(self.red), encode(self.green), encode(self.blue))
+	}
+}
+
+/// # `Oklab`: perceptual `lightness` in [0; 1], and opponent axes `a` (green-red), `b` (blue-yellow).
+/// 
+/// Source: [Björn Ottosson](https://bottosson.github.io/posts/oklab/)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+	pub lightness: f64,
+	pub a: f64,
+	pub b: f64,
+}
+
+impl Oklab {
+	pub fn new(lightness: f64, a: f64, b: f64) -> Oklab {
+		Oklab { lightness, a, b }
+	}
+
+	pub fn from_linear(linear: &LinearRgb) -> Oklab {
+		let l: f64 = 0.4122214708 * linear.red + 0.5363325363 * linear.green + 0.0514459929 * linear.blue;
+		let m: f64 = 0.2119034982 * linear.red + 0.6806995451 * linear.green + 0.1073969566 * linear.blue;
+		let s: f64 = 0.0883024619 * linear.red + 0.2817188376 * linear.green + 0.6299787005 * linear.blue;
+
+		let (l, m, s): (f64, f64, f64) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+		Oklab::new(
+			0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+			1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+			0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+		)
+	}
+
+	pub fn to_linear(self: &Self) -> LinearRgb {
+		let l: f64 = self.lightness + 0.3963377774 * self.a + 0.2158037573 * self.b;
+		let m: f64 = self.lightness - 0.1055613458 * self.a - 0.0638541728 * self.b;
+		let s: f64 = self.lightness - 0.0894841775 * self.a - 1.2914855480 * self.b;
+
+		let (l, m, s): (f64, f64, f64) = (l * l * l, m * m * m, s * s * s);
+
+		LinearRgb::new(
+			4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+			-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+			-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+		)
+	}
+
+	pub fn from_rgb(rgb: &Rgb) -> Oklab {
+		Oklab::from_linear(&LinearRgb::from_rgb(rgb))
+	}
+
+	pub fn to_rgb(self: &Self) -> Rgb {
+		self.to_linear().to_rgb()
+	}
+}
+
+/// # `Oklch`: `Oklab` in polar coordinates.
+/// - `lightness`: `f64` in [0; 1];
+/// - `chroma`: `f64`, 0 is gray, about 0.32 at most in sRGB;
+/// - `hue`: `f64` in [0; 360[, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+	pub lightness: f64,
+	pub chroma: f64,
+	pub hue: f64,
+}
+
+impl Oklch {
+	pub fn new(lightness: f64, chroma: f64, hue: f64) -> Oklch {
+		Oklch { lightness, chroma, hue: hue.rem_euclid(360.0) }
+	}
+
+	pub fn from_oklab(oklab: &Oklab) -> Oklch {
+		Oklch::new(
+			oklab.lightness,
+			oklab.a.hypot(oklab.b),
+			oklab.b.atan2(oklab.a).to_degrees(),
+		)
+	}
+
+	pub fn to_oklab(self: &Self) -> Oklab {
+		let hue: f64 = self.hue.to_radians();
+
+		Oklab::new(self.lightness, self.chroma * hue.cos(), self.chroma * hue.sin())
+	}
+
+	pub fn from_rgb(rgb: &Rgb) -> Oklch {
+		Oklch::from_oklab(&Oklab::from_rgb(rgb))
+	}
+
+	pub fn to_rgb(self: &Self) -> Rgb {
+		self.to_oklab().to_rgb()
+	}
+}
+
+/// # `Lab`: CIE L*a*b*, D65 white point.
+/// - `lightness`: `f64` in [0; 100];
+/// - `a`, `b`: opponent axes, about [-128; 127].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+	pub lightness: f64,
+	pub a: f64,
+	pub b: f64,
+}
+
+/// D65 white point, in XYZ.
+const WHITE_D65: [f64; 3] = [0.95047, 1.0, 1.08883];
+/// Threshold between the linear and cubic parts of the CIELAB functions.
+const LAB_DELTA: f64 = 6.0 / 29.0;
+
+impl Lab {
+	pub fn new(lightness: f64, a: f64, b: f64) -> Lab {
+		Lab { lightness, a, b }
+	}
+
+	pub fn from_linear(linear: &LinearRgb) -> Lab {
+		let x: f64 = 0.4124564 * linear.red + 0.3575761 * linear.green + 0.1804375 * linear.blue;
+		let y: f64 = 0.2126729 * linear.red + 0.7151522 * linear.green + 0.0721750 * linear.blue;
+		let z: f64 = 0.0193339 * linear.red + 0.1191920 * linear.green + 0.9503041 * linear.blue;
+
+		let f = |t: f64| -> f64 {
+			if t > LAB_DELTA * LAB_DELTA * LAB_DELTA {
+				t.cbrt()
+			} else {
+				t / (3.0 * LAB_DELTA * LAB_DELTA) + 4.0 / 29.0
+			}
+		};
+		let (fx, fy, fz): (f64, f64, f64) = (f(x / WHITE_D65[0]), f(y / WHITE_D65[1]), f(z / WHITE_D65[2]));
+
+		Lab::new(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+	}
+
+	pub fn to_linear(self: &Self) -> LinearRgb {
+		let fy: f64 = (self.lightness + 16.0) / 116.0;
+		let fx: f64 = fy + self.a / 500.0;
+		let fz: f64 = fy - self.b / 200.0;
+
+		let f_inverse = |t: f64| -> f64 {
+			if t > LAB_DELTA {
+				t * t * t
+			} else {
+				3.0 * LAB_DELTA * LAB_DELTA * (t - 4.0 / 29.0)
+			}
+		};
+		let x: f64 = WHITE_D65[0] * f_inverse(fx);
+		let y: f64 = WHITE_D65[1] * f_inverse(fy);
+		let z: f64 = WHITE_D65[2] * f_inverse(fz);
+
+		LinearRgb::new(
+			3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+			-0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+			0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+		)
+	}
+
+	pub fn from_rgb(rgb: &Rgb) -> Lab {
+		Lab::from_linear(&LinearRgb::from_rgb(rgb))
+	}
+
+	pub fn to_rgb(self: &Self) -> Rgb {
+		self.to_linear().to_rgb()
+	}
+}
+
+/// Linear interpolation from `start` to `end`, at `fraction` in [0; 1].
+fn mix(start: f64, end: f64, fraction: f64) -> f64 {
+	start + (end - start) * fraction
+}
+
+/// # `ColorSpace` where colors are interpolated.
+/// ```ignore,
+/// 1. `Srgb`: gamma encoded components, as stored;
+/// 2. `LinearRgb`: physically correct blending;
+/// 3. `Oklab`: perceptually even lightness;
+/// 4. `Oklch`: `Oklab` through the hues;
+/// 5. `Lab`: CIELAB.
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+	Srgb,
+	LinearRgb,
+	Oklab,
+	Oklch,
+	Lab,
+}
+
+impl ColorSpace {
+	/// Returns the lowercase name, as written in palette files.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			ColorSpace::Srgb => "srgb",
+			ColorSpace::LinearRgb => "linear",
+			ColorSpace::Oklab => "oklab",
+			ColorSpace::Oklch => "oklch",
+			ColorSpace::Lab => "lab",
+		}
+	}
+
+	/// Mix `start` and `end` at `fraction` in [0; 1], in this space.
+	/// 
+	/// `Oklch` takes the shortest way around the hues; the hue of a gray follows the other color.
+	pub fn interpolate(self: &Self, start: &Rgb, end: &Rgb, fraction: f64) -> Rgb {
+		match &self {
+			ColorSpace::Srgb => Rgb::new(
+				mix(start.red as f64, end.red as f64, fraction).round() as u8,
+				mix(start.green as f64, end.green as f64, fraction).round() as u8,
+				mix(start.blue as f64, end.blue as f64, fraction).round() as u8,
+			),
+			ColorSpace::LinearRgb => {
+				let (start, end): (LinearRgb, LinearRgb) = (LinearRgb::from_rgb(start), LinearRgb::from_rgb(end));
+				LinearRgb::new(
+					mix(start.red, end.red, fraction),
+					mix(start.green, end.green, fraction),
+					mix(start.blue, end.blue, fraction),
+				).to_rgb()
+			},
+			ColorSpace::Oklab => {
+				let (start, end): (Oklab, Oklab) = (Oklab::from_rgb(start), Oklab::from_rgb(end));
+				Oklab::new(
+					mix(start.lightness, end.lightness, fraction),
+					mix(start.a, end.a, fraction),
+					mix(start.b, end.b, fraction),
+				).to_rgb()
+			},
+			ColorSpace::Oklch => {
+				let (start, end): (Oklch, Oklch) = (Oklch::from_rgb(start), Oklch::from_rgb(end));
+				let gray: f64 = 1e-4;
+				let (start_hue, end_hue): (f64, f64) = match (start.chroma < gray, end.chroma < gray) {
+					(true, false) => (end.hue, end.hue),
+					(false, true) => (start.hue, start.hue),
+					_ => (start.hue, end.hue),
+				};
+				let turn: f64 = (end_hue - start_hue + 540.0).rem_euclid(360.0) - 180.0;
+				Oklch::new(
+					mix(start.lightness, end.lightness, fraction),
+					mix(start.chroma, end.chroma, fraction),
+					start_hue + turn * fraction,
+				).to_rgb()
+			},
+			ColorSpace::Lab => {
+				let (start, end): (Lab, Lab) = (Lab::from_rgb(start), Lab::from_rgb(end));
+				Lab::new(
+					mix(start.lightness, end.lightness, fraction),
+					mix(start.a, end.a, fraction),
+					mix(start.b, end.b, fraction),
+				).to_rgb()
+			},
+		}
+	}
+}
+
+impl ComboMethod for ColorSpace {
+	fn list() -> Vec<ColorSpace> {
+		vec![
+			ColorSpace::Srgb,
+			ColorSpace::LinearRgb,
+			ColorSpace::Oklab,
+			ColorSpace::Oklch,
+			ColorSpace::Lab,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			ColorSpace::Srgb => 1,
+			ColorSpace::LinearRgb => 2,
+			ColorSpace::Oklab => 3,
+			ColorSpace::Oklch => 4,
+			ColorSpace::Lab => 5,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			ColorSpace::Srgb => "1. sRGB",
+			ColorSpace::LinearRgb => "2. Linear RGB",
+			ColorSpace::Oklab => "3. Oklab",
+			ColorSpace::Oklch => "4. Oklch",
+			ColorSpace::Lab => "5. CIELAB",
+		}
+	}
+}
+
+impl fmt::Display for ColorSpace {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for ColorSpace {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+
+/// Escape radius of the `SMOOTH` color mode.
+pub const BAILOUT_SMOOTH: f64 = 1e6;
+
 /// # `ColorMode`:
 /// ```ignore,
 /// 0. `GRAYSCALE`,
-/// 1. `HSV`.
+/// 1. `HSV`,
+/// 2. `SMOOTH`,
+/// 3. `PALETTE`,
+/// 4. `OKLCH`,
+/// 5. `HISTOGRAM`.
 /// ``````
 #[derive(Clone, Copy)]
 pub enum ColorMode {
 	GRAYSCALE,
 	HSV,
+	/// HSV of the fractional iteration count, without bands.
+	SMOOTH,
+	/// Gradient `palette::Palette` of the fractional iteration count, or of the roots.
+	PALETTE,
+	/// Hues of `Oklch`, of even perceived lightness.
+	OKLCH,
+	/// Gradient `palette::Palette` of the cumulative distribution of the iteration counts,
+	/// over the whole image: equalized at any zoom.
+	HISTOGRAM,
 }
 
 impl ColorMode {
@@ -121,13 +475,54 @@ impl ColorMode {
 		vec![
 			ColorMode::GRAYSCALE,
 			ColorMode::HSV,
+			ColorMode::SMOOTH,
+			ColorMode::PALETTE,
+			ColorMode::OKLCH,
+			ColorMode::HISTOGRAM,
 		]
 	}
 
+	/// Returns `true` if the mode colors through the gradient `palette::Palette`.
+	pub fn uses_palette(self: &Self) -> bool {
+		matches!(self, ColorMode::PALETTE | ColorMode::HISTOGRAM)
+	}
+
+	/// Escape radius of the divergence fractals, from the user's `threshold`.
+	/// 
+	/// Modes of the fractional count need a large one: it is only accurate once |z| is far out.
+	pub fn bailout(self: &Self, threshold: f64) -> f64 {
+		match self.is_smooth() {
+			true => threshold.max(BAILOUT_SMOOTH),
+			false => threshold,
+		}
+	}
+
+	/// Returns `true` if the mode colors by the fractional count, from the modulus of the escape,
+	/// `false` if only by the iterations.
+	pub fn is_smooth(self: &Self) -> bool {
+		matches!(self, ColorMode::SMOOTH | ColorMode::PALETTE | ColorMode::OKLCH | ColorMode::HISTOGRAM)
+	}
+
 	fn to_static_str(self: &Self) -> &'static str {
 		match &self {
 			ColorMode::GRAYSCALE => "0. Grayscale.",
 			ColorMode::HSV => "1. HSV.",
+			ColorMode::SMOOTH => "2. Smooth.",
+			ColorMode::PALETTE => "3. Palette.",
+			ColorMode::OKLCH => "4. Oklch.",
+			ColorMode::HISTOGRAM => "5. Histogram.",
+		}
+	}
+
+	/// Returns the lowercase name of the `ColorMode`, as used in the command-line and exports.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			ColorMode::GRAYSCALE => "grayscale",
+			ColorMode::HSV => "hsv",
+			ColorMode::SMOOTH => "smooth",
+			ColorMode::PALETTE => "palette",
+			ColorMode::OKLCH => "oklch",
+			ColorMode::HISTOGRAM => "histogram",
 		}
 	}
 }
@@ -137,6 +532,10 @@ impl fmt::Display for ColorMode {
 		write!(formatter, "Method::{}", match &self {
 			ColorMode::GRAYSCALE => "Grayscale",
 			ColorMode::HSV => "HSV",
+			ColorMode::SMOOTH => "Smooth",
+			ColorMode::PALETTE => "Palette",
+			ColorMode::OKLCH => "Oklch",
+			ColorMode::HISTOGRAM => "Histogram",
 		})
 	}
 }
@@ -147,6 +546,189 @@ impl convert::AsRef<str> for ColorMode {
 	}
 }
 
+/// # `InteriorMode`: color of the bounded points of the divergence fractals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteriorMode {
+	/// The single stable color.
+	Flat,
+	/// A hue per period of the attracting cycle.
+	Period,
+	/// Bright at the centers of the components, where the multiplier is 0, dark at their edges.
+	Multiplier,
+}
+
+impl InteriorMode {
+	/// Returns the lowercase name, as used in the command-line.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			InteriorMode::Flat => "flat",
+			InteriorMode::Period => "period",
+			InteriorMode::Multiplier => "multiplier",
+		}
+	}
+
+	/// Color of a point caught by a cycle of `period` and `multiplier` in [0; 1[,
+	/// between the `stable` and `divergent` colors.
+	pub fn color(
+		self: &Self,
+		period: usize,
+		multiplier: f64,
+		stable: &Rgb,
+		divergent: &Rgb,
+	) -> Rgb {
+		match &self {
+			InteriorMode::Flat => *stable,
+			// Golden angle: neighbouring periods get distant hues.
+			InteriorMode::Period => Oklch::new(0.72, 0.12, period as f64 * 137.508).to_rgb(),
+			InteriorMode::Multiplier => ColorSpace::Oklab.interpolate(divergent, stable, multiplier.clamp(0.0, 1.0)),
+		}
+	}
+}
+
+impl ComboMethod for InteriorMode {
+	fn list() -> Vec<InteriorMode> {
+		vec![
+			InteriorMode::Flat,
+			InteriorMode::Period,
+			InteriorMode::Multiplier,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			InteriorMode::Flat => 1,
+			InteriorMode::Period => 2,
+			InteriorMode::Multiplier => 3,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			InteriorMode::Flat => "1. Flat",
+			InteriorMode::Period => "2. Period",
+			InteriorMode::Multiplier => "3. Multiplier",
+		}
+	}
+}
+
+impl fmt::Display for InteriorMode {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for InteriorMode {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+/// # `BoundaryMode`: how the distance estimation draws the boundary of the sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryMode {
+	/// Sharp line, anti-aliased on its edges.
+	Outline,
+	/// Fading away from the boundary.
+	Glow,
+}
+
+impl BoundaryMode {
+	/// Returns the lowercase name, as used in the command-line.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			BoundaryMode::Outline => "outline",
+			BoundaryMode::Glow => "glow",
+		}
+	}
+}
+
+impl ComboMethod for BoundaryMode {
+	fn list() -> Vec<BoundaryMode> {
+		vec![
+			BoundaryMode::Outline,
+			BoundaryMode::Glow,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			BoundaryMode::Outline => 1,
+			BoundaryMode::Glow => 2,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			BoundaryMode::Outline => "1. Outline",
+			BoundaryMode::Glow => "2. Glow",
+		}
+	}
+}
+
+impl fmt::Display for BoundaryMode {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for BoundaryMode {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+/// # `Boundary` of the sets, drawn `thickness` pixels wide in `mode`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Boundary {
+	pub mode: BoundaryMode,
+	pub thickness: f64,
+}
+
+impl Default for Boundary {
+	fn default() -> Self {
+		Boundary { mode: BoundaryMode::Outline, thickness: 1.0 }
+	}
+}
+
+impl Boundary {
+	/// Coverage in [0; 1] of a point `distance` pixels away from the boundary.
+	pub fn coverage(self: &Self, distance: f64) -> f64 {
+		let thickness: f64 = self.thickness.max(f64::EPSILON);
+
+		match &self.mode {
+			BoundaryMode::Outline => (thickness + 0.5 - distance).clamp(0.0, 1.0),
+			BoundaryMode::Glow => (-distance / thickness).exp(),
+		}
+	}
+}
+
+/// # Directional `Light` of the normal map, embossing the escaped points.
+/// Comes from `angle` in degrees on the plane, `height` above it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+	pub enabled: bool,
+	pub angle: f64,
+	pub height: f64,
+}
+
+impl Default for Light {
+	fn default() -> Self {
+		Light { enabled: false, angle: 45.0, height: 1.5 }
+	}
+}
+
+impl Light {
+	/// Light `color` on a surface of unit `normal` [x, y] on the plane, in linear RGB.
+	pub fn shade(self: &Self, color: &Rgb, normal: [f64; 2]) -> Rgb {
+		let (sin, cos): (f64, f64) = self.angle.to_radians().sin_cos();
+		let height: f64 = self.height.max(0.0);
+		// Lambert's cosine, with the normal tilted up by the height.
+		let lambert: f64 = ((normal[0] * cos + normal[1] * sin + height) / (1.0 + height)).clamp(0.0, 1.0);
+
+		ColorSpace::LinearRgb.interpolate(&Rgb::new(0, 0, 0), color, lambert)
+	}
+}
+
 
 #[test]
 fn test_hsv_to_rgb() -> () {
@@ -161,3 +743,30 @@ fn test_hsv_to_rgb() -> () {
 	}
 }
 
+
+#[test]
+fn test_color_spaces() -> () {
+	// Round trips, on a grid of the sRGB cube.
+	for red in (0..=255).step_by(51) {
+		for green in (0..=255).step_by(51) {
+			for blue in (0..=255).step_by(51) {
+				let rgb: Rgb = Rgb::new(red as u8, green as u8, blue as u8);
+				assert_eq!(Oklab::from_rgb(&rgb).to_rgb(), rgb);
+				assert_eq!(Oklch::from_rgb(&rgb).to_rgb(), rgb);
+				assert_eq!(Lab::from_rgb(&rgb).to_rgb(), rgb);
+			}
+		}
+	}
+
+	let white: Rgb = Rgb::new(255, 255, 255);
+	let black: Rgb = Rgb::new(0, 0, 0);
+	assert!((Oklab::from_rgb(&white).lightness - 1.0).abs() < 1e-4);
+	assert!((Lab::from_rgb(&white).lightness - 100.0).abs() < 1e-2);
+
+	// Half the light is brighter than half the gamma encoded value.
+	assert_eq!(ColorSpace::Srgb.interpolate(&black, &white, 0.5), Rgb::new(128, 128, 128));
+	assert_eq!(ColorSpace::LinearRgb.interpolate(&black, &white, 0.5), Rgb::new(188, 188, 188));
+	// Red to blue through the hues goes by magenta, not gray.
+	let magenta: Rgb = ColorSpace::Oklch.interpolate(&Rgb::new(255, 0, 0), &Rgb::new(0, 0, 255), 0.5);
+	assert!(magenta.red > 100 && magenta.blue > 100 && magenta.green < 50);
+}
@@ -9,17 +9,28 @@ pub struct Data {
 
 /// # Divergence `State`.
 /// Tell if a function diverges toward infinity or remains bounded.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum State {
-	/// Divergent: in how many `iterations` does it diverged. 
-	Divergent{ iterations: usize },
+	/// Divergent: in how many `iterations` does it diverged,
+	/// and the `modulus` |z| of the first value past the threshold.
+	Divergent{ iterations: usize, modulus: f64 },
+	/// Divergent, with the estimated `distance` to the boundary of the set, on the complex plane,
+	/// and the unit `normal` to the equipotential lines, pointing outwards.
+	Estimated{ iterations: usize, modulus: f64, distance: f64, normal: complex::Algebraic },
 	Stable,
+	/// Bounded, caught by an attracting cycle of `period` after `iterations`,
+	/// whose `multiplier` |(fᵖ)'(z)| is in [0; 1[: 0 at the centers of the components.
+	Periodic{ iterations: usize, period: usize, multiplier: f64 },
+	/// Orbit followed by an orbit trap: the `distance` of its closest approach, at `closest` iteration.
+	/// `escaped` in that many iterations, `None` if it remained bounded.
+	Trapped{ distance: f64, closest: usize, escaped: Option<usize> },
 }
 
 /// # `IsRoot`.
 /// Define a possible root.
 /// - `No`: no root,
 /// - `Yes`: there is root, and the root is `root`: complex::Algebraic.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum IsRoot {
 	No,
 	Yes { 
@@ -1,7 +1,8 @@
 //! # Complex sequences.
 //! src/fractals/structures/configuration.rs
 
-use crate::structures::color;
+use crate::structures::{color, palette};
+use crate::fractals;
 
 /// # Global `Settings` state.
 /// Store only persistent settings.
@@ -12,6 +13,14 @@ pub struct GlobalSettings {
 	pub color_mode_id: usize,
 	pub color_mode: color::ColorMode,
 	pub resolution_scale: complex::Real,
+	/// Render coarse passes first, refined up to the full resolution, instead of using `resolution_scale`.
+	pub progressive: bool,
+	/// Sub-pixel samples of the full resolution, on screen and in exports.
+	pub antialiasing: fractals::antialiasing::Antialiasing,
+	/// Contrast and samples of the `Adaptive` antialiasing.
+	pub adaptive: fractals::antialiasing::Adaptive,
+	pub export: ExportSettings,
+	pub palette: PaletteSettings,
 }
 
 impl Default for GlobalSettings {
@@ -23,6 +32,50 @@ impl Default for GlobalSettings {
 			color_mode_id: 0,
 			color_mode: color::ColorMode::GRAYSCALE,
 			resolution_scale: 1.0,
+			progressive: true,
+			antialiasing: fractals::antialiasing::Antialiasing::Off,
+			adaptive: fractals::antialiasing::Adaptive::default(),
+			export: ExportSettings::default(),
+			palette: PaletteSettings::default(),
+		}
+	}
+}
+
+/// # `ExportSettings` of the current fractal to an image file.
+pub struct ExportSettings {
+	/// Output image size: [width, height]. Independent of the window and `resolution_scale`.
+	pub size: [u32; 2],
+	/// Output image path. The format is deduced from the extension.
+	pub path: String,
+	/// Result of the last export, shown in the settings.
+	pub status: Option<String>,
+}
+
+impl Default for ExportSettings {
+	fn default() -> Self {
+		ExportSettings {
+			size: [1920, 1080],
+			path: String::from("fractal.png"),
+			status: Option::None,
+		}
+	}
+}
+
+/// # `PaletteSettings`: gradient of the `PALETTE` color mode, and its file.
+pub struct PaletteSettings {
+	pub palette: palette::Palette,
+	/// Palette file to save to, or load from.
+	pub path: String,
+	/// Result of the last save or load, shown in the settings.
+	pub status: Option<String>,
+}
+
+impl Default for PaletteSettings {
+	fn default() -> Self {
+		PaletteSettings {
+			palette: palette::Palette::default(),
+			path: String::from("palette.toml"),
+			status: Option::None,
 		}
 	}
 }
@@ -2,6 +2,8 @@
 //! src/fractals/structures/mod.rs
 
 pub mod color;
+pub mod palette;
 pub mod configuration;
 pub mod combos;
 pub mod computations;
+pub mod precision;
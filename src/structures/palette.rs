@@ -0,0 +1,335 @@
+//! # Complex sequences.
+//! src/structures/palette.rs
+//!
+//! Gradient `Palette`s: color stops, interpolated and cycled over the fractal weights.
+
+use std::{convert, error, f64, fmt, fs, path};
+
+use crate::structures::color;
+use crate::structures::combos::ComboMethod;
+
+/// # `Stop` of a gradient: `color` at `position` in [0; 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stop {
+	pub position: f64,
+	pub color: color::Rgb,
+}
+
+impl Stop {
+	pub fn new(position: f64, color: color::Rgb) -> Stop {
+		Stop { position, color }
+	}
+}
+
+/// # `Interpolation` between two stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+	Linear,
+	/// Eased with a half cosine: flat around the stops.
+	Cosine,
+}
+
+impl Interpolation {
+	/// Returns the lowercase name, as written in palette files.
+	pub fn to_name(self: &Self) -> &'static str {
+		match &self {
+			Interpolation::Linear => "linear",
+			Interpolation::Cosine => "cosine",
+		}
+	}
+
+	/// Ease the `fraction` in [0; 1] between two stops.
+	fn ease(self: &Self, fraction: f64) -> f64 {
+		match &self {
+			Interpolation::Linear => fraction,
+			Interpolation::Cosine => (1.0 - (fraction * f64::consts::PI).cos()) / 2.0,
+		}
+	}
+}
+
+impl ComboMethod for Interpolation {
+	/// Return a `Vec` of all the interpolations.
+	/// ```rust, no_run
+	/// 1. Linear;
+	/// 2. Cosine.
+	/// ```
+	fn list() -> Vec<Interpolation> {
+		vec![
+			Interpolation::Linear,
+			Interpolation::Cosine,
+		]
+	}
+
+	fn id(self: &Self) -> u32 {
+		match &self {
+			Interpolation::Linear => 1,
+			Interpolation::Cosine => 2,
+		}
+	}
+
+	fn to_static_str(self: &Self) -> &'static str {
+		match &self {
+			Interpolation::Linear => "1. Linear",
+			Interpolation::Cosine => "2. Cosine",
+		}
+	}
+}
+
+impl fmt::Display for Interpolation {
+	fn fmt(self: &Self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{}", self.to_static_str())
+	}
+}
+
+impl convert::AsRef<str> for Interpolation {
+	fn as_ref(self: &Self) -> &str {
+		&self.to_static_str()
+	}
+}
+
+/// # Gradient `Palette`.
+/// Weights in [0; 1] are divided by `period` and shifted by `offset`, then wrapped in [0; 1[
+/// on the gradient of `stops`: the gradient cycles `1 / period` times.
+/// Stops are mixed in the color `space`.
+///
+/// The `stops` must be sorted by position, cf. `sort`. The gradient wraps from the last
+/// stop back to the first one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+	pub stops: Vec<Stop>,
+	pub interpolation: Interpolation,
+	pub space: color::ColorSpace,
+	pub offset: f64,
+	pub period: f64,
+}
+
+impl Default for Palette {
+	/// Deep blue, white and orange.
+	fn default() -> Self {
+		Palette::new(
+			vec![
+				Stop::new(0.0, color::Rgb::new(0, 7, 100)),
+				Stop::new(0.16, color::Rgb::new(32, 107, 203)),
+				Stop::new(0.42, color::Rgb::new(237, 255, 255)),
+				Stop::new(0.6425, color::Rgb::new(255, 170, 0)),
+				Stop::new(0.8575, color::Rgb::new(0, 2, 0)),
+			],
+			Interpolation::Cosine,
+			color::ColorSpace::Oklab,
+			0.0,
+			1.0,
+		)
+	}
+}
+
+impl Palette {
+	/// Instantiate a `Palette`, with its `stops` sorted.
+	pub fn new(
+		stops: Vec<Stop>,
+		interpolation: Interpolation,
+		space: color::ColorSpace,
+		offset: f64,
+		period: f64,
+	) -> Palette {
+		let mut palette: Palette = Palette { stops, interpolation, space, offset, period };
+		palette.sort();
+
+		palette
+	}
+
+	/// Sort the stops by position. Call after editing them.
+	pub fn sort(self: &mut Self) -> () {
+		self.stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+	}
+
+	/// Color of a fractal `weight`, cycled with `offset` and `period`.
+	pub fn color_at(self: &Self, weight: f64) -> color::Rgb {
+		let period: f64 = if self.period > 0.0 { self.period } else { 1.0 };
+
+		self.gradient_at((weight / period + self.offset).rem_euclid(1.0))
+	}
+
+	/// Color of the gradient at `position` in [0; 1[.
+	pub fn gradient_at(self: &Self, position: f64) -> color::Rgb {
+		let (first, last): (Stop, Stop) = match (self.stops.first(), self.stops.last()) {
+			(Option::Some(first), Option::Some(last)) => (*first, *last),
+			_ => return color::Rgb::new(0, 0, 0),
+		};
+
+		// Segment around `position`, wrapping from the last stop to the first one.
+		let (start, end, start_position, end_position): (Stop, Stop, f64, f64) = match self.stops
+			.iter()
+			.position(|stop| stop.position > position)
+		{
+			Option::Some(0) => (last, first, last.position - 1.0, first.position),
+			Option::Some(index) => {
+				let start: Stop = self.stops[index - 1];
+				let end: Stop = self.stops[index];
+				(start, end, start.position, end.position)
+			},
+			Option::None => (last, first, last.position, first.position + 1.0),
+		};
+
+		let length: f64 = end_position - start_position;
+		let fraction: f64 = if length > 0.0 {
+			((position - start_position) / length).clamp(0.0, 1.0)
+		} else {
+			0.0
+		};
+		self.space.interpolate(&start.color, &end.color, self.interpolation.ease(fraction))
+	}
+
+	/// Add a stop in the middle of the widest gap, with the color already there.
+	pub fn insert_stop(self: &mut Self) -> () {
+		let position: f64 = match self.stops.len() {
+			0 => 0.0,
+			1 => (self.stops[0].position + 0.5).rem_euclid(1.0),
+			_ => {
+				let mut widest: (f64, f64) = (0.0, -1.0);
+				for index in 0..self.stops.len() {
+					let start: f64 = self.stops[index].position;
+					let end: f64 = match self.stops.get(index + 1) {
+						Option::Some(stop) => stop.position,
+						Option::None => self.stops[0].position + 1.0,
+					};
+					if end - start > widest.1 {
+						widest = ((start + end) / 2.0, end - start);
+					}
+				}
+
+				widest.0.rem_euclid(1.0)
+			},
+		};
+
+		self.stops.push(Stop::new(position, self.gradient_at(position)));
+		self.sort();
+	}
+
+	/// Write the palette as TOML-like `key = value` lines.
+	pub fn to_text(self: &Self) -> String {
+		let mut content: String = String::from("# Complex sequences. Palette.\n");
+
+		content.push_str(&format!("interpolation = \"{}\"\n", self.interpolation.to_name()));
+		content.push_str(&format!("space = \"{}\"\n", self.space.to_name()));
+		content.push_str(&format!("offset = {}\n", self.offset));
+		content.push_str(&format!("period = {}\n", self.period));
+		for stop in &self.stops {
+			content.push_str(&format!(
+				"stop = [{}, {}, {}, {}]\n",
+				stop.position, stop.color.red, stop.color.green, stop.color.blue,
+			));
+		}
+
+		content
+	}
+
+	/// Read a palette written by `to_text`. Missing keys take their default value, `srgb` for the space.
+	pub fn from_text(content: &str) -> Result<Palette, Box<dyn error::Error>> {
+		let mut stops: Vec<Stop> = Vec::new();
+		let mut interpolation: Interpolation = Interpolation::Linear;
+		let mut space: color::ColorSpace = color::ColorSpace::Srgb;
+		let mut offset: f64 = 0.0;
+		let mut period: f64 = 1.0;
+
+		for (number, line) in content.lines().enumerate() {
+			let line: &str = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let (key, value) = match line.split_once('=') {
+				Option::Some((key, value)) => (key.trim(), value.trim()),
+				Option::None => return Result::Err(
+					format!("(X) Palette line {}: expected `key = value`, got `{}`.", number + 1, line).into()
+				),
+			};
+
+			match key {
+				"interpolation" => interpolation = match Interpolation::list()
+					.into_iter()
+					.find(|interpolation| interpolation.to_name() == value.trim_matches('"'))
+				{
+					Option::Some(interpolation) => interpolation,
+					Option::None => return Result::Err(
+						format!("(X) Palette line {}: unknown interpolation {}.", number + 1, value).into()
+					),
+				},
+				"space" => space = match color::ColorSpace::list()
+					.into_iter()
+					.find(|space| space.to_name() == value.trim_matches('"'))
+				{
+					Option::Some(space) => space,
+					Option::None => return Result::Err(
+						format!("(X) Palette line {}: unknown color space {}.", number + 1, value).into()
+					),
+				},
+				"offset" => offset = value.parse::<f64>()?,
+				"period" => period = value.parse::<f64>()?,
+				"stop" => {
+					let values: Vec<&str> = value
+						.trim_start_matches('[')
+						.trim_end_matches(']')
+						.split(',')
+						.map(|value| value.trim())
+						.collect();
+					if values.len() != 4 {
+						return Result::Err(format!(
+							"(X) Palette line {}: a stop is `[position, red, green, blue]`, got {}.",
+							number + 1, value,
+						).into());
+					}
+
+					stops.push(Stop::new(
+						values[0].parse::<f64>()?,
+						color::Rgb::new(values[1].parse::<u8>()?, values[2].parse::<u8>()?, values[3].parse::<u8>()?),
+					));
+				},
+				_ => return Result::Err(format!("(X) Palette line {}: unknown key `{}`.", number + 1, key).into()),
+			}
+		}
+
+		if stops.is_empty() {
+			return Result::Err("(X) Palette without any `stop`.".into());
+		}
+
+		Result::Ok(Palette::new(stops, interpolation, space, offset, period))
+	}
+
+	pub fn save(self: &Self, path: &path::Path) -> Result<(), Box<dyn error::Error>> {
+		fs::write(path, self.to_text())?;
+
+		Result::Ok(())
+	}
+
+	pub fn load(path: &path::Path) -> Result<Palette, Box<dyn error::Error>> {
+		Palette::from_text(&fs::read_to_string(path)?)
+	}
+}
+
+
+#[test]
+fn test_palette() -> () {
+	let palette: Palette = Palette::new(
+		vec![
+			Stop::new(0.5, color::Rgb::new(200, 100, 0)),
+			Stop::new(0.0, color::Rgb::new(0, 0, 0)),
+		],
+		Interpolation::Linear,
+		color::ColorSpace::Srgb,
+		0.0,
+		1.0,
+	);
+
+	// Stops, in between, and the wrap from the last stop to the first one.
+	assert_eq!(palette.gradient_at(0.0), color::Rgb::new(0, 0, 0));
+	assert_eq!(palette.gradient_at(0.25), color::Rgb::new(100, 50, 0));
+	assert_eq!(palette.gradient_at(0.5), color::Rgb::new(200, 100, 0));
+	assert_eq!(palette.gradient_at(0.75), color::Rgb::new(100, 50, 0));
+	// Two cycles.
+	let cycling: Palette = Palette { period: 0.5, ..palette.clone() };
+	assert_eq!(cycling.color_at(0.625), palette.gradient_at(0.25));
+
+	let read: Palette = Palette::from_text(&Palette::default().to_text()).unwrap();
+	assert_eq!(read, Palette::default());
+	assert!(Palette::from_text("stop = [0.5, 300, 0, 0]").is_err());
+}
@@ -0,0 +1,350 @@
+//! # Complex sequences.
+//! src/structures/precision.rs
+//!
+//! Arbitrary precision real numbers, for the coordinates of deep zooms.
+
+use std::{cmp, error};
+
+/// Bits of a digit.
+const DIGIT_BITS: usize = 32;
+/// Digits of the integer part: |x| < 2⁶⁴, enough for the squares of the escape radii.
+const INTEGER_DIGITS: usize = 2;
+
+/// # `Fixed`: arbitrary precision fixed point real number.
+/// A sign and a magnitude of base 2³² `digits`, from the lowest: the `fraction` first ones
+/// are after the point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+	negative: bool,
+	digits: Vec<u32>,
+	fraction: usize,
+}
+
+impl Fixed {
+	/// Zero, with `fraction` digits after the point.
+	pub fn zero(fraction: usize) -> Fixed {
+		Fixed { negative: false, digits: vec![0; fraction + INTEGER_DIGITS], fraction }
+	}
+
+	/// Digits after the point needed to tell apart the pixels of a `zoom`, plus some margin.
+	pub fn fraction_for_zoom(zoom: f64) -> usize {
+		let bits: f64 = zoom.abs().max(1.0).log2() + 64.0;
+
+		(bits / DIGIT_BITS as f64).ceil() as usize
+	}
+
+	/// Exact `value`, rounded toward zero past `fraction` digits.
+	pub fn from_f64(value: f64, fraction: usize) -> Fixed {
+		let mut fixed: Fixed = Fixed::zero(fraction);
+		if value == 0.0 || !value.is_finite() {
+			return fixed;
+		}
+
+		// value = mantissa * 2^exponent, exactly.
+		let bits: u64 = value.abs().to_bits();
+		let biased: i64 = ((bits >> 52) & 0x7FF) as i64;
+		let (mantissa, exponent): (u64, i64) = match biased {
+			0 => (bits & ((1 << 52) - 1), -1074),
+			_ => ((bits & ((1 << 52) - 1)) | (1 << 52), biased - 1075),
+		};
+
+		let shift: i64 = exponent + (fraction * DIGIT_BITS) as i64;
+		for bit in 0..53 {
+			let position: i64 = shift + bit;
+			if mantissa >> bit & 1 == 1 && position >= 0 {
+				let position: usize = position as usize;
+				if let Option::Some(digit) = fixed.digits.get_mut(position / DIGIT_BITS) {
+					*digit |= 1 << (position % DIGIT_BITS);
+				}
+			}
+		}
+		fixed.negative = value < 0.0;
+
+		fixed
+	}
+
+	/// Parse a decimal `text`, e.g. `-0.7436438870371587`, with at least `fraction` digits after the point,
+	/// and enough for all its decimals. Other float texts, e.g. `1e-3`, are rounded to floats.
+	pub fn parse(text: &str, fraction: usize) -> Result<Fixed, Box<dyn error::Error>> {
+		let text: &str = text.trim();
+		let (negative, magnitude): (bool, &str) = match text.strip_prefix('-') {
+			Option::Some(magnitude) => (true, magnitude),
+			Option::None => (false, text.strip_prefix('+').unwrap_or(text)),
+		};
+		let (integer, decimals): (&str, &str) = magnitude.split_once('.').unwrap_or((magnitude, ""));
+		if (integer.is_empty() && decimals.is_empty()) 
+			|| !integer.chars().chain(decimals.chars()).all(|character| character.is_ascii_digit())
+		{
+			return match text.parse::<f64>() {
+				Result::Ok(value) if value.is_finite() => Result::Ok(Fixed::from_f64(value, fraction)),
+				_ => Result::Err(format!("(X) Can't parse the decimal `{}`.", text).into()),
+			};
+		}
+
+		// 10 > 2³: 4 bits per decimal, and a digit of margin.
+		let fraction: usize = fraction.max(decimals.len() * 4 / DIGIT_BITS + 1);
+		let integer: u64 = match integer {
+			"" => 0,
+			integer => integer
+				.parse::<u64>()
+				.map_err(|_| format!("(X) The integer part of `{}` is too large.", text))?,
+		};
+
+		// From the last decimal: x = (x + d) / 10.
+		let mut fixed: Fixed = Fixed::zero(fraction);
+		for decimal in decimals.bytes().rev() {
+			fixed.digits[fraction] += (decimal - b'0') as u32;
+			fixed = fixed.divide_small(10);
+		}
+		fixed.digits[fraction] = integer as u32;
+		fixed.digits[fraction + 1] = (integer >> DIGIT_BITS) as u32;
+
+		Result::Ok(Fixed::normalized(negative, fixed.digits, fraction))
+	}
+
+	/// Decimal text with `decimals` after the point, rounded to the nearest.
+	pub fn to_decimal(self: &Self, decimals: usize) -> String {
+		let mut integer: u64 = self.digits[self.fraction] as u64 
+			| (self.digits[self.fraction + 1] as u64) << DIGIT_BITS;
+		let mut fractional: Fixed = Fixed::normalized(false, self.digits.clone(), self.fraction);
+		fractional.digits[self.fraction] = 0;
+		fractional.digits[self.fraction + 1] = 0;
+
+		// One more decimal, to round.
+		let mut characters: Vec<u8> = Vec::with_capacity(decimals + 1);
+		for _ in 0..=decimals {
+			fractional = fractional.multiply_small(10);
+			characters.push(b'0' + fractional.digits[self.fraction] as u8);
+			fractional.digits[self.fraction] = 0;
+		}
+		if characters.pop().unwrap_or(b'0') >= b'5' {
+			let mut carry: bool = true;
+			for character in characters.iter_mut().rev() {
+				match *character {
+					b'9' => *character = b'0',
+					_ => {
+						*character += 1;
+						carry = false;
+						break;
+					},
+				}
+			}
+			if carry {
+				integer += 1;
+			}
+		}
+
+		let sign: &str = match self.negative && (integer != 0 || characters.iter().any(|character| *character != b'0')) {
+			true => "-",
+			false => "",
+		};
+		match decimals {
+			0 => format!("{}{}", sign, integer),
+			_ => format!("{}{}.{}", sign, integer, String::from_utf8_lossy(&characters)),
+		}
+	}
+
+	/// Nearest float.
+	pub fn to_f64(self: &Self) -> f64 {
+		let magnitude: f64 = self.digits
+			.iter()
+			.enumerate()
+			.rev()
+			.skip_while(|(_, digit)| **digit == 0)
+			.take(3)
+			.map(|(index, digit)| {
+				*digit as f64 * 2f64.powi((index as i32 - self.fraction as i32) * DIGIT_BITS as i32)
+			})
+			.sum();
+
+		match self.negative {
+			true => -magnitude,
+			false => magnitude,
+		}
+	}
+
+	pub fn get_fraction(self: &Self) -> usize {
+		self.fraction
+	}
+
+	/// The same number, with `fraction` digits after the point.
+	pub fn with_fraction(self: &Self, fraction: usize) -> Fixed {
+		let mut digits: Vec<u32> = vec![0; fraction + INTEGER_DIGITS];
+		for (index, digit) in self.digits.iter().enumerate() {
+			let target: i64 = index as i64 - self.fraction as i64 + fraction as i64;
+			if target >= 0 && (target as usize) < digits.len() {
+				digits[target as usize] = *digit;
+			}
+		}
+
+		Fixed::normalized(self.negative, digits, fraction)
+	}
+
+	pub fn is_zero(self: &Self) -> bool {
+		self.digits.iter().all(|digit| *digit == 0)
+	}
+
+	pub fn negate(self: &Self) -> Fixed {
+		Fixed::normalized(!self.negative, self.digits.clone(), self.fraction)
+	}
+
+	pub fn add(self: &Self, other: &Fixed) -> Fixed {
+		let fraction: usize = self.fraction.max(other.fraction);
+		let (first, second): (Fixed, Fixed) = (self.with_fraction(fraction), other.with_fraction(fraction));
+
+		if first.negative == second.negative {
+			return Fixed::normalized(first.negative, add_digits(&first.digits, &second.digits), fraction);
+		}
+		match compare_digits(&first.digits, &second.digits) {
+			cmp::Ordering::Less => Fixed::normalized(
+				second.negative,
+				subtract_digits(&second.digits, &first.digits),
+				fraction,
+			),
+			_ => Fixed::normalized(first.negative, subtract_digits(&first.digits, &second.digits), fraction),
+		}
+	}
+
+	pub fn subtract(self: &Self, other: &Fixed) -> Fixed {
+		self.add(&other.negate())
+	}
+
+	/// Product, rounded toward zero. The integer part must fit.
+	pub fn multiply(self: &Self, other: &Fixed) -> Fixed {
+		let fraction: usize = self.fraction.max(other.fraction);
+		let (first, second): (Fixed, Fixed) = (self.with_fraction(fraction), other.with_fraction(fraction));
+		let length: usize = first.digits.len();
+		let mut product: Vec<u64> = vec![0; 2 * length + 1];
+
+		for (index, digit) in first.digits.iter().enumerate() {
+			if *digit == 0 {
+				continue;
+			}
+			let mut carry: u64 = 0;
+			for (other_index, other_digit) in second.digits.iter().enumerate() {
+				let sum: u64 = product[index + other_index] + *digit as u64 * *other_digit as u64 + carry;
+				product[index + other_index] = sum & 0xFFFF_FFFF;
+				carry = sum >> DIGIT_BITS;
+			}
+			product[index + length] += carry;
+		}
+
+		let digits: Vec<u32> = product[fraction..fraction + length]
+			.iter()
+			.map(|digit| *digit as u32)
+			.collect();
+
+		Fixed::normalized(first.negative != second.negative, digits, fraction)
+	}
+
+	/// Product by 2.
+	pub fn double(self: &Self) -> Fixed {
+		self.add(self)
+	}
+
+	/// Product of the magnitude by a small `factor`. The carry out of the integer part is lost.
+	fn multiply_small(self: &Self, factor: u32) -> Fixed {
+		let mut carry: u64 = 0;
+		let digits: Vec<u32> = self.digits
+			.iter()
+			.map(|digit| {
+				let product: u64 = *digit as u64 * factor as u64 + carry;
+				carry = product >> DIGIT_BITS;
+				product as u32
+			})
+			.collect();
+
+		Fixed::normalized(self.negative, digits, self.fraction)
+	}
+
+	/// Quotient of the magnitude by a small `divisor`, rounded toward zero.
+	fn divide_small(self: &Self, divisor: u32) -> Fixed {
+		let mut remainder: u64 = 0;
+		let mut digits: Vec<u32> = self.digits.clone();
+		for digit in digits.iter_mut().rev() {
+			let dividend: u64 = remainder << DIGIT_BITS | *digit as u64;
+			*digit = (dividend / divisor as u64) as u32;
+			remainder = dividend % divisor as u64;
+		}
+
+		Fixed::normalized(self.negative, digits, self.fraction)
+	}
+
+	/// Zero is never negative.
+	fn normalized(negative: bool, digits: Vec<u32>, fraction: usize) -> Fixed {
+		let is_zero: bool = digits.iter().all(|digit| *digit == 0);
+
+		Fixed { negative: negative && !is_zero, digits, fraction }
+	}
+}
+
+/// Sum of two magnitudes of the same length. The carry out of the integer part is lost.
+fn add_digits(first: &[u32], second: &[u32]) -> Vec<u32> {
+	let mut carry: u64 = 0;
+
+	first
+		.iter()
+		.zip(second.iter())
+		.map(|(a, b)| {
+			let sum: u64 = *a as u64 + *b as u64 + carry;
+			carry = sum >> DIGIT_BITS;
+			sum as u32
+		})
+		.collect()
+}
+
+/// Difference of two magnitudes of the same length, `first` ≥ `second`.
+fn subtract_digits(first: &[u32], second: &[u32]) -> Vec<u32> {
+	let mut borrow: i64 = 0;
+
+	first
+		.iter()
+		.zip(second.iter())
+		.map(|(a, b)| {
+			let mut difference: i64 = *a as i64 - *b as i64 - borrow;
+			borrow = 0;
+			if difference < 0 {
+				difference += 1 << DIGIT_BITS;
+				borrow = 1;
+			}
+			difference as u32
+		})
+		.collect()
+}
+
+/// Compare two magnitudes of the same length.
+fn compare_digits(first: &[u32], second: &[u32]) -> cmp::Ordering {
+	first.iter().rev().cmp(second.iter().rev())
+}
+
+
+#[test]
+fn test_fixed_arithmetic() -> () {
+	let fraction: usize = 4;
+	for value in [0.0, 1.5, -0.1, 3.0e-20, -1234.5678] {
+		assert_eq!(Fixed::from_f64(value, fraction).to_f64(), value);
+	}
+
+	let third: Fixed = Fixed::from_f64(1.0 / 3.0, fraction);
+	let two: Fixed = Fixed::from_f64(-2.0, fraction);
+	assert_eq!(third.multiply(&two).to_f64(), -2.0 / 3.0);
+	assert_eq!(third.subtract(&two).to_f64(), 1.0 / 3.0 + 2.0);
+	assert_eq!(two.multiply(&two).to_f64(), 4.0);
+
+	// Far below the precision of a float: 1 + 2⁻¹⁰⁰ - 1.
+	let one: Fixed = Fixed::from_f64(1.0, fraction);
+	let tiny: Fixed = Fixed::from_f64(2f64.powi(-100), fraction);
+	assert_eq!(one.add(&tiny).to_f64(), 1.0);
+	assert_eq!(one.add(&tiny).subtract(&one).to_f64(), 2f64.powi(-100));
+	assert!(one.subtract(&one).is_zero());
+
+	// Decimals, past the precision of a float.
+	let text: &str = "-1.74364388703715870475219150611477";
+	let parsed: Fixed = Fixed::parse(text, fraction).unwrap();
+	assert_eq!(parsed.to_decimal(32), text);
+	assert_eq!(parsed.to_decimal(4), "-1.7436");
+	assert_eq!(Fixed::parse("0.99996", fraction).unwrap().to_decimal(4), "1.0000");
+	assert_eq!(Fixed::parse("-.5", fraction).unwrap().to_f64(), -0.5);
+	assert!(Fixed::parse("1.2.3", fraction).is_err());
+	assert_eq!(Fixed::parse("-2.5e-3", fraction).unwrap().to_f64(), -2.5e-3);
+}
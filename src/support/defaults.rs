@@ -3,6 +3,18 @@
 
 use complex;
 
+use crate::structures::color;
+
+/// Color of bounded points of `Divergent` fractals.
+pub const COLOR_STABLE: color::Rgb = color::Rgb { red: 0, green: 5, blue: 15 };
+/// Color of divergent points of `Divergent` fractals.
+pub const COLOR_DIVERGENT: color::Rgb = color::Rgb { red: 255, green: 250, blue: 240 };
+/// Color of points without any root of `Root` fractals.
+pub const COLOR_NO_ROOT: color::Rgb = color::Rgb { red: 0, green: 0, blue: 0 };
+
+/// Degree of `polynomial2_c`, as z^n + c.
+pub const POLYNOMIAL2_C_DEGREE: u32 = 2;
+
 /// 2 degree polynomial with `c` coefficient
 #[allow(dead_code)]
 pub fn polynomial2_c(z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic {
@@ -12,6 +24,8 @@ pub fn polynomial2_c(z: complex::Algebraic, c: complex::Algebraic) -> complex::A
 /// 3 degree polynomial with `c` coefficient.
 #[allow(dead_code)]
 pub fn polynomial3_c(z: complex::Algebraic, c: complex::Algebraic) -> complex::Algebraic {
-	z * z + c
+	z * z * z + c
 }
 
+/// Function of `Root` fractals, with roots the cube roots of -1.
+pub const ROOT_FORMULA: &str = "z^3 + 1";
@@ -0,0 +1,73 @@
+//! # Complex sequences.
+//! src/support/export.rs
+//! 
+//! Write rendered fractals to image files, using `image`.
+
+use std::{error, fs, path};
+
+use image;
+use complex;
+
+/// Encode RGB `raw_pixels` of `size` [width, height] into an image file at `path`.
+/// 
+/// The file format is deduced from the extension of `path` (`.png`, `.jpg`, ...).
+pub fn save_rgb(
+	path: &path::Path, 
+	raw_pixels: &[u8], 
+	size: [usize; 2],
+) -> Result<(), Box<dyn error::Error>> {
+	let size_expected: usize = size[0] * size[1] * 3;
+
+	if raw_pixels.len() != size_expected {
+		return Result::Err(format!(
+			"(X) support::export::save_rgb() Expected {} * {} * 3 = {} components, got {}.",
+			size[0], size[1], size_expected, raw_pixels.len(),
+		).into());
+	}
+
+	image::save_buffer(
+		path, 
+		raw_pixels, 
+		size[0] as u32, 
+		size[1] as u32, 
+		image::ColorType::Rgb8,
+	)?;
+
+	Result::Ok(())
+}
+
+/// Write the `parameters` used to render the image at `path` in a sidecar file,
+/// as TOML-like `key = value` lines. Values must already be formatted.
+///
+/// Returns the sidecar path: `path` with the `.toml` extension.
+pub fn save_sidecar(
+	path: &path::Path,
+	parameters: &[(&str, String)],
+) -> Result<path::PathBuf, Box<dyn error::Error>> {
+	let sidecar_path: path::PathBuf = path.with_extension("toml");
+	let mut content: String = format!(
+		"# Complex sequences. Parameters of `{}`.\n",
+		path.display(),
+	);
+
+	for (key, value) in parameters {
+		content.push_str(&format!("{} = {}\n", key, value));
+	}
+
+	fs::write(&sidecar_path, content)?;
+
+	Result::Ok(sidecar_path)
+}
+
+/// Output `zoom` to keep the viewport of a `screen_width` wide window in an `export_width` wide image.
+pub fn scaled_zoom(
+	zoom: complex::Real,
+	screen_width: u32,
+	export_width: u32,
+) -> complex::Real {
+	if screen_width == 0 {
+		zoom
+	} else {
+		zoom * export_width as complex::Real / screen_width as complex::Real
+	}
+}
@@ -5,5 +5,6 @@
 
 mod clipboard;
 pub mod defaults;
+pub mod export;
 pub mod initialization;
 pub mod rendering;
@@ -16,12 +16,6 @@ pub enum ColorFormat {
 	RGBA,
 }
 
-/// # Named tuple for `ViewportSettings`.
-pub struct ViewportSettings {
-	pub position: [complex::Real; 2],
-	pub zoom: complex::Real,
-}
-
 /// Register and render a texture:
 /// - from image information: `texture_id`, `data`, `size`,
 /// - to registers `gl_context`, `textures`.